@@ -0,0 +1,307 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// Everything the station list view can do. Keys map onto these in
+// from_normal_key and the command palette lists them, so both always offer
+// the same set of actions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Play,
+    Stop,
+    PreviousStation,
+    MoveUp,
+    MoveDown,
+    Search,
+    LastSearch,
+    ToggleMute,
+    VolumeUp,
+    VolumeDown,
+    SetVolume(u8),
+    FilterByLetter(char),
+    SeekBack,
+    SeekForward,
+    ToggleFavorite,
+    TogglePinned,
+    AddStation,
+    PasteStations,
+    EditStation,
+    DeleteStation,
+    ToggleMark,
+    ClearMarks,
+    ExportPlaylist,
+    CopyFavorites,
+    CopyMpvCommand,
+    OpenExternal,
+    Cast,
+    RefreshMetadata,
+    Undo,
+    Redo,
+    ToggleTopStations,
+    CycleTopStationsPeriod,
+    ToggleHistory,
+    StationDetails,
+    ToggleFailingOnly,
+    VisualizationMenu,
+    BrowseTags,
+    CycleRightPane,
+    FewerStars,
+    MoreStars,
+    ToggleVisFreeze,
+    ToggleFooter,
+    RcastStations,
+    RemoveDuplicates,
+    RewriteUrls,
+    UnhideAll,
+    ShowKeys,
+    CommandPalette,
+    Quit,
+}
+
+impl Action {
+    // Actions offered in the command palette, in their default order
+    pub const PALETTE: &'static [Action] = &[
+        Action::Play,
+        Action::Stop,
+        Action::PreviousStation,
+        Action::Search,
+        Action::LastSearch,
+        Action::ToggleMute,
+        Action::VolumeUp,
+        Action::VolumeDown,
+        Action::ToggleFavorite,
+        Action::TogglePinned,
+        Action::AddStation,
+        Action::PasteStations,
+        Action::EditStation,
+        Action::DeleteStation,
+        Action::ToggleMark,
+        Action::ClearMarks,
+        Action::ExportPlaylist,
+        Action::CopyFavorites,
+        Action::CopyMpvCommand,
+        Action::OpenExternal,
+        Action::Cast,
+        Action::RefreshMetadata,
+        Action::Undo,
+        Action::Redo,
+        Action::ToggleTopStations,
+        Action::CycleTopStationsPeriod,
+        Action::ToggleHistory,
+        Action::StationDetails,
+        Action::ToggleFailingOnly,
+        Action::VisualizationMenu,
+        Action::BrowseTags,
+        Action::CycleRightPane,
+        Action::FewerStars,
+        Action::MoreStars,
+        Action::ToggleVisFreeze,
+        Action::ToggleFooter,
+        Action::RcastStations,
+        Action::RemoveDuplicates,
+        Action::RewriteUrls,
+        Action::UnhideAll,
+        Action::ShowKeys,
+        Action::Quit,
+    ];
+
+    // Map a key pressed in the station list view to its action
+    pub fn from_normal_key(key: KeyEvent) -> Option<Action> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let action = match key.code {
+            // A–Z index: only the stations starting with that letter or digit
+            KeyCode::Char(c) if alt && c.is_alphanumeric() => {
+                Action::FilterByLetter(crate::db::fold_initial(c))
+            }
+            KeyCode::Char('q') => Action::Quit,
+            KeyCode::Tab => Action::RcastStations,
+            KeyCode::Char('p') if ctrl => Action::CommandPalette,
+            KeyCode::Char(':') => Action::CommandPalette,
+            KeyCode::Char('r') if ctrl => Action::Redo,
+            KeyCode::Char('a') => Action::AddStation,
+            KeyCode::Char('p') => Action::PasteStations,
+            KeyCode::Char('e') => Action::EditStation,
+            KeyCode::Char('d') => Action::DeleteStation,
+            KeyCode::Char(' ') => Action::ToggleMark,
+            KeyCode::Char('x') => Action::ExportPlaylist,
+            KeyCode::Char('F') => Action::CopyFavorites,
+            KeyCode::Char('c') => Action::CopyMpvCommand,
+            KeyCode::Char('o') => Action::OpenExternal,
+            KeyCode::Char('C') => Action::Cast,
+            KeyCode::Char('R') => Action::RefreshMetadata,
+            KeyCode::Char('u') => Action::Undo,
+            KeyCode::Esc => Action::ClearMarks,
+            KeyCode::Char('?') => Action::ShowKeys,
+            KeyCode::Char('v') => Action::VisualizationMenu,
+            KeyCode::Char('g') => Action::BrowseTags,
+            KeyCode::Char('/') if alt => Action::LastSearch,
+            KeyCode::Char('/') => Action::Search,
+            KeyCode::Down => Action::MoveDown,
+            KeyCode::Up => Action::MoveUp,
+            KeyCode::Enter => Action::Play,
+            KeyCode::Char('s') => Action::Stop,
+            KeyCode::Char('`') => Action::PreviousStation,
+            KeyCode::Char('m') => Action::ToggleMute,
+            KeyCode::Char('+') | KeyCode::Char('=') => Action::VolumeUp,
+            KeyCode::Char('-') => Action::VolumeDown,
+            // Jump straight to 0%, 10%, ... 90%; Shift+0 is full volume
+            KeyCode::Char(c @ '0'..='9') => Action::SetVolume((c as u8 - b'0') * 10),
+            KeyCode::Char(')') => Action::SetVolume(100),
+            KeyCode::Char(',') => Action::SeekBack,
+            KeyCode::Char('.') => Action::SeekForward,
+            KeyCode::Char('t') => Action::ToggleTopStations,
+            KeyCode::Char('T') => Action::CycleTopStationsPeriod,
+            KeyCode::Char('h') => Action::ToggleHistory,
+            KeyCode::Char('i') => Action::StationDetails,
+            KeyCode::Char('!') => Action::ToggleFailingOnly,
+            KeyCode::Char('f') => Action::ToggleFavorite,
+            KeyCode::Char('P') => Action::TogglePinned,
+            KeyCode::Char('V') => Action::CycleRightPane,
+            KeyCode::Char('[') => Action::FewerStars,
+            KeyCode::Char(']') => Action::MoreStars,
+            KeyCode::Char('Z') => Action::ToggleVisFreeze,
+            KeyCode::Char('H') => Action::ToggleFooter,
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    // Name shown in the command palette
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Play => "Play Station",
+            Action::Stop => "Stop Playback",
+            Action::PreviousStation => "Previous Station",
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::Search => "Search Stations",
+            Action::LastSearch => "Repeat Last Search",
+            Action::ToggleMute => "Mute/Unmute",
+            Action::VolumeUp => "Volume Up",
+            Action::VolumeDown => "Volume Down",
+            Action::SetVolume(_) => "Set Volume",
+            Action::FilterByLetter(_) => "Filter by First Letter",
+            Action::SeekBack => "Seek Back",
+            Action::SeekForward => "Seek Forward",
+            Action::ToggleFavorite => "Toggle Favorite",
+            Action::TogglePinned => "Pin/Unpin to Top",
+            Action::AddStation => "Add Station",
+            Action::PasteStations => "Add Stations From Clipboard",
+            Action::EditStation => "Edit Station",
+            Action::DeleteStation => "Delete Station",
+            Action::ToggleMark => "Mark/Unmark Station",
+            Action::ClearMarks => "Clear Marks and Letter Filter",
+            Action::ExportPlaylist => "Export Playlist",
+            Action::CopyFavorites => "Copy Favorites as M3U",
+            Action::CopyMpvCommand => "Copy mpv Command",
+            Action::OpenExternal => "Open in External App",
+            Action::Cast => "Cast to Device",
+            Action::RefreshMetadata => "Refresh Now Playing",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ToggleTopStations => "Toggle Top Stations",
+            Action::CycleTopStationsPeriod => "Top Stations: All Time/Week/Month",
+            Action::ToggleHistory => "Toggle History",
+            Action::StationDetails => "Station Details",
+            Action::ToggleFailingOnly => "Show Only Failing Stations",
+            Action::VisualizationMenu => "Choose Visualization",
+            Action::BrowseTags => "Browse Tags",
+            Action::CycleRightPane => "Cycle Right Pane: Vis/Stats/Split",
+            Action::FewerStars => "Fewer Stars in the Starfield",
+            Action::MoreStars => "More Stars in the Starfield",
+            Action::ToggleVisFreeze => "Freeze/Resume Visualization",
+            Action::ToggleFooter => "Show/Hide Help Footer",
+            Action::RcastStations => "Browse RCast Stations",
+            Action::RemoveDuplicates => "Remove Duplicate Stations",
+            Action::RewriteUrls => "Rewrite Station URLs",
+            Action::UnhideAll => "Unhide All Directory Stations",
+            Action::ShowKeys => "Show Key Bindings",
+            Action::CommandPalette => "Command Palette",
+            Action::Quit => "Quit",
+        }
+    }
+
+    // Key hint shown next to the label, empty for palette-only actions
+    pub fn key_hint(&self) -> &'static str {
+        match self {
+            Action::Play => "⏎",
+            Action::Stop => "s",
+            Action::PreviousStation => "`",
+            Action::MoveUp => "↑",
+            Action::MoveDown => "↓",
+            Action::Search => "/",
+            Action::LastSearch => "Alt+/",
+            Action::ToggleMute => "m",
+            Action::VolumeUp => "+",
+            Action::VolumeDown => "-",
+            Action::SetVolume(_) => "0-9",
+            Action::FilterByLetter(_) => "Alt+A-Z",
+            Action::SeekBack => ",",
+            Action::SeekForward => ".",
+            Action::ToggleFavorite => "f",
+            Action::TogglePinned => "P",
+            Action::AddStation => "a",
+            Action::PasteStations => "p",
+            Action::EditStation => "e",
+            Action::DeleteStation => "d",
+            Action::ToggleMark => "Space",
+            Action::ClearMarks => "Esc",
+            Action::ExportPlaylist => "x",
+            Action::CopyFavorites => "F",
+            Action::CopyMpvCommand => "c",
+            Action::OpenExternal => "o",
+            Action::Cast => "C",
+            Action::RefreshMetadata => "R",
+            Action::Undo => "u",
+            Action::Redo => "Ctrl+R",
+            Action::ToggleTopStations => "t",
+            Action::CycleTopStationsPeriod => "T",
+            Action::ToggleHistory => "h",
+            Action::StationDetails => "i",
+            Action::ToggleFailingOnly => "!",
+            Action::VisualizationMenu => "v",
+            Action::BrowseTags => "g",
+            Action::CycleRightPane => "V",
+            Action::FewerStars => "[",
+            Action::MoreStars => "]",
+            Action::ToggleVisFreeze => "Z",
+            Action::ToggleFooter => "H",
+            Action::RcastStations => "Tab",
+            Action::RemoveDuplicates => "",
+            Action::RewriteUrls => "",
+            Action::UnhideAll => "",
+            Action::ShowKeys => "?",
+            Action::CommandPalette => ":",
+            Action::Quit => "q",
+        }
+    }
+}
+
+// Score how well `query` fuzzy-matches `candidate`: every query character
+// must appear in order (case-insensitive). Lower is better; tighter and
+// earlier matches win. None means no match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut first = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = candidate[pos..].iter().position(|&c| c == q)?;
+        first.get_or_insert(pos + offset);
+        score += offset;
+        pos += offset + 1;
+    }
+
+    Some(score + first.unwrap_or(0))
+}
+
+// Palette entries matching `query`, best matches first
+pub fn palette_matches(query: &str) -> Vec<Action> {
+    let mut matches: Vec<(usize, Action)> = Action::PALETTE
+        .iter()
+        .filter_map(|action| fuzzy_score(query, action.label()).map(|score| (score, *action)))
+        .collect();
+    // Stable, so equal scores keep the palette's own order
+    matches.sort_by_key(|(score, _)| *score);
+    matches.into_iter().map(|(_, action)| action).collect()
+}
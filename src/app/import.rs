@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// Messages from a background station import to the UI
+pub enum ImportEvent {
+    Progress { done: usize, total: usize },
+    Finished(ImportSummary),
+    Failed(String),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub existing: usize, // Skipped because the URL is already saved
+    pub invalid: usize,  // Lines that weren't stream URLs
+}
+
+impl ImportSummary {
+    pub fn message(&self) -> String {
+        let mut message = format!("Added {} station(s) from the clipboard", self.added);
+        if self.existing > 0 {
+            message.push_str(&format!(", {} already saved", self.existing));
+        }
+        if self.invalid > 0 {
+            message.push_str(&format!(", skipped {} invalid line(s)", self.invalid));
+        }
+        message
+    }
+}
+
+// Import stream URLs on a background thread with its own database
+// connection, naming each from its stream headers. Progress is reported
+// after every URL, since probing a slow server can take seconds.
+pub fn spawn_url_import(
+    urls: Vec<String>,
+    invalid: usize,
+    proxy: Option<String>,
+) -> Receiver<ImportEvent> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let event = match import_urls(&urls, invalid, proxy.as_deref(), &tx) {
+            Ok(summary) => ImportEvent::Finished(summary),
+            Err(e) => ImportEvent::Failed(e.to_string()),
+        };
+        // The UI may have quit in the meantime
+        let _ = tx.send(event);
+    });
+    rx
+}
+
+fn import_urls(
+    urls: &[String],
+    invalid: usize,
+    proxy: Option<&str>,
+    tx: &Sender<ImportEvent>,
+) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+    let conn = super::open_database()?;
+    let mut saved: HashSet<String> = crate::db::saved_url_index(&conn)?.into_keys().collect();
+    let rt = tokio::runtime::Runtime::new()?;
+    let mut summary = ImportSummary {
+        invalid,
+        ..Default::default()
+    };
+
+    for (i, url) in urls.iter().enumerate() {
+        // Matched like the import preview, so its counts hold
+        if !saved.insert(crate::db::normalize_url(url)) {
+            summary.existing += 1;
+        } else {
+            // Fall back to the host name when the stream doesn't say
+            let headers = rt
+                .block_on(crate::stream_info::probe_stream(url, proxy))
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to probe {}: {}", url, e);
+                    Default::default()
+                });
+            let name = headers.name.unwrap_or_else(|| {
+                reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|parsed| parsed.host_str().map(str::to_string))
+                    .unwrap_or_else(|| url.clone())
+            });
+            crate::db::add_station(&conn, &name, url, headers.description.as_deref())?;
+            summary.added += 1;
+        }
+
+        let _ = tx.send(ImportEvent::Progress {
+            done: i + 1,
+            total: urls.len(),
+        });
+    }
+    Ok(summary)
+}
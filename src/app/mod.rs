@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
 use std::io;
@@ -6,18 +7,37 @@ use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use crate::audio::{AudioVisualizer, Player};
-use crate::db::{toggle_favorite, update_station_stats, Station};
+use crate::db::{toggle_favorite, update_station_stats, Station, TopStationsPeriod};
+use crate::rcast::RcastSort;
 use crate::ui;
-use crate::visualizations::VisualizationManager;
-use rusqlite::params;
+use crate::undo::{StationCommand, StationSnapshot, UndoStack};
+use crate::visualizations::{ColorMode, VisualizationManager, VisualizationType};
+
+mod action;
+mod import;
+mod script;
+pub mod search_history;
+mod tags;
+mod text_input;
+pub use action::{palette_matches, Action};
+pub use import::ImportEvent;
+pub use script::{buffer_to_text, parse_key_script, SCRIPT_HEIGHT, SCRIPT_WIDTH};
+pub use search_history::SearchHistory;
+pub use tags::{complete_tag, is_existing_tag, suggest_tags, TAGS_FIELD};
+pub use text_input::{insert_char, remove_char_before};
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use lazy_static::lazy_static;
-use ratatui::{backend::CrosstermBackend, widgets::ListState, Terminal};
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    widgets::ListState,
+    Frame, Terminal,
+};
 use rusqlite::Connection;
 
 // Global application state for UI components to access
@@ -30,8 +50,81 @@ pub struct AppState {
     pub edit_station_name: String,
     pub edit_station_url: String,
     pub edit_station_desc: String,
+    pub edit_station_tags: String,
+    pub edit_station_args: String,
+    pub edit_station_username: String,
+    pub edit_station_password: String,
+    pub edit_station_mirrors: String,
+}
+
+// Number of fields in the add station form (name, url, description, tags)
+const ADD_FIELD_COUNT: usize = 4;
+
+// What probing a URL typed into the Add form found
+type AutofillResult = Result<crate::stream_info::StreamHeaders, String>;
+
+// The URL of a station that answered its check before playing
+type PlayCheck = Result<String, String>;
+
+// A station to play, with the login and stream URLs looked up for it
+pub struct PlayRequest {
+    name: String,
+    url: String,
+    mpv_args: Option<String>,
+    saved_id: Option<i32>,
+    credentials: Option<crate::db::Credentials>,
+    candidates: Vec<String>,
 }
 
+// Number of fields in the edit station form (name, url, description, tags,
+// mpv args, username, password, mirrors)
+const EDIT_FIELD_COUNT: usize = 8;
+
+// How often the UI's cached stats are reloaded from the database
+const STATS_CACHE_REFRESH: Duration = Duration::from_secs(3);
+
+// Settings key: play the next station in the list when a stream ends by itself
+pub const AUTO_ADVANCE_SETTING: &str = "auto_advance";
+
+// Settings key: set once the first-run welcome overlay has been dismissed
+pub const WELCOME_SETTING: &str = "seen_welcome";
+
+// Settings key: what the station view's right pane shows (visualization, stats or split)
+pub const RIGHT_PANE_SETTING: &str = "right_pane";
+
+// Settings key: show the key help footer (on by default; H toggles it)
+pub const FOOTER_SETTING: &str = "show_footer";
+
+// Settings key: seconds a station must play before it counts in the history,
+// stats and scrobbles, so brief previews don't
+pub const MIN_PLAY_SETTING: &str = "min_play_seconds";
+const DEFAULT_MIN_PLAY: Duration = Duration::from_secs(30);
+
+// Settings key: seconds mpv waits on a stream that sends nothing before
+// giving up (0 waits forever)
+pub const NETWORK_TIMEOUT_SETTING: &str = "network_timeout";
+const DEFAULT_NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Shown instead of the player when there's no terminal to draw it in
+pub const NEEDS_TERMINAL: &str =
+    "radio_cli needs an interactive terminal. See --help for options that work without one.";
+
+// Settings key: how many stars the starfield has ([ and ] change it)
+pub const STAR_COUNT_SETTING: &str = "star_count";
+const STAR_COUNT_STEP: usize = 50;
+
+// How often play time is added to the stats of the playing station
+const STATS_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+// Settings key: check a station answers with audio before starting mpv (on by default)
+pub const PREFLIGHT_CHECK_SETTING: &str = "preflight_check";
+
+// Minimum time between RCast directory fetches
+const RCAST_REFRESH_COOLDOWN: Duration = Duration::from_secs(5);
+
+// Lines scrolled per PgUp/PgDn in the RCast Station Info pane
+const INFO_SCROLL_STEP: u16 = 3;
+
 // Add an enum for app modes
 #[derive(PartialEq)]
 pub enum AppMode {
@@ -42,10 +135,18 @@ pub enum AppMode {
     DeletingStation,
     RcastStations,
     Searching,
+    ExportingStations,
+    CommandPalette,
+    TagBrowser,
+    ConfirmingImport,
+    RewritingUrls,
+    ConfirmingRewrite,
+    StationDetail,
+    #[cfg(feature = "cast")]
+    CastPicker,
 }
 
 pub struct App {
-    pub terminal: Terminal<CrosstermBackend<io::Stdout>>,
     pub stations: Vec<Station>,
     pub list_state: ListState,
     pub visualizer: AudioVisualizer,
@@ -55,49 +156,161 @@ pub struct App {
     pub add_station_name: String,
     pub add_station_url: String,
     pub add_station_desc: String,
+    pub add_station_tags: String,
     pub input_cursor: usize,
-    pub input_field: usize, // 0 = name, 1 = url, 2 = description
+    pub input_field: usize, // 0 = name, 1 = url, 2 = description, 3 = tags
+    pub tag_suggestions: Vec<String>, // Existing tags completing the one being typed
+    pub tag_suggestion: usize, // Highlighted entry in tag_suggestions
+    pub tag_counts: Vec<(String, usize)>, // Tags listed in the tag browser, with station counts
+    pub tag_list_state: ListState,
+    pub tag_stations: Vec<Station>, // Stations carrying the highlighted tag
+    pub tag_station_state: ListState,
+    pub tag_stations_focused: bool, // Whether the tag browser has drilled into tag_stations
     pub vis_manager: VisualizationManager,
     pub vis_menu_state: ListState, // State for visualization menu selection
     pub edit_station_id: i32,      // ID of the station being edited
     pub edit_station_name: String,
     pub edit_station_url: String,
     pub edit_station_desc: String,
-    pub confirm_delete: bool, // Whether the user has confirmed deletion
+    pub edit_station_tags: String, // Comma-separated tags for the station being edited
+    pub edit_station_args: String, // Extra mpv arguments for the station being edited
+    pub edit_station_username: String, // Basic auth login for the station being edited
+    pub edit_station_password: String,
+    pub edit_station_mirrors: String, // Fallback stream URLs, separated by spaces
+    pub confirm_delete: bool,         // Whether the user has confirmed deletion
     pub rcast_stations: Vec<crate::rcast::RcastStation>, // List of stations from RCast.net
-    pub rcast_list_state: ListState, // State for RCast stations list
-    pub rcast_loading: bool,  // Whether we're currently loading RCast stations
+    pub rcast_list_state: ListState,  // State for RCast stations list
+    pub rcast_loading: bool,          // Whether we're currently loading RCast stations
+    pub import_events: Option<std::sync::mpsc::Receiver<ImportEvent>>, // Running background import
+    pub import_progress: Option<(usize, usize)>, // Stations done and total for that import
+    pub spinner_tick: u64,            // Draw loop iterations, for the loading spinner
+    pub pending_import: Vec<String>,  // URLs waiting for the import preview to be confirmed
+    pub pending_import_invalid: usize, // Lines skipped from pending_import as not URLs
+    pub import_preview: crate::db::ImportPreview,
+    pub last_rcast_fetch: Option<Instant>, // When the RCast directory was last fetched
+    pub rcast_sort: Option<RcastSort>,     // Directory list order; None keeps the server's order
+    pub rcast_info_scroll: u16,            // Scroll offset of the RCast Station Info pane
+    pub metadata_refresh_pending: bool,    // Re-read now-playing from mpv after the next draw
+    pub db_warning: Option<&'static str>,  // Set once a write fails on a read-only DB or full disk
+    pub color_mode: ColorMode, // Truecolor, or visualization colors mapped to the 16 ANSI ones
+    pub list_style: ui::ListStyle, // Selection marker and style shared by every list
+    pub theme: ui::Theme,      // Recolors the UI to fit the terminal's scheme
     pub stats_last_update: Instant, // Last time stats were updated
+    pub stats_cache: crate::db::StatsSnapshot, // Stats shown by the UI, refreshed off the draw path
+    pub stats_cache_updated: Instant, // When stats_cache was last loaded
+    pub stats_cache_dirty: bool, // Reload stats_cache before the next frame
     pub metadata_last_update: Instant, // Last time metadata was updated
     pub current_station_id: Option<i32>, // Currently playing station ID
+    pub previous_station_id: Option<i32>, // Station played before the current one (for `)
+    pub auto_advance: bool,    // Play the next station when a stream ends by itself
+    pub preflight_check: bool, // Check a station is reachable before starting mpv
     pub show_top_stations: bool, // Whether to show top stations in Stream info
-    pub search_query: String, // Current search query
+    pub top_stations_period: TopStationsPeriod, // Time window the top stations are ranked over
+    pub show_history: bool,    // Whether to show recently played in Stream info
+    pub current_history_id: Option<i64>, // History entry for the current play
+    pub play_started: Instant, // When the current station started playing
+    pub play_counted: bool,    // Whether the current play has passed min_play_time
+    pub min_play_time: Duration, // Play time before a play counts in history and stats
+    pub history_song_recorded: bool, // Whether the current entry has its song yet
+    pub search_query: String,  // Current search query
     pub search_results: Vec<Station>, // Filtered search results
     pub search_list_state: ListState, // State for search results list pane
-    pub show_visualizations: bool, // Whether to show visualizations (false = show stats instead)
+    pub search_favorites_only: bool, // Restrict search results to favorite stations
+    pub search_history: SearchHistory, // Past queries, for Alt+/ and Up/Down on an empty query
+    pub right_pane: ui::RightPane, // Visualization, station stats or both in the right pane
+    pub show_footer: bool,     // Whether the key help footer takes up the bottom of the screen
+    pub failing_only: bool,    // List only stations whose last check failed (! toggles)
+    pub letter_filter: Option<char>, // List only stations starting with this letter (Alt+letter)
+    pub status_message: Option<(String, Instant)>, // Transient message shown in the help bar
+    pub proxy: Option<String>, // HTTP proxy used for streams and directory fetches
+    pub metadata_format: Option<String>, // Pattern for splitting StreamTitle into artist/title
+    pub selected_station_ids: HashSet<i32>, // Stations marked with Space for bulk actions
+    pub export_path: String,   // Filename typed into the playlist export prompt
+    pub rewrite_from: String,  // Text to replace in station URLs
+    pub rewrite_to: String,    // What replaces it
+    pub rewrite_preview: Vec<crate::db::UrlRewrite>, // Stations the rewrite would change
+    pub undo_stack: UndoStack, // Undo/redo history for station add/edit/delete
+    pub show_key_help: bool,   // Whether the full key list overlay is open
+    pub show_welcome: bool,    // Whether the first-run welcome overlay is open
+    pub palette_query: String, // Text typed into the command palette
+    pub palette_matches: Vec<Action>, // Palette actions matching palette_query
+    pub palette_state: ListState, // Highlighted palette entry
+    pub station_detail: Option<ui::StationDetail>, // Station shown on the detail page
+    pub detail_check: Option<std::sync::mpsc::Receiver<Result<(), String>>>, // Its reachability check
+    pub autofill: Option<(String, std::sync::mpsc::Receiver<AutofillResult>)>, // URL the Add form is probing
+    pub pending_play: Option<(PlayRequest, std::sync::mpsc::Receiver<PlayCheck>)>, // Station waiting on its check
+    #[cfg(feature = "scrobble")]
+    pub scrobbler: Option<crate::scrobble::Scrobbler>, // Last.fm scrobbler, if configured
+    #[cfg(feature = "cast")]
+    pub cast: crate::cast::CastSession, // Renderers found on the LAN and the one being cast to
 }
 
 impl App {
     pub fn new(show_visualizations: bool) -> Result<Self, Box<dyn Error>> {
-        // Get the database path
-        let db_path = get_database_path()?;
+        // Set up database
+        let conn = open_database()?;
+        let stations = crate::db::load_stations(&conn)?;
 
-        // Ensure the directory exists
-        if let Some(parent) = db_path.parent() {
-            fs::create_dir_all(parent)?;
+        // --offline already switched the network off for this run
+        if crate::db::get_bool_setting(&conn, crate::offline::OFFLINE_SETTING)? {
+            crate::offline::set_offline(true);
         }
 
-        // Set up database
-        let conn = Connection::open(&db_path)?;
-        crate::db::init_db(&conn)?;
-        let stations = crate::db::load_stations(&conn)?;
+        // Resolve the proxy from the saved setting or the environment
+        let proxy_setting = crate::db::get_setting(&conn, crate::proxy::PROXY_SETTING)?;
+        let proxy = crate::proxy::resolve_proxy(proxy_setting.as_deref());
 
-        // Set up terminal
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let terminal = Terminal::new(backend)?;
+        // User-defined StreamTitle format; auto-detected when unset
+        let metadata_format =
+            crate::db::get_setting(&conn, crate::metadata::METADATA_FORMAT_SETTING)?;
+
+        let auto_advance = crate::db::get_bool_setting(&conn, AUTO_ADVANCE_SETTING)?;
+
+        // --vis shows the visualization for this run; otherwise the saved choice
+        let right_pane = if show_visualizations {
+            ui::RightPane::Visualization
+        } else {
+            crate::db::get_setting(&conn, RIGHT_PANE_SETTING)?
+                .and_then(|value| ui::RightPane::from_setting(&value))
+                .unwrap_or_default()
+        };
+
+        // Greet new users; anyone who has played something already knows their way
+        let show_welcome = !crate::db::get_bool_setting(&conn, WELCOME_SETTING)?
+            && crate::db::get_recent_history(&conn, 1)?.is_empty();
+        let preflight_check = crate::db::get_bool_setting_or(&conn, PREFLIGHT_CHECK_SETTING, true)?;
+        let show_footer = crate::db::get_bool_setting_or(&conn, FOOTER_SETTING, true)?;
+        let list_style = ui::ListStyle::from_settings(
+            crate::db::get_setting(&conn, ui::HIGHLIGHT_SYMBOL_SETTING)?.as_deref(),
+            crate::db::get_setting(&conn, ui::HIGHLIGHT_STYLE_SETTING)?.as_deref(),
+        );
+        let theme = ui::Theme::detect(crate::db::get_setting(&conn, ui::THEME_SETTING)?.as_deref());
+        let min_play_time = crate::db::get_setting(&conn, MIN_PLAY_SETTING)?
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_MIN_PLAY);
+        let network_timeout = crate::db::get_setting(&conn, NETWORK_TIMEOUT_SETTING)?
+            .and_then(|value| value.trim().parse().ok())
+            .map_or(Some(DEFAULT_NETWORK_TIMEOUT), |seconds| {
+                (seconds > 0).then(|| Duration::from_secs(seconds))
+            });
+
+        // Scrobble to Last.fm only when credentials have been configured
+        #[cfg(feature = "scrobble")]
+        let scrobbler = crate::scrobble::LastFmConfig::load(&conn)
+            .filter(|_| !crate::offline::is_offline())
+            .map(|config| {
+                let log_path = get_database_path()
+                    .ok()
+                    .and_then(|path| path.parent().map(|dir| dir.join("scrobble.log")))
+                    .unwrap_or_else(|| PathBuf::from("scrobble.log"));
+                crate::scrobble::Scrobbler::new(
+                    config,
+                    log_path,
+                    metadata_format.clone(),
+                    min_play_time,
+                )
+            });
 
         // Create app state
         let mut list_state = ListState::default();
@@ -107,7 +320,14 @@ impl App {
 
         // Create visualization and player components
         let visualizer = AudioVisualizer::new();
-        let player = Player::new();
+        if let Some(count) = crate::db::get_setting(&conn, STAR_COUNT_SETTING)?
+            .and_then(|value| value.trim().parse().ok())
+        {
+            visualizer.set_star_count(count);
+        }
+        let mut player = Player::new();
+        player.proxy = proxy.clone();
+        player.network_timeout = network_timeout;
         let vis_manager = VisualizationManager::new();
 
         // Create visualization menu state
@@ -115,7 +335,6 @@ impl App {
         vis_menu_state.select(Some(0)); // Select first visualization by default
 
         Ok(App {
-            terminal,
             stations,
             list_state,
             visualizer,
@@ -125,6 +344,14 @@ impl App {
             add_station_name: String::new(),
             add_station_url: String::new(),
             add_station_desc: String::new(),
+            add_station_tags: String::new(),
+            tag_suggestions: Vec::new(),
+            tag_suggestion: 0,
+            tag_counts: Vec::new(),
+            tag_list_state: ListState::default(),
+            tag_stations: Vec::new(),
+            tag_station_state: ListState::default(),
+            tag_stations_focused: false,
             input_cursor: 0,
             input_field: 0,
             vis_manager,
@@ -133,62 +360,463 @@ impl App {
             edit_station_name: String::new(),
             edit_station_url: String::new(),
             edit_station_desc: String::new(),
+            edit_station_tags: String::new(),
+            edit_station_args: String::new(),
+            edit_station_username: String::new(),
+            edit_station_password: String::new(),
+            edit_station_mirrors: String::new(),
             confirm_delete: false,
             rcast_stations: Vec::new(),
             rcast_list_state: ListState::default(),
             rcast_loading: false,
+            import_events: None,
+            import_progress: None,
+            spinner_tick: 0,
+            pending_import: Vec::new(),
+            pending_import_invalid: 0,
+            import_preview: crate::db::ImportPreview::default(),
+            last_rcast_fetch: None,
+            rcast_sort: None,
+            rcast_info_scroll: 0,
+            metadata_refresh_pending: false,
+            db_warning: None,
+            color_mode: theme.color_mode(ColorMode::detect()),
+            list_style,
+            theme,
             stats_last_update: Instant::now(),
+            stats_cache: crate::db::StatsSnapshot::default(),
+            stats_cache_updated: Instant::now(),
+            stats_cache_dirty: true,
             metadata_last_update: Instant::now(),
             current_station_id: None,
+            previous_station_id: None,
+            auto_advance,
+            preflight_check,
             show_top_stations: false,
+            top_stations_period: TopStationsPeriod::default(),
+            show_history: false,
+            current_history_id: None,
+            play_started: Instant::now(),
+            play_counted: false,
+            min_play_time,
+            history_song_recorded: false,
             search_query: String::new(),
             search_results: Vec::new(),
             search_list_state: ListState::default(),
-            show_visualizations,
+            search_favorites_only: false,
+            search_history: SearchHistory::default(),
+            right_pane,
+            show_footer,
+            failing_only: false,
+            letter_filter: None,
+            status_message: None,
+            proxy,
+            metadata_format,
+            selected_station_ids: HashSet::new(),
+            export_path: String::new(),
+            rewrite_from: String::new(),
+            rewrite_to: String::new(),
+            rewrite_preview: Vec::new(),
+            undo_stack: UndoStack::new(),
+            show_key_help: false,
+            show_welcome,
+            palette_query: String::new(),
+            palette_matches: Vec::new(),
+            palette_state: ListState::default(),
+            station_detail: None,
+            detail_check: None,
+            autofill: None,
+            pending_play: None,
+            #[cfg(feature = "scrobble")]
+            scrobbler,
+            #[cfg(feature = "cast")]
+            cast: crate::cast::CastSession::default(),
         })
     }
 
-    // Helper method to update station stats
+    // Reload the station list from the database, keeping the failing-only filter
+    fn reload_stations(&mut self) -> Result<(), Box<dyn Error>> {
+        self.stations = crate::db::load_stations(&self.conn)?;
+        if self.failing_only {
+            self.stations.retain(Station::check_failed);
+        }
+        if let Some(letter) = self.letter_filter {
+            self.stations
+                .retain(|s| crate::db::name_initial(&s.name) == Some(letter));
+        }
+        Ok(())
+    }
+
+    // Show only the stations starting with a letter, or all of them again
+    // with None, keeping the highlighted station when it's still listed
+    fn set_letter_filter(&mut self, letter: Option<char>) -> Result<(), Box<dyn Error>> {
+        let selected_id = self
+            .list_state
+            .selected()
+            .and_then(|i| self.stations.get(i))
+            .map(|s| s.id);
+        let previous = std::mem::replace(&mut self.letter_filter, letter);
+        self.reload_stations()?;
+        if let (Some(letter), true) = (letter, self.stations.is_empty()) {
+            self.letter_filter = previous;
+            self.reload_stations()?;
+            self.set_status(format!("No stations start with {}", letter));
+        }
+        let position = self
+            .stations
+            .iter()
+            .position(|s| Some(s.id) == selected_id)
+            .or((!self.stations.is_empty()).then_some(0));
+        self.list_state.select(position);
+        Ok(())
+    }
+
+    // Store a saved station's check result so the list can flag dead stations
+    fn record_check(&mut self, station_id: i32, result: Result<(), &str>) {
+        // With only failing stations listed, a station that passes drops
+        // out and shifts the rows below it; keep the same station highlighted
+        let selected = self.list_state.selected();
+        let selected_id = selected.and_then(|i| self.stations.get(i)).map(|s| s.id);
+        let recorded = crate::db::record_check(
+            &self.conn,
+            station_id,
+            result,
+            chrono::Utc::now().timestamp(),
+        )
+        .and_then(|()| self.reload_stations());
+        if let Err(e) = recorded {
+            if !self.note_db_write_failure(e.as_ref()) {
+                log::error!("Failed to record station check: {}", e);
+            }
+            return;
+        }
+        let position = self
+            .stations
+            .iter()
+            .position(|s| Some(s.id) == selected_id)
+            .or_else(|| Some(selected?.min(self.stations.len().checked_sub(1)?)));
+        self.list_state.select(position);
+    }
+
+    // List only the stations whose last check failed, to prune them, or all again
+    fn toggle_failing_only(&mut self) -> Result<(), Box<dyn Error>> {
+        let selected_id = self
+            .list_state
+            .selected()
+            .and_then(|i| self.stations.get(i))
+            .map(|s| s.id);
+        self.failing_only = !self.failing_only;
+        self.reload_stations()?;
+        let position = self
+            .stations
+            .iter()
+            .position(|s| Some(s.id) == selected_id)
+            .or((!self.stations.is_empty()).then_some(0));
+        self.list_state.select(position);
+        self.set_status(match (self.failing_only, self.stations.len()) {
+            (true, 0) => "No stations failed their last check".to_string(),
+            (true, count) => format!("Showing {} failing station(s)", count),
+            (false, _) => "Showing all stations".to_string(),
+        });
+        Ok(())
+    }
+
+    // Show a short-lived message in the help bar
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    // Remember a failed database write so the UI can warn that changes
+    // aren't being saved. Returns false for errors that aren't write failures.
+    fn note_db_write_failure(&mut self, err: &(dyn Error + 'static)) -> bool {
+        match crate::db::write_failure_message(err) {
+            Some(message) => {
+                if self.db_warning.is_none() {
+                    log::error!("Database write failed: {}", err);
+                }
+                self.db_warning = Some(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Reload the stats snapshot the UI draws from
+    fn refresh_stats_cache(&mut self) {
+        match crate::db::StatsSnapshot::load(&self.conn) {
+            Ok(snapshot) => self.stats_cache = snapshot,
+            Err(e) => log::error!("Failed to load station stats: {}", e),
+        }
+        self.stats_cache_updated = Instant::now();
+        self.stats_cache_dirty = false;
+    }
+
+    // Record the current play once it has lasted min_play_time, crediting the
+    // time so far, then add play time every STATS_UPDATE_INTERVAL
     fn update_station_stats(&mut self) -> Result<(), Box<dyn Error>> {
-        if let Some(station_id) = self.current_station_id {
-            // Update stats for the current station (add 10 seconds of play time)
-            update_station_stats(&self.conn, station_id, 10)?;
+        let Some(station_id) = self.current_station_id else {
+            return Ok(());
+        };
+
+        let seconds = if !self.play_counted {
+            let listened = self.play_started.elapsed();
+            if listened < self.min_play_time {
+                return Ok(());
+            }
+            // Marked first so a failing write isn't retried every frame
+            self.play_counted = true;
+            self.stats_last_update = Instant::now();
+            let started_at = chrono::Utc::now().timestamp() - listened.as_secs() as i64;
+            self.current_history_id = Some(crate::db::add_history_entry(
+                &self.conn, station_id, started_at, None,
+            )?);
+            listened.as_secs() as i64
+        } else if self.stats_last_update.elapsed() >= STATS_UPDATE_INTERVAL {
+            self.stats_last_update = Instant::now();
+            STATS_UPDATE_INTERVAL.as_secs() as i64
+        } else {
+            return Ok(());
+        };
+
+        self.stats_cache_dirty = true;
+        update_station_stats(&self.conn, station_id, seconds)?;
+        if let Some(entry_id) = self.current_history_id {
+            crate::db::add_history_play_time(&self.conn, entry_id, seconds)?;
         }
-        self.stats_last_update = Instant::now();
+        log::debug!("Added {}s of play time to station {}", seconds, station_id);
         Ok(())
     }
 
-    pub fn run(&mut self) -> Result<(), Box<dyn Error>> {
+    // Helper method to record the current song on the active history entry
+    fn record_history_song(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.history_song_recorded {
+            return Ok(());
+        }
+        let entry_id = match self.current_history_id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        let song = self
+            .visualizer
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.stream_info.as_ref()?.current_song.clone());
+
+        if let Some(song) = song {
+            crate::db::update_history_song(&self.conn, entry_id, &song)?;
+            self.history_song_recorded = true;
+        }
+        Ok(())
+    }
+
+    // Draw one frame of the UI
+    fn draw(&mut self, f: &mut Frame) {
         // Update global app state for UI components
-        {
-            let mut app_state = APP_STATE.lock().unwrap();
+        if let Ok(mut app_state) = APP_STATE.lock() {
             *app_state = Some(AppState {
                 edit_station_name: self.edit_station_name.clone(),
                 edit_station_url: self.edit_station_url.clone(),
                 edit_station_desc: self.edit_station_desc.clone(),
+                edit_station_tags: self.edit_station_tags.clone(),
+                edit_station_args: self.edit_station_args.clone(),
+                edit_station_username: self.edit_station_username.clone(),
+                edit_station_password: self.edit_station_password.clone(),
+                edit_station_mirrors: self.edit_station_mirrors.clone(),
             });
         }
 
+        // The cast popup's device list, while it's open
+        #[cfg(feature = "cast")]
+        let cast_picker = (self.mode == AppMode::CastPicker).then(|| ui::CastPicker {
+            devices: self
+                .cast
+                .devices
+                .iter()
+                .map(|device| match &self.cast.target {
+                    Some(target) if target == device => format!("{} - casting", device.label()),
+                    _ => device.label(),
+                })
+                .collect(),
+            selected: self.cast.selected,
+            searching: self.cast.is_searching(),
+        });
+
+        let mut ctx = ui::UiContext {
+            stations: &self.stations,
+            list_state: &mut self.list_state,
+            visualizer: &self.visualizer,
+            mode: &self.mode,
+            add_station_name: &self.add_station_name,
+            add_station_url: &self.add_station_url,
+            add_station_desc: &self.add_station_desc,
+            add_station_tags: &self.add_station_tags,
+            input_field: self.input_field,
+            input_cursor: self.input_cursor,
+            tag_suggestions: &self.tag_suggestions,
+            tag_suggestion: self.tag_suggestion,
+            tag_counts: &self.tag_counts,
+            tag_list_state: &mut self.tag_list_state,
+            tag_stations: &self.tag_stations,
+            tag_station_state: &mut self.tag_station_state,
+            tag_stations_focused: self.tag_stations_focused,
+            import_preview: self.import_preview,
+            vis_manager: &self.vis_manager,
+            vis_menu_state: &mut self.vis_menu_state,
+            rcast_stations: &self.rcast_stations,
+            rcast_list_state: &mut self.rcast_list_state,
+            rcast_loading: self.rcast_loading,
+            spinner: ui::Spinner::new(self.spinner_tick),
+            import_progress: self.import_progress,
+            autofilling: self.autofill.is_some(),
+            checking_station: self
+                .pending_play
+                .as_ref()
+                .map(|(play, _)| play.name.as_str()),
+            rcast_sort: self.rcast_sort,
+            rcast_info_scroll: &mut self.rcast_info_scroll,
+            db_warning: self.db_warning,
+            color_mode: self.color_mode,
+            list_style: &self.list_style,
+            show_top_stations: self.show_top_stations,
+            top_stations_period: self.top_stations_period,
+            show_history: self.show_history,
+            stats: &self.stats_cache,
+            current_station_id: self.current_station_id,
+            search_query: &self.search_query,
+            search_results: &self.search_results,
+            search_list_state: &mut self.search_list_state,
+            search_favorites_only: self.search_favorites_only,
+            right_pane: self.right_pane,
+            show_footer: self.show_footer,
+            failing_only: self.failing_only,
+            letter_filter: self.letter_filter,
+            status_message: self.status_message.as_ref().map(|(msg, _)| msg.as_str()),
+            metadata_format: self.metadata_format.as_deref(),
+            selected_station_ids: &self.selected_station_ids,
+            export_path: &self.export_path,
+            rewrite_fields: [&self.rewrite_from, &self.rewrite_to],
+            rewrite_preview: &self.rewrite_preview,
+            show_key_help: self.show_key_help,
+            show_welcome: self.show_welcome,
+            palette_query: &self.palette_query,
+            palette_matches: &self.palette_matches,
+            palette_state: &mut self.palette_state,
+            station_detail: self.station_detail.as_ref(),
+            #[cfg(feature = "cast")]
+            cast_picker,
+        };
+        ui::ui(f, &mut ctx);
+        self.theme.apply(f.buffer_mut());
+    }
+
+    // Handle one key press the way the event loop does. Returns true to quit.
+    pub fn dispatch_key(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<bool, Box<dyn Error>> {
+        // Any action may have changed stations or stats
+        self.stats_cache_dirty = true;
+
+        // Any key closes the key list overlay
+        if self.show_key_help {
+            self.show_key_help = false;
+            return Ok(false);
+        }
+
+        // Any key dismisses the welcome overlay, for good
+        if self.show_welcome {
+            self.show_welcome = false;
+            if let Err(e) = crate::db::set_setting(&self.conn, WELCOME_SETTING, "on") {
+                self.note_db_write_failure(e.as_ref());
+            }
+            return Ok(false);
+        }
+
+        // A read-only or full database only stops changes from being saved;
+        // playback and browsing carry on
+        match self.handle_key(key) {
+            Ok(quit) => Ok(quit),
+            Err(e) if self.note_db_write_failure(e.as_ref()) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Feed scripted key presses through the normal handlers, drawing to an
+    // off-screen buffer after each one, and return the final screen
+    pub fn run_script(
+        &mut self,
+        keys: &[crossterm::event::KeyEvent],
+        width: u16,
+        height: u16,
+    ) -> Result<Buffer, Box<dyn Error>> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        // Scripts start from the station list, not the first-run greeting
+        self.show_welcome = false;
+        self.refresh_stats_cache();
+        terminal.draw(|f| self.draw(f))?;
+
+        for key in keys {
+            if self.dispatch_key(*key)? {
+                break;
+            }
+            self.refresh_stats_cache();
+            self.visualizer.update();
+            terminal.draw(|f| self.draw(f))?;
+        }
+
+        self.player.stop();
+        self.undo_stack.clear();
+        Ok(terminal.backend().buffer().clone())
+    }
+
+    pub fn run(&mut self) -> Result<(), Box<dyn Error>> {
+        // Set up terminal. Minimal terminals can refuse either step, so
+        // explain that rather than passing on crossterm's error.
+        enable_raw_mode().map_err(|e| format!("{} ({})", NEEDS_TERMINAL, e))?;
+        let mut stdout = io::stdout();
+        if let Err(e) = execute!(stdout, EnterAlternateScreen, EnableMouseCapture) {
+            let _ = disable_raw_mode();
+            return Err(format!("{} ({})", NEEDS_TERMINAL, e).into());
+        }
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
         // Main event loop
         loop {
-            // Update global app state with latest values
-            {
-                if let Ok(mut app_state) = APP_STATE.lock() {
-                    if let Some(state) = app_state.as_mut() {
-                        state.edit_station_name = self.edit_station_name.clone();
-                        state.edit_station_url = self.edit_station_url.clone();
-                        state.edit_station_desc = self.edit_station_desc.clone();
+            // Count the play once it's long enough, then keep adding play time
+            if self.current_station_id.is_some() {
+                if let Err(e) = self.update_station_stats() {
+                    if !self.note_db_write_failure(e.as_ref()) {
+                        log::error!("Failed to update station stats: {}", e);
                     }
                 }
             }
 
-            // Check if we need to update stats (every 10 seconds)
-            if self.current_station_id.is_some()
-                && self.stats_last_update.elapsed() >= Duration::from_secs(10)
-            {
-                if let Err(e) = self.update_station_stats() {
-                    eprintln!("Failed to update station stats: {}", e);
+            // Let the scrobbler know what's playing
+            #[cfg(feature = "scrobble")]
+            if let Some(scrobbler) = &mut self.scrobbler {
+                let song = self
+                    .visualizer
+                    .state
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.stream_info.as_ref()?.current_song.clone());
+                scrobbler.update(song.as_deref());
+            }
+
+            // Notice mpv finishing a finite stream on its own
+            if let Some(clean_exit) = self.player.take_stream_ended() {
+                self.handle_stream_ended(clean_exit)?;
+            }
+
+            // Backfill the song into the history entry once metadata arrives
+            if let Err(e) = self.record_history_song() {
+                if !self.note_db_write_failure(e.as_ref()) {
+                    log::error!("Failed to update play history: {}", e);
                 }
+                // Don't retry every frame
+                self.history_song_recorded = true;
             }
 
             // We don't need to explicitly update metadata as it's handled by
@@ -198,80 +826,82 @@ impl App {
                 self.metadata_last_update = Instant::now();
             }
 
+            // Pick up progress from a background import
+            self.poll_import()?;
+
+            // Pick up the detail page's reachability check
+            self.poll_detail_check();
+
+            // Pick up the details found for a URL in the Add form
+            self.poll_autofill();
+
+            // Start the station that was being checked, if it answered
+            self.poll_pending_play()?;
+
+            // Pick up the devices found by a cast search, and finished casts
+            #[cfg(feature = "cast")]
+            self.poll_cast();
+
+            // Expire the status message after a few seconds
+            if let Some((_, shown_at)) = &self.status_message {
+                if shown_at.elapsed() >= Duration::from_secs(3) {
+                    self.status_message = None;
+                }
+            }
+
+            // Refresh cached stats every few seconds or after something changed
+            if self.stats_cache_dirty || self.stats_cache_updated.elapsed() >= STATS_CACHE_REFRESH {
+                self.refresh_stats_cache();
+            }
+
             // Draw the UI
-            self.terminal.draw(|f| {
-                ui::ui(
-                    f,
-                    &self.stations,
-                    &mut self.list_state,
-                    &self.visualizer,
-                    &self.mode,
-                    &self.add_station_name,
-                    &self.add_station_url,
-                    &self.add_station_desc,
-                    self.input_field,
-                    self.input_cursor,
-                    &self.vis_manager,
-                    &mut self.vis_menu_state,
-                    &self.rcast_stations,
-                    &mut self.rcast_list_state,
-                    self.rcast_loading,
-                    self.show_top_stations,
-                    &self.conn,
-                    self.current_station_id,
-                    &self.search_query,
-                    &self.search_results,
-                    &mut self.search_list_state,
-                    self.show_visualizations,
-                )
-            })?;
+            terminal.draw(|f| self.draw(f))?;
+            self.spinner_tick += 1;
 
             // Update the visualization
             self.visualizer.update();
 
+            if self.metadata_refresh_pending {
+                self.metadata_refresh_pending = false;
+                match self.player.refresh_metadata(&self.visualizer) {
+                    Ok(()) => self.set_status("Now playing refreshed"),
+                    Err(e) => {
+                        log::warn!("Failed to refresh metadata: {}", e);
+                        self.set_status(format!("Refresh failed: {}", e));
+                    }
+                }
+            }
+
             // Handle input
             if crossterm::event::poll(Duration::from_millis(16))? {
                 if let Event::Key(key) = event::read()? {
-                    match self.mode {
-                        AppMode::Normal => {
-                            if self.handle_normal_mode(key)? {
-                                break; // User requested exit
-                            }
-                        }
-                        AppMode::AddingStation => {
-                            self.handle_adding_mode(key)?;
-                        }
-                        AppMode::EditingStation => {
-                            self.handle_editing_mode(key)?;
-                        }
-                        AppMode::DeletingStation => {
-                            self.handle_deleting_mode(key)?;
-                        }
-                        AppMode::VisualizationMenu => {
-                            self.handle_vis_menu_mode(key)?;
-                        }
-                        AppMode::RcastStations => {
-                            if self.handle_rcast_stations_mode(key)? {
-                                break; // User requested exit
-                            }
-                        }
-                        AppMode::Searching => {
-                            self.handle_search_mode(key)?;
-                        }
+                    if self.dispatch_key(key)? {
+                        break; // User requested exit
                     }
                 }
             }
         }
 
-        // Clean up
-        self.player.stop();
+        // Clean up, leaving the player running if asked to detach
+        if self.player.detach {
+            if let Some(pid) = self.player.release() {
+                match crate::detach::write_pid(pid, self.player.ipc.path()) {
+                    Ok(path) => log::info!("Detached player {} ({})", pid, path.display()),
+                    Err(e) => log::error!("Failed to write player PID file: {}", e),
+                }
+            }
+        } else {
+            self.player.stop();
+        }
+        // Deletions can't be undone any more, so their passwords can go
+        self.undo_stack.clear();
         disable_raw_mode()?;
         execute!(
-            self.terminal.backend_mut(),
+            terminal.backend_mut(),
             LeaveAlternateScreen,
             DisableMouseCapture
         )?;
-        self.terminal.show_cursor()?;
+        terminal.show_cursor()?;
 
         Ok(())
     }
@@ -280,11 +910,20 @@ impl App {
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Result<bool, Box<dyn Error>> {
-        match key.code {
-            KeyCode::Char('q') => {
+        match Action::from_normal_key(key) {
+            Some(action) => self.perform(action),
+            None => Ok(false),
+        }
+    }
+
+    // Carry out an action from the station list view (a key press or the
+    // command palette). Returns true when the user asked to quit.
+    fn perform(&mut self, action: Action) -> Result<bool, Box<dyn Error>> {
+        match action {
+            Action::Quit => {
                 return Ok(true); // Signal to exit the program
             }
-            KeyCode::Tab => {
+            Action::RcastStations => {
                 // Toggle to RcastStations mode
                 self.mode = AppMode::RcastStations;
                 // Initialize rcast stations list if empty
@@ -297,15 +936,17 @@ impl App {
                     self.rcast_list_state.select(Some(0));
                 }
             }
-            KeyCode::Char('a') => {
+            Action::AddStation => {
                 self.mode = AppMode::AddingStation;
                 self.add_station_name.clear();
                 self.add_station_url.clear();
                 self.add_station_desc.clear();
+                self.add_station_tags.clear();
                 self.input_cursor = 0;
                 self.input_field = 0;
             }
-            KeyCode::Char('e') => {
+            Action::PasteStations => self.paste_stations()?,
+            Action::EditStation => {
                 // Edit selected station
                 if let Some(i) = self.list_state.selected() {
                     if i < self.stations.len() {
@@ -315,21 +956,99 @@ impl App {
                         self.edit_station_name = station.name.clone();
                         self.edit_station_url = station.url.clone();
                         self.edit_station_desc = station.description.clone().unwrap_or_default();
+                        self.edit_station_args = station.mpv_args.clone().unwrap_or_default();
+                        self.edit_station_tags =
+                            crate::db::get_station_tags(&self.conn, self.edit_station_id)?
+                                .join(", ");
+                        let credentials =
+                            crate::db::get_station_credentials(&self.conn, self.edit_station_id)?;
+                        self.edit_station_username = credentials
+                            .as_ref()
+                            .map(|c| c.username.clone())
+                            .unwrap_or_default();
+                        self.edit_station_password =
+                            credentials.map(|c| c.password).unwrap_or_default();
+                        self.edit_station_mirrors =
+                            crate::db::get_station_mirrors(&self.conn, self.edit_station_id)?
+                                .join(" ");
                         self.input_cursor = 0;
                         self.input_field = 0;
                     }
                 }
             }
-            KeyCode::Char('d') => {
-                // Delete selected station
-                if let Some(i) = self.list_state.selected() {
+            Action::DeleteStation => {
+                // Delete the marked stations, or the highlighted one if none are marked
+                if !self.selected_station_ids.is_empty() {
+                    self.mode = AppMode::DeletingStation;
+                    self.confirm_delete = false;
+                } else if let Some(i) = self.list_state.selected() {
                     if i < self.stations.len() {
                         self.mode = AppMode::DeletingStation;
                         self.confirm_delete = false;
                     }
                 }
             }
-            KeyCode::Char('v') => {
+            Action::ToggleMark => {
+                // Mark/unmark the highlighted station and move to the next one
+                if let Some(i) = self.list_state.selected() {
+                    if i < self.stations.len() {
+                        let id = self.stations[i].id;
+                        if !self.selected_station_ids.remove(&id) {
+                            self.selected_station_ids.insert(id);
+                        }
+                        if i + 1 < self.stations.len() {
+                            self.list_state.select(Some(i + 1));
+                        }
+                    }
+                }
+            }
+            Action::ExportPlaylist if !self.stations.is_empty() => {
+                // Export the marked stations (or the highlighted one) to a playlist file
+                self.mode = AppMode::ExportingStations;
+                self.export_path = "stations.m3u".to_string();
+                self.input_cursor = self.export_path.chars().count();
+            }
+            Action::Undo => {
+                let result = self.undo_stack.undo(&self.conn);
+                self.finish_undo_redo("Undo", result)?;
+            }
+            Action::Redo => {
+                let result = self.undo_stack.redo(&self.conn);
+                self.finish_undo_redo("Redo", result)?;
+            }
+            Action::ClearMarks => {
+                self.selected_station_ids.clear();
+                if self.letter_filter.is_some() {
+                    self.set_letter_filter(None)?;
+                }
+            }
+            Action::FilterByLetter(letter) => self.set_letter_filter(Some(letter))?,
+            Action::ShowKeys => {
+                self.show_key_help = true;
+            }
+            Action::ToggleFailingOnly => self.toggle_failing_only()?,
+            Action::StationDetails => {
+                let station = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.stations.get(i))
+                    .cloned();
+                if let Some(station) = station {
+                    self.open_station_detail(station)?;
+                }
+            }
+            Action::BrowseTags => {
+                self.tag_counts = crate::db::get_tag_counts(&self.conn)?;
+                if self.tag_counts.is_empty() {
+                    self.set_status("No tags yet - add some in the Add or Edit popup");
+                } else {
+                    self.mode = AppMode::TagBrowser;
+                    self.tag_stations_focused = false;
+                    self.tag_list_state.select(Some(0));
+                    self.load_tag_stations()?;
+                }
+            }
+            Action::VisualizationMenu => {
                 self.mode = AppMode::VisualizationMenu;
 
                 // Select the current visualization in the menu
@@ -344,265 +1063,918 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('/') => {
-                // Enter search mode
-                self.mode = AppMode::Searching;
-                self.search_query.clear();
-                self.search_results.clear();
-                self.search_list_state.select(None);
-            }
-            KeyCode::Down => {
-                if !self.stations.is_empty() {
-                    let i = match self.list_state.selected() {
-                        Some(i) => {
-                            if i >= self.stations.len() - 1 {
-                                0
-                            } else {
-                                i + 1
-                            }
+            Action::Search => self.enter_search_mode(false),
+            Action::LastSearch => self.enter_search_mode(true),
+            Action::MoveDown if !self.stations.is_empty() => {
+                let i = match self.list_state.selected() {
+                    Some(i) => {
+                        if i >= self.stations.len() - 1 {
+                            0
+                        } else {
+                            i + 1
                         }
-                        None => 0,
-                    };
-                    self.list_state.select(Some(i));
-                }
+                    }
+                    None => 0,
+                };
+                self.list_state.select(Some(i));
             }
-            KeyCode::Up => {
-                if !self.stations.is_empty() {
-                    let i = match self.list_state.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                self.stations.len() - 1
-                            } else {
-                                i - 1
-                            }
+            Action::MoveUp if !self.stations.is_empty() => {
+                let i = match self.list_state.selected() {
+                    Some(i) => {
+                        if i == 0 {
+                            self.stations.len() - 1
+                        } else {
+                            i - 1
                         }
-                        None => 0,
-                    };
-                    self.list_state.select(Some(i));
-                }
+                    }
+                    None => 0,
+                };
+                self.list_state.select(Some(i));
             }
-            KeyCode::Enter => {
+            Action::Play => {
                 if let Some(i) = self.list_state.selected() {
                     if i < self.stations.len() {
                         // Clone the values to avoid borrowing issues
                         let name = self.stations[i].name.clone();
                         let url = self.stations[i].url.clone();
-                        let description = self.stations[i].description.clone();
+                        let mpv_args = self.stations[i].mpv_args.clone();
 
-                        self.play_station(&name, &url, description.as_deref())?;
+                        self.play_station(&name, &url, mpv_args.as_deref())?;
                     }
                 }
             }
-            KeyCode::Char('s') => {
+            Action::Stop => {
+                // Stopping also drops a station still being checked, and ends a cast
+                self.pending_play = None;
+                #[cfg(feature = "cast")]
+                self.stop_cast();
                 self.player.stop();
                 self.visualizer.set_playing(false);
-                // Clear current station ID when stopping
-                self.current_station_id = None;
+                // Clear current station ID when stopping, keeping it for `
+                if let Some(id) = self.current_station_id.take() {
+                    self.previous_station_id = Some(id);
+                }
             }
-            KeyCode::Char('m') => {
+            Action::CopyFavorites => {
+                // Share the favorites as an M3U playlist without writing a file
+                let favorites: Vec<Station> = self
+                    .stations
+                    .iter()
+                    .filter(|s| s.favorite)
+                    .cloned()
+                    .collect();
+                if favorites.is_empty() {
+                    self.set_status("No favorites to copy");
+                } else {
+                    match crate::clipboard::copy(&crate::playlist::to_m3u(&favorites)) {
+                        Ok(_) => self.set_status(format!(
+                            "Copied {} favorite(s) as M3U to the clipboard",
+                            favorites.len()
+                        )),
+                        Err(e) => self.set_status(e),
+                    }
+                }
+            }
+            Action::CopyMpvCommand => {
+                // Copy the command line that plays the highlighted station
+                let station = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.stations.get(i));
+                if let Some(station) = station {
+                    let command = self.player.mpv_command_line(
+                        &station.url,
+                        station.mpv_args.as_deref(),
+                        self.visualizer.get_volume(),
+                    );
+                    match crate::clipboard::copy(&command) {
+                        Ok(_) => self.set_status("Copied mpv command to the clipboard"),
+                        Err(e) => self.set_status(e),
+                    }
+                }
+            }
+            Action::OpenExternal => {
+                // Hand the highlighted station to the configured player or cast tool
+                let station = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.stations.get(i))
+                    .map(|s| (s.name.clone(), s.url.clone()));
+                let template =
+                    crate::db::get_setting(&self.conn, crate::external::EXTERNAL_COMMAND_SETTING)?;
+                match (station, template) {
+                    (None, _) => {}
+                    (Some(_), None) => self.set_status(
+                        "No external app set; try radio_cli --set 'external_command=vlc {url}'",
+                    ),
+                    (Some((name, url)), Some(template)) => {
+                        match crate::external::open(&template, &url, &name) {
+                            Ok(program) => {
+                                self.set_status(format!("Opened {} in {}", name, program))
+                            }
+                            Err(e) => self.set_status(e),
+                        }
+                    }
+                }
+            }
+            Action::Cast => {
+                #[cfg(feature = "cast")]
+                if crate::offline::is_offline() {
+                    self.set_status(crate::offline::OFFLINE_MESSAGE);
+                } else {
+                    self.cast.start_discovery();
+                    self.mode = AppMode::CastPicker;
+                }
+                #[cfg(not(feature = "cast"))]
+                self.set_status("Casting needs a build with --features cast");
+            }
+            Action::PreviousStation => {
+                // Flip back to the station played before this one
+                let previous = match self.previous_station_id {
+                    Some(id) => crate::db::get_station_by_id(&self.conn, id)?,
+                    None => None,
+                };
+                match previous {
+                    Some(station) => {
+                        self.play_station(&station.name, &station.url, station.mpv_args.as_deref())?
+                    }
+                    None => self.set_status("No previous station"),
+                }
+            }
+            Action::ToggleMute => {
                 // Toggle mute
                 if let Err(e) = self.player.toggle_mute(&self.visualizer) {
-                    eprintln!("Failed to toggle mute: {}", e);
+                    log::warn!("Failed to toggle mute: {}", e);
                 }
             }
-            KeyCode::Char('+') | KeyCode::Char('=') => {
+            Action::VolumeUp => {
                 // Increase volume
                 if let Err(e) = self.player.volume_up(&self.visualizer) {
-                    eprintln!("Failed to increase volume: {}", e);
+                    log::warn!("Failed to increase volume: {}", e);
                 }
             }
-            KeyCode::Char('-') => {
+            Action::VolumeDown => {
                 // Decrease volume
                 if let Err(e) = self.player.volume_down(&self.visualizer) {
-                    eprintln!("Failed to decrease volume: {}", e);
+                    log::warn!("Failed to decrease volume: {}", e);
                 }
             }
-            KeyCode::Char('t') => {
+            Action::SetVolume(volume) => {
+                if let Err(e) = self.player.set_volume(volume, &self.visualizer) {
+                    log::warn!("Failed to set volume: {}", e);
+                }
+            }
+            Action::SeekBack | Action::SeekForward => {
+                // Seek back/forward 10 seconds on streams that support it
+                let offset = if action == Action::SeekBack {
+                    -10.0
+                } else {
+                    10.0
+                };
+                if let Err(e) = self.player.seek(offset) {
+                    self.set_status(e);
+                }
+            }
+            Action::ToggleTopStations => {
                 // Toggle showing top stations in Stream info
                 self.show_top_stations = !self.show_top_stations;
             }
-            KeyCode::Char('f') => {
+            Action::CycleTopStationsPeriod => self.cycle_top_stations_period(),
+            Action::ToggleHistory => {
+                // Toggle showing recently played stations in Stream info
+                self.show_history = !self.show_history;
+            }
+            Action::ToggleFavorite if !self.selected_station_ids.is_empty() => {
+                // Favorite all marked stations, or unfavorite them if they already all are
+                let marked = self
+                    .stations
+                    .iter()
+                    .filter(|s| self.selected_station_ids.contains(&s.id));
+                let new_favorite = marked.clone().any(|s| !s.favorite);
+                for station in marked {
+                    toggle_favorite(&self.conn, station.id, new_favorite)?;
+                }
+                self.reload_stations()?;
+            }
+            Action::ToggleFavorite => {
                 if let Some(i) = self.list_state.selected() {
                     if i < self.stations.len() {
                         let station = &self.stations[i];
                         let new_favorite = !station.favorite;
                         toggle_favorite(&self.conn, station.id, new_favorite)?;
                         // Update the local stations list
-                        self.stations = crate::db::load_stations(&self.conn)?;
+                        self.reload_stations()?;
                     }
                 }
             }
-            KeyCode::Char('V') => {
-                // Toggle visualization mode
-                self.show_visualizations = !self.show_visualizations;
+            Action::TogglePinned => self.toggle_pinned()?,
+            Action::CycleRightPane => self.cycle_right_pane()?,
+            Action::FewerStars => self.change_star_count(false)?,
+            Action::MoreStars => self.change_star_count(true)?,
+            Action::ToggleVisFreeze => {
+                if self.visualizer.toggle_frozen() {
+                    self.set_status("Visualization frozen (Z to resume)");
+                } else {
+                    self.set_status("Visualization resumed");
+                }
+            }
+            Action::ToggleFooter => self.toggle_footer()?,
+            Action::CommandPalette => {
+                self.mode = AppMode::CommandPalette;
+                self.palette_query.clear();
+                self.update_palette_matches();
+            }
+            Action::RefreshMetadata => {
+                if self.player.current_player.is_none() {
+                    self.set_status("Nothing is playing");
+                } else {
+                    // Done after the next draw so "Refreshing…" gets shown
+                    // while mpv is queried
+                    self.metadata_refresh_pending = true;
+                    self.set_status("Refreshing…");
+                }
+            }
+            Action::UnhideAll => {
+                let count = crate::db::unhide_all_stations(&self.conn)?;
+                self.set_status(format!("Unhid {} directory station(s)", count));
+            }
+            Action::RewriteUrls => {
+                self.mode = AppMode::RewritingUrls;
+                self.rewrite_from.clear();
+                self.rewrite_to.clear();
+                self.input_field = 0;
+                self.input_cursor = 0;
+            }
+            Action::RemoveDuplicates => {
+                let removed = crate::db::remove_duplicate_urls(&self.conn)?;
+                self.reload_stations()?;
+                self.selected_station_ids.clear();
+                if let Some(i) = self.list_state.selected() {
+                    if i >= self.stations.len() {
+                        self.list_state.select(self.stations.len().checked_sub(1));
+                    }
+                }
+                self.set_status(format!("Removed {} duplicate station(s)", removed));
             }
             _ => {}
         }
         Ok(false)
     }
 
-    fn handle_vis_menu_mode(
+    // Handle command palette input events. Returns true when the chosen
+    // action asks to quit.
+    fn handle_palette_mode(
         &mut self,
         key: crossterm::event::KeyEvent,
-    ) -> Result<(), Box<dyn Error>> {
-        let visualizations = self.vis_manager.get_available_visualizations();
-
+    ) -> Result<bool, Box<dyn Error>> {
         match key.code {
             KeyCode::Esc => {
                 self.mode = AppMode::Normal;
             }
             KeyCode::Enter => {
-                if let Some(selected) = self.vis_menu_state.selected() {
-                    // Apply the selected visualization
-                    if selected < visualizations.len() {
-                        let (vis_type, _, _) = visualizations[selected];
-                        self.vis_manager.set_visualization_type(vis_type);
-                    }
-                }
+                let selected = self
+                    .palette_state
+                    .selected()
+                    .and_then(|i| self.palette_matches.get(i).copied());
                 self.mode = AppMode::Normal;
-            }
-            KeyCode::Down => {
-                if !visualizations.is_empty() {
-                    let i = match self.vis_menu_state.selected() {
-                        Some(i) => {
-                            if i >= visualizations.len() - 1 {
-                                0
-                            } else {
-                                i + 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.vis_menu_state.select(Some(i));
+                if let Some(action) = selected {
+                    return self.perform(action);
                 }
             }
-            KeyCode::Up => {
-                if !visualizations.is_empty() {
-                    let i = match self.vis_menu_state.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                visualizations.len() - 1
-                            } else {
-                                i - 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.vis_menu_state.select(Some(i));
-                }
+            KeyCode::Char(c) => {
+                self.palette_query.push(c);
+                self.update_palette_matches();
+            }
+            KeyCode::Backspace => {
+                self.palette_query.pop();
+                self.update_palette_matches();
+            }
+            KeyCode::Down if !self.palette_matches.is_empty() => {
+                let i = match self.palette_state.selected() {
+                    Some(i) if i + 1 < self.palette_matches.len() => i + 1,
+                    _ => 0,
+                };
+                self.palette_state.select(Some(i));
+            }
+            KeyCode::Up if !self.palette_matches.is_empty() => {
+                let i = match self.palette_state.selected() {
+                    Some(0) | None => self.palette_matches.len() - 1,
+                    Some(i) => i - 1,
+                };
+                self.palette_state.select(Some(i));
             }
             _ => {}
         }
+        Ok(false)
+    }
 
-        Ok(())
+    // Dispatch a key press to the current mode. Returns true to quit.
+    fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> Result<bool, Box<dyn Error>> {
+        match self.mode {
+            AppMode::Normal => return self.handle_normal_mode(key),
+            AppMode::AddingStation => self.handle_adding_mode(key)?,
+            AppMode::EditingStation => self.handle_editing_mode(key)?,
+            AppMode::DeletingStation => self.handle_deleting_mode(key)?,
+            AppMode::VisualizationMenu => self.handle_vis_menu_mode(key)?,
+            AppMode::RcastStations => return self.handle_rcast_stations_mode(key),
+            AppMode::Searching => self.handle_search_mode(key)?,
+            AppMode::ExportingStations => self.handle_exporting_mode(key)?,
+            AppMode::CommandPalette => return self.handle_palette_mode(key),
+            AppMode::TagBrowser => self.handle_tag_browser_mode(key)?,
+            AppMode::ConfirmingImport => self.handle_confirming_import_mode(key)?,
+            AppMode::RewritingUrls => self.handle_rewriting_urls_mode(key)?,
+            AppMode::ConfirmingRewrite => self.handle_confirming_rewrite_mode(key)?,
+            AppMode::StationDetail => self.handle_station_detail_mode(key)?,
+            #[cfg(feature = "cast")]
+            AppMode::CastPicker => self.handle_cast_picker_mode(key)?,
+        }
+        self.refresh_tag_suggestions();
+        Ok(false)
     }
 
-    fn handle_adding_mode(
+    // Tag browser: tags on the left, the highlighted tag's stations on the
+    // right. Enter/→ moves into the stations and plays one, Esc/← backs out.
+    fn handle_tag_browser_mode(
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Result<(), Box<dyn Error>> {
         match key.code {
-            KeyCode::Esc => {
-                self.mode = AppMode::Normal;
+            KeyCode::Esc | KeyCode::Left if self.tag_stations_focused => {
+                self.tag_stations_focused = false;
+            }
+            KeyCode::Esc => self.mode = AppMode::Normal,
+            KeyCode::Up | KeyCode::Down => {
+                let down = key.code == KeyCode::Down;
+                if self.tag_stations_focused {
+                    step_selection(&mut self.tag_station_state, self.tag_stations.len(), down);
+                } else {
+                    step_selection(&mut self.tag_list_state, self.tag_counts.len(), down);
+                    self.load_tag_stations()?;
+                }
             }
-            KeyCode::Tab => {
-                // Cycle through fields
-                self.input_field = (self.input_field + 1) % 3;
-                // Adjust cursor position
-                match self.input_field {
-                    0 => self.input_cursor = self.add_station_name.len(),
-                    1 => self.input_cursor = self.add_station_url.len(),
-                    2 => self.input_cursor = self.add_station_desc.len(),
-                    _ => {}
+            KeyCode::Enter | KeyCode::Right
+                if !self.tag_stations_focused && !self.tag_stations.is_empty() =>
+            {
+                self.tag_stations_focused = true;
+                self.tag_station_state.select(Some(0));
+            }
+            KeyCode::Enter if self.tag_stations_focused => {
+                if let Some(station) = self
+                    .tag_station_state
+                    .selected()
+                    .and_then(|i| self.tag_stations.get(i))
+                    .cloned()
+                {
+                    self.play_station(&station.name, &station.url, station.mpv_args.as_deref())?;
                 }
             }
-            KeyCode::Enter => {
-                // Submit form if URL and name are not empty
-                if !self.add_station_name.is_empty() && !self.add_station_url.is_empty() {
-                    let desc = if self.add_station_desc.is_empty() {
-                        None
-                    } else {
-                        Some(self.add_station_desc.as_str())
-                    };
+            _ => {}
+        }
+        Ok(())
+    }
 
-                    crate::db::add_station(
-                        &self.conn,
-                        &self.add_station_name,
-                        &self.add_station_url,
-                        desc,
-                    )?;
+    // Reload the stations shown for the tag highlighted in the tag browser
+    fn load_tag_stations(&mut self) -> Result<(), Box<dyn Error>> {
+        self.tag_stations = match self
+            .tag_list_state
+            .selected()
+            .and_then(|i| self.tag_counts.get(i))
+        {
+            Some((tag, _)) => crate::db::get_stations_with_tag(&self.conn, tag)?,
+            None => Vec::new(),
+        };
+        self.tag_station_state.select(None);
+        Ok(())
+    }
 
-                    // Reload stations and return to normal mode
-                    self.stations = crate::db::load_stations(&self.conn)?;
-                    self.mode = AppMode::Normal;
+    // The Tags field of whichever station form is open
+    fn tags_field_mut(&mut self) -> Option<&mut String> {
+        match self.mode {
+            AppMode::AddingStation => Some(&mut self.add_station_tags),
+            AppMode::EditingStation => Some(&mut self.edit_station_tags),
+            _ => None,
+        }
+    }
+
+    // Offer existing tags while the Tags field is being typed in
+    fn refresh_tag_suggestions(&mut self) {
+        let text = match self.tags_field_mut() {
+            Some(text) => text.clone(),
+            None => String::new(),
+        };
+        if self.input_field != TAGS_FIELD || text.is_empty() {
+            self.tag_suggestions.clear();
+            return;
+        }
+        let all_tags = crate::db::get_all_tags(&self.conn).unwrap_or_default();
+        let suggestions = suggest_tags(&all_tags, &text);
+        if suggestions != self.tag_suggestions {
+            self.tag_suggestions = suggestions;
+            self.tag_suggestion = 0;
+        }
+    }
+
+    fn typed_tag_exists(&mut self) -> bool {
+        let text = self
+            .tags_field_mut()
+            .map(|text| text.clone())
+            .unwrap_or_default();
+        let all_tags = crate::db::get_all_tags(&self.conn).unwrap_or_default();
+        is_existing_tag(&all_tags, &text)
+    }
+
+    // Keys for the tag suggestion dropdown: Up/Down pick, Tab/Enter complete.
+    // Returns false when the form should handle the key instead.
+    fn handle_tag_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        if self.tag_suggestions.is_empty() || self.input_field != TAGS_FIELD {
+            return false;
+        }
+        let count = self.tag_suggestions.len();
+        match key.code {
+            KeyCode::Down => self.tag_suggestion = (self.tag_suggestion + 1) % count,
+            KeyCode::Up => self.tag_suggestion = (self.tag_suggestion + count - 1) % count,
+            // "jazz" is a finished tag even while "Jazz Fusion" is suggested
+            KeyCode::Enter if self.typed_tag_exists() => return false,
+            KeyCode::Tab | KeyCode::Enter => {
+                let tag = self.tag_suggestions[self.tag_suggestion.min(count - 1)].clone();
+                let mut cursor = 0;
+                if let Some(text) = self.tags_field_mut() {
+                    *text = complete_tag(text, &tag);
+                    cursor = text.chars().count();
                 }
+                self.input_cursor = cursor;
             }
-            KeyCode::Char(c) => {
-                // Add character to current field
-                match self.input_field {
-                    0 => {
-                        if self.input_cursor < self.add_station_name.len() {
-                            self.add_station_name.insert(self.input_cursor, c);
-                        } else {
-                            self.add_station_name.push(c);
-                        }
-                        self.input_cursor += 1;
+            _ => return false,
+        }
+        true
+    }
+
+    // mpv exited without being stopped: mark playback as over and, if enabled,
+    // move on to the next station in the list
+    fn handle_stream_ended(&mut self, clean_exit: bool) -> Result<(), Box<dyn Error>> {
+        self.visualizer.set_playing(false);
+        let ended_id = self.current_station_id.take();
+        if ended_id.is_some() {
+            self.previous_station_id = ended_id;
+        }
+
+        if !clean_exit {
+            log::warn!(
+                "mpv exited with an error while playing station {:?}",
+                ended_id
+            );
+            // Never got any audio: the server is down or stalled
+            let message = match self.player.network_timeout {
+                _ if self.player.audio_started() => "Stream stopped unexpectedly".to_string(),
+                Some(timeout) if self.play_started.elapsed() >= timeout => format!(
+                    "Timed out: no data from the stream in {}s",
+                    timeout.as_secs()
+                ),
+                _ => "Could not connect to the stream".to_string(),
+            };
+            if let Some(id) = ended_id.filter(|_| !self.player.audio_started()) {
+                self.record_check(id, Err(&message));
+            }
+            self.set_status(message);
+            return Ok(());
+        }
+        if !self.auto_advance {
+            self.set_status("Stream ended");
+            return Ok(());
+        }
+
+        // Next station after the one that ended, in list order
+        let position = self
+            .stations
+            .iter()
+            .position(|station| Some(station.id) == ended_id);
+        let next = match position {
+            Some(i) => (i + 1) % self.stations.len(),
+            None => return Ok(()),
+        };
+
+        let station = self.stations[next].clone();
+        self.list_state.select(Some(next));
+        self.play_station(&station.name, &station.url, station.mpv_args.as_deref())?;
+        self.set_status(format!("Stream ended, playing {}", station.name));
+        Ok(())
+    }
+
+    // Thin out or fill in the starfield by one step, keeping the choice
+    fn change_star_count(&mut self, more: bool) -> Result<(), Box<dyn Error>> {
+        if self.vis_manager.current_type() != VisualizationType::Starfield {
+            self.set_status("Star count applies to the Starfield visualization (v to pick it)");
+            return Ok(());
+        }
+        let current = self.visualizer.star_count();
+        let wanted = if more {
+            current + STAR_COUNT_STEP
+        } else {
+            current.saturating_sub(STAR_COUNT_STEP)
+        };
+        let count = self.visualizer.set_star_count(wanted);
+        crate::db::set_setting(&self.conn, STAR_COUNT_SETTING, &count.to_string())?;
+        self.set_status(format!("Stars: {}", count));
+        Ok(())
+    }
+
+    // Switch the right pane between visualization, stats and both, and
+    // keep the choice for the next launch
+    fn cycle_right_pane(&mut self) -> Result<(), Box<dyn Error>> {
+        self.right_pane = self.right_pane.next();
+        crate::db::set_setting(&self.conn, RIGHT_PANE_SETTING, self.right_pane.label())?;
+        self.set_status(format!("Right pane: {}", self.right_pane.label()));
+        Ok(())
+    }
+
+    // Pin the highlighted station to the top of the list, or unpin it,
+    // keeping it highlighted as it moves
+    fn toggle_pinned(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(station) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.stations.get(i))
+        else {
+            return Ok(());
+        };
+        let (id, pinned, name) = (station.id, !station.pinned, station.name.clone());
+        crate::db::set_pinned(&self.conn, id, pinned)?;
+        self.reload_stations()?;
+        self.list_state
+            .select(self.stations.iter().position(|s| s.id == id));
+        self.set_status(if pinned {
+            format!("Pinned {} to the top", name)
+        } else {
+            format!("Unpinned {}", name)
+        });
+        Ok(())
+    }
+
+    // Hide or show the key help footer, keeping the choice for the next launch
+    fn toggle_footer(&mut self) -> Result<(), Box<dyn Error>> {
+        self.show_footer = !self.show_footer;
+        crate::db::set_setting(
+            &self.conn,
+            FOOTER_SETTING,
+            if self.show_footer { "on" } else { "off" },
+        )?;
+        Ok(())
+    }
+
+    // Rank the top stations over the next time window, showing the view if hidden
+    fn cycle_top_stations_period(&mut self) {
+        if self.show_top_stations {
+            self.top_stations_period = self.top_stations_period.next();
+        }
+        self.show_top_stations = true;
+    }
+
+    // Re-run the fuzzy match and highlight the best entry
+    fn update_palette_matches(&mut self) {
+        self.palette_matches = palette_matches(&self.palette_query);
+        self.palette_state
+            .select(if self.palette_matches.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+    }
+
+    fn handle_vis_menu_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        let visualizations = self.vis_manager.get_available_visualizations();
+
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.vis_menu_state.selected() {
+                    // Apply the selected visualization
+                    if selected < visualizations.len() {
+                        let (vis_type, _, _) = visualizations[selected];
+                        self.vis_manager.set_visualization_type(vis_type);
                     }
-                    1 => {
-                        if self.input_cursor < self.add_station_url.len() {
-                            self.add_station_url.insert(self.input_cursor, c);
+                }
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Down if !visualizations.is_empty() => {
+                let i = match self.vis_menu_state.selected() {
+                    Some(i) => {
+                        if i >= visualizations.len() - 1 {
+                            0
                         } else {
-                            self.add_station_url.push(c);
+                            i + 1
                         }
-                        self.input_cursor += 1;
                     }
-                    2 => {
-                        if self.input_cursor < self.add_station_desc.len() {
-                            self.add_station_desc.insert(self.input_cursor, c);
+                    None => 0,
+                };
+                self.vis_menu_state.select(Some(i));
+            }
+            KeyCode::Up if !visualizations.is_empty() => {
+                let i = match self.vis_menu_state.selected() {
+                    Some(i) => {
+                        if i == 0 {
+                            visualizations.len() - 1
                         } else {
-                            self.add_station_desc.push(c);
+                            i - 1
                         }
-                        self.input_cursor += 1;
                     }
-                    _ => {}
+                    None => 0,
+                };
+                self.vis_menu_state.select(Some(i));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cast")]
+    fn handle_cast_picker_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        let count = self.cast.devices.len();
+        match key.code {
+            KeyCode::Esc => self.mode = AppMode::Normal,
+            KeyCode::Char('r') => self.cast.start_discovery(),
+            KeyCode::Down if count > 0 => self.cast.selected = (self.cast.selected + 1) % count,
+            KeyCode::Up if count > 0 => {
+                self.cast.selected = (self.cast.selected + count - 1) % count;
+            }
+            KeyCode::Enter => {
+                if let Some(device) = self.cast.selected_device().cloned() {
+                    self.cast_to(device);
+                    self.mode = AppMode::Normal;
                 }
             }
-            KeyCode::Backspace => {
-                // Remove character from current field
-                match self.input_field {
-                    0 => {
-                        if self.input_cursor > 0 {
-                            self.add_station_name.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Report a finished cast device search, cast or stop
+    #[cfg(feature = "cast")]
+    fn poll_cast(&mut self) {
+        match self.cast.poll() {
+            Some(Ok(0)) => self.set_status("No cast devices found"),
+            Some(Ok(count)) => self.set_status(format!("Found {} cast device(s)", count)),
+            Some(Err(e)) => {
+                log::warn!("Cast device search failed: {}", e);
+                self.set_status(format!("Cast device search failed: {}", e));
+            }
+            None => {}
+        }
+
+        while let Some(event) = self.cast.poll_job() {
+            match event {
+                crate::cast::CastEvent::Started {
+                    device,
+                    title,
+                    result,
+                } => {
+                    // Something else may have been played or cast since
+                    let current = self.cast.target.as_ref() == Some(&device);
+                    match result {
+                        Ok(()) if current => {
+                            self.player.stop();
+                            self.visualizer.set_playing(false);
+                            if let Some(id) = self.current_station_id.take() {
+                                self.previous_station_id = Some(id);
+                            }
+                            self.set_status(format!("Casting {} to {}", title, device.name));
                         }
-                    }
-                    1 => {
-                        if self.input_cursor > 0 {
-                            self.add_station_url.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
+                        Ok(()) => {}
+                        Err(e) => {
+                            log::warn!("Cast failed: {}", e);
+                            if current {
+                                self.cast.target = None;
+                            }
+                            self.set_status(format!("Cast failed: {}", e));
                         }
                     }
-                    2 => {
-                        if self.input_cursor > 0 {
-                            self.add_station_desc.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
-                        }
+                }
+                crate::cast::CastEvent::Stopped { device, result } => match result {
+                    Ok(()) => self.set_status(format!("Stopped casting to {}", device.name)),
+                    Err(e) => {
+                        log::warn!("Failed to stop cast: {}", e);
+                        self.set_status(format!("Could not stop the cast: {}", e));
                     }
-                    _ => {}
+                },
+            }
+        }
+    }
+
+    // Send the playing station, or the highlighted one, to a device. It
+    // keeps playing locally until the device has taken over.
+    #[cfg(feature = "cast")]
+    fn cast_to(&mut self, device: crate::cast::CastDevice) {
+        let station = self
+            .current_station_id
+            .and_then(|id| self.stations.iter().find(|s| s.id == id))
+            .or_else(|| {
+                self.list_state
+                    .selected()
+                    .and_then(|i| self.stations.get(i))
+            })
+            .map(|s| (s.name.clone(), s.url.clone()));
+        let Some((name, url)) = station else {
+            self.set_status("No station to cast");
+            return;
+        };
+
+        // Only one device plays at a time
+        if self.cast.target.as_ref() != Some(&device) {
+            self.stop_cast();
+        }
+        self.set_status(format!("Connecting to {}…", device.name));
+        self.cast.request_cast(device.clone(), &url, &name);
+        self.cast.target = Some(device);
+    }
+
+    #[cfg(feature = "cast")]
+    fn stop_cast(&mut self) {
+        if let Some(device) = self.cast.target.take() {
+            self.cast.request_stop(device);
+        }
+    }
+
+    // Load everything known about a station and show it full screen
+    fn open_station_detail(&mut self, station: Station) -> Result<(), Box<dyn Error>> {
+        self.station_detail = Some(ui::StationDetail {
+            tags: crate::db::get_station_tags(&self.conn, station.id)?,
+            stats: crate::db::get_station_stats(&self.conn, station.id)?,
+            play_count: crate::db::count_station_plays(&self.conn, station.id)?,
+            history: crate::db::get_station_history(&self.conn, station.id, 10)?,
+            reachability: ui::Reachability::Checking,
+            station,
+        });
+        self.mode = AppMode::StationDetail;
+        self.check_detail_station()
+    }
+
+    // Check whether the detail page's station answers, in the background
+    // so the page shows up right away
+    fn check_detail_station(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(detail) = &mut self.station_detail else {
+            return Ok(());
+        };
+        let url = detail.station.url.clone();
+        if crate::offline::is_offline() && !crate::offline::is_local_url(&url) {
+            detail.reachability =
+                ui::Reachability::Unknown(crate::offline::OFFLINE_MESSAGE.to_string());
+            return Ok(());
+        }
+        if crate::offline::is_local_url(&url) {
+            let path = url.strip_prefix("file://").unwrap_or(&url);
+            detail.reachability = if std::path::Path::new(path).exists() {
+                ui::Reachability::Reachable
+            } else {
+                ui::Reachability::Unreachable("file not found".to_string())
+            };
+            return Ok(());
+        }
+
+        detail.reachability = ui::Reachability::Checking;
+        let credentials = crate::db::get_station_credentials(&self.conn, detail.station.id)?;
+        let proxy = self.proxy.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Runtime::new()
+                .map_err(|e| e.to_string())
+                .and_then(|rt| {
+                    rt.block_on(crate::stream_info::check_stream(
+                        &url,
+                        proxy.as_deref(),
+                        credentials.as_ref(),
+                    ))
+                });
+            let _ = tx.send(result);
+        });
+        self.detail_check = Some(rx);
+        Ok(())
+    }
+
+    fn poll_detail_check(&mut self) {
+        let Some(result) = self.detail_check.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.detail_check = None;
+        let Some(station_id) = self.station_detail.as_ref().map(|d| d.station.id) else {
+            return;
+        };
+        self.record_check(station_id, result.as_ref().map_err(String::as_str).copied());
+        if let Some(detail) = &mut self.station_detail {
+            detail.reachability = match result {
+                Ok(()) => ui::Reachability::Reachable,
+                Err(e) => ui::Reachability::Unreachable(e),
+            };
+        }
+    }
+
+    fn handle_station_detail_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('i') | KeyCode::Char('q') => {
+                self.mode = AppMode::Normal;
+                self.station_detail = None;
+                self.detail_check = None;
+            }
+            KeyCode::Char('r') if self.detail_check.is_none() => self.check_detail_station()?,
+            KeyCode::Enter => {
+                if let Some(station) = self.station_detail.as_ref().map(|d| d.station.clone()) {
+                    self.play_station(&station.name, &station.url, station.mpv_args.as_deref())?;
                 }
             }
-            KeyCode::Left => {
-                if self.input_cursor > 0 {
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_adding_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.handle_tag_key(key) {
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Tab => {
+                // Leaving the URL field with no name yet: try to fill it in from the stream
+                if self.input_field == 1 && self.add_station_name.is_empty() {
+                    self.autofill_station_from_url();
+                }
+
+                // Cycle through fields
+                self.input_field = (self.input_field + 1) % ADD_FIELD_COUNT;
+                // Adjust cursor position
+                self.input_cursor = self
+                    .add_field_mut()
+                    .map_or(0, |field| field.chars().count());
+            }
+            // Only a URL given: fill in the details and let the user review them
+            KeyCode::Enter
+                if self.add_station_name.is_empty() && !self.add_station_url.is_empty() =>
+            {
+                self.autofill_station_from_url();
+                self.input_field = 0;
+                self.input_cursor = self.add_station_name.chars().count();
+            }
+            // Submit form if URL and name are not empty
+            KeyCode::Enter
+                if !self.add_station_name.is_empty() && !self.add_station_url.is_empty() =>
+            {
+                let desc = if self.add_station_desc.is_empty() {
+                    None
+                } else {
+                    Some(self.add_station_desc.as_str())
+                };
+
+                let id = crate::db::add_station(
+                    &self.conn,
+                    &self.add_station_name,
+                    &self.add_station_url,
+                    desc,
+                )?;
+                let tags = crate::db::parse_tags(&self.add_station_tags);
+                crate::db::set_station_tags(&self.conn, id, &tags)?;
+                self.record_added_station(id)?;
+
+                // Reload stations and return to normal mode
+                self.reload_stations()?;
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Char(c) => {
+                // Add character to current field
+                let cursor = self.input_cursor;
+                if let Some(field) = self.add_field_mut() {
+                    insert_char(field, cursor, c);
+                    self.input_cursor += 1;
+                }
+            }
+            KeyCode::Backspace if self.input_cursor > 0 => {
+                // Remove character from current field
+                let cursor = self.input_cursor;
+                if let Some(field) = self.add_field_mut() {
+                    remove_char_before(field, cursor);
                     self.input_cursor -= 1;
                 }
             }
+            KeyCode::Left if self.input_cursor > 0 => {
+                self.input_cursor -= 1;
+            }
             KeyCode::Right => {
-                let max_cursor = match self.input_field {
-                    0 => self.add_station_name.len(),
-                    1 => self.add_station_url.len(),
-                    2 => self.add_station_desc.len(),
-                    _ => 0,
-                };
+                let max_cursor = self
+                    .add_field_mut()
+                    .map_or(0, |field| field.chars().count());
                 if self.input_cursor < max_cursor {
                     self.input_cursor += 1;
                 }
@@ -612,6 +1984,71 @@ impl App {
         Ok(())
     }
 
+    // The add form field currently focused by input_field
+    fn add_field_mut(&mut self) -> Option<&mut String> {
+        match self.input_field {
+            0 => Some(&mut self.add_station_name),
+            1 => Some(&mut self.add_station_url),
+            2 => Some(&mut self.add_station_desc),
+            TAGS_FIELD => Some(&mut self.add_station_tags),
+            _ => None,
+        }
+    }
+
+    fn handle_exporting_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Enter if !self.export_path.trim().is_empty() => {
+                let stations: Vec<Station> = if self.selected_station_ids.is_empty() {
+                    self.list_state
+                        .selected()
+                        .and_then(|i| self.stations.get(i))
+                        .cloned()
+                        .into_iter()
+                        .collect()
+                } else {
+                    self.stations
+                        .iter()
+                        .filter(|s| self.selected_station_ids.contains(&s.id))
+                        .cloned()
+                        .collect()
+                };
+
+                let path = expand_home(self.export_path.trim());
+                match crate::playlist::write_playlist(&path, &stations) {
+                    Ok(()) => self.set_status(format!(
+                        "Exported {} station(s) to {}",
+                        stations.len(),
+                        path.display()
+                    )),
+                    Err(e) => self.set_status(format!("Export failed: {}", e)),
+                }
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Char(c) => {
+                insert_char(&mut self.export_path, self.input_cursor, c);
+                self.input_cursor += 1;
+            }
+            KeyCode::Backspace if self.input_cursor > 0 => {
+                remove_char_before(&mut self.export_path, self.input_cursor);
+                self.input_cursor -= 1;
+            }
+            KeyCode::Left if self.input_cursor > 0 => {
+                self.input_cursor -= 1;
+            }
+            KeyCode::Right if self.input_cursor < self.export_path.chars().count() => {
+                self.input_cursor += 1;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn handle_deleting_mode(
         &mut self,
         key: crossterm::event::KeyEvent,
@@ -620,17 +2057,46 @@ impl App {
             KeyCode::Esc => {
                 self.mode = AppMode::Normal;
             }
+            KeyCode::Char('y') if !self.selected_station_ids.is_empty() => {
+                let doomed: Vec<Station> = self
+                    .stations
+                    .iter()
+                    .filter(|s| self.selected_station_ids.contains(&s.id))
+                    .cloned()
+                    .collect();
+                let command = StationCommand::delete(&self.conn, doomed)?;
+
+                for station_id in self.selected_station_ids.drain() {
+                    crate::db::delete_station(&self.conn, station_id)?;
+                }
+                self.undo_stack.record(command);
+
+                self.reload_stations()?;
+                self.mode = AppMode::Normal;
+
+                // Keep the highlight within the shortened list
+                match self.list_state.selected() {
+                    _ if self.stations.is_empty() => self.list_state.select(None),
+                    Some(i) if i >= self.stations.len() => {
+                        self.list_state.select(Some(self.stations.len() - 1))
+                    }
+                    _ => {}
+                }
+            }
             KeyCode::Char('y') => {
                 if let Some(i) = self.list_state.selected() {
                     if i < self.stations.len() {
                         // Store the station ID to delete
                         let station_id = self.stations[i].id;
+                        let command =
+                            StationCommand::delete(&self.conn, vec![self.stations[i].clone()])?;
 
                         // Delete the station from the database
                         crate::db::delete_station(&self.conn, station_id)?;
+                        self.undo_stack.record(command);
 
                         // Reload stations and return to normal mode
-                        self.stations = crate::db::load_stations(&self.conn)?;
+                        self.reload_stations()?;
                         self.mode = AppMode::Normal;
 
                         // If the deleted station was the last one, select the previous one
@@ -656,109 +2122,99 @@ impl App {
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Result<(), Box<dyn Error>> {
+        if self.handle_tag_key(key) {
+            return Ok(());
+        }
         match key.code {
             KeyCode::Esc => {
                 self.mode = AppMode::Normal;
             }
             KeyCode::Tab => {
                 // Cycle through fields
-                self.input_field = (self.input_field + 1) % 3;
+                self.input_field = (self.input_field + 1) % EDIT_FIELD_COUNT;
                 // Adjust cursor position
-                match self.input_field {
-                    0 => self.input_cursor = self.edit_station_name.len(),
-                    1 => self.input_cursor = self.edit_station_url.len(),
-                    2 => self.input_cursor = self.edit_station_desc.len(),
-                    _ => {}
-                }
+                self.input_cursor = self
+                    .edit_field_mut()
+                    .map_or(0, |field| field.chars().count());
             }
-            KeyCode::Enter => {
-                // Submit form if URL and name are not empty
-                if !self.edit_station_name.is_empty() && !self.edit_station_url.is_empty() {
-                    let desc = if self.edit_station_desc.is_empty() {
-                        None
-                    } else {
-                        Some(self.edit_station_desc.as_str())
-                    };
+            // Submit form if URL and name are not empty
+            KeyCode::Enter
+                if !self.edit_station_name.is_empty() && !self.edit_station_url.is_empty() =>
+            {
+                let desc = if self.edit_station_desc.is_empty() {
+                    None
+                } else {
+                    Some(self.edit_station_desc.as_str())
+                };
+                let mpv_args = if self.edit_station_args.trim().is_empty() {
+                    None
+                } else {
+                    Some(self.edit_station_args.trim())
+                };
 
-                    crate::db::update_station(
-                        &self.conn,
-                        self.edit_station_id,
-                        &self.edit_station_name,
-                        &self.edit_station_url,
-                        desc,
-                    )?;
+                let before = crate::db::get_station_by_id(&self.conn, self.edit_station_id)?
+                    .map(|station| StationSnapshot::take(&self.conn, station))
+                    .transpose()?;
 
-                    // Reload stations and return to normal mode
-                    self.stations = crate::db::load_stations(&self.conn)?;
-                    self.mode = AppMode::Normal;
+                crate::db::update_station(
+                    &self.conn,
+                    self.edit_station_id,
+                    &self.edit_station_name,
+                    &self.edit_station_url,
+                    desc,
+                    mpv_args,
+                )?;
+                // An empty username clears the login
+                let credentials = crate::db::Credentials {
+                    username: self.edit_station_username.trim().to_string(),
+                    password: self.edit_station_password.clone(),
+                };
+                crate::db::set_station_credentials(
+                    &self.conn,
+                    self.edit_station_id,
+                    Some(&credentials).filter(|c| !c.username.is_empty()),
+                )?;
+                let tags = crate::db::parse_tags(&self.edit_station_tags);
+                crate::db::set_station_tags(&self.conn, self.edit_station_id, &tags)?;
+                let mirrors = crate::db::parse_mirror_urls(&self.edit_station_mirrors);
+                crate::db::set_station_mirrors(&self.conn, self.edit_station_id, &mirrors)?;
+
+                // Reload stations and return to normal mode
+                self.reload_stations()?;
+                let after = crate::db::get_station_by_id(&self.conn, self.edit_station_id)?
+                    .map(|station| StationSnapshot::take(&self.conn, station))
+                    .transpose()?;
+                if let (Some(before), Some(after)) = (before, after) {
+                    self.undo_stack.record(StationCommand::Edit {
+                        before: Box::new(before),
+                        after: Box::new(after),
+                    });
                 }
+                self.mode = AppMode::Normal;
             }
             KeyCode::Char(c) => {
                 // Add character to current field
-                match self.input_field {
-                    0 => {
-                        if self.input_cursor < self.edit_station_name.len() {
-                            self.edit_station_name.insert(self.input_cursor, c);
-                        } else {
-                            self.edit_station_name.push(c);
-                        }
-                        self.input_cursor += 1;
-                    }
-                    1 => {
-                        if self.input_cursor < self.edit_station_url.len() {
-                            self.edit_station_url.insert(self.input_cursor, c);
-                        } else {
-                            self.edit_station_url.push(c);
-                        }
-                        self.input_cursor += 1;
-                    }
-                    2 => {
-                        if self.input_cursor < self.edit_station_desc.len() {
-                            self.edit_station_desc.insert(self.input_cursor, c);
-                        } else {
-                            self.edit_station_desc.push(c);
-                        }
-                        self.input_cursor += 1;
-                    }
-                    _ => {}
+                let cursor = self.input_cursor;
+                if let Some(field) = self.edit_field_mut() {
+                    insert_char(field, cursor, c);
+                    self.input_cursor += 1;
                 }
             }
-            KeyCode::Backspace => {
+            KeyCode::Backspace if self.input_cursor > 0 => {
                 // Remove character from current field
-                match self.input_field {
-                    0 => {
-                        if self.input_cursor > 0 {
-                            self.edit_station_name.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
-                        }
-                    }
-                    1 => {
-                        if self.input_cursor > 0 {
-                            self.edit_station_url.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
-                        }
-                    }
-                    2 => {
-                        if self.input_cursor > 0 {
-                            self.edit_station_desc.remove(self.input_cursor - 1);
-                            self.input_cursor -= 1;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            KeyCode::Left => {
-                if self.input_cursor > 0 {
+                let cursor = self.input_cursor;
+                if let Some(field) = self.edit_field_mut() {
+                    remove_char_before(field, cursor);
                     self.input_cursor -= 1;
                 }
             }
+            KeyCode::Left if self.input_cursor > 0 => {
+                self.input_cursor -= 1;
+            }
             KeyCode::Right => {
-                let max_cursor = match self.input_field {
-                    0 => self.edit_station_name.len(),
-                    1 => self.edit_station_url.len(),
-                    2 => self.edit_station_desc.len(),
-                    _ => 0,
-                };
+                let max_cursor = self
+                    .edit_field_mut()
+                    .map_or(0, |field| field.chars().count());
                 if self.input_cursor < max_cursor {
                     self.input_cursor += 1;
                 }
@@ -768,11 +2224,54 @@ impl App {
         Ok(())
     }
 
+    // The edit form field currently focused by input_field
+    fn edit_field_mut(&mut self) -> Option<&mut String> {
+        match self.input_field {
+            0 => Some(&mut self.edit_station_name),
+            1 => Some(&mut self.edit_station_url),
+            2 => Some(&mut self.edit_station_desc),
+            TAGS_FIELD => Some(&mut self.edit_station_tags),
+            4 => Some(&mut self.edit_station_args),
+            5 => Some(&mut self.edit_station_username),
+            6 => Some(&mut self.edit_station_password),
+            7 => Some(&mut self.edit_station_mirrors),
+            _ => None,
+        }
+    }
+
     fn handle_rcast_stations_mode(
         &mut self,
         key: crossterm::event::KeyEvent,
+    ) -> Result<bool, Box<dyn Error>> {
+        let selected_before = self.selected_rcast_url();
+        let quit = self.handle_rcast_key(key)?;
+
+        // Each station's description starts from the top
+        if self.selected_rcast_url() != selected_before {
+            self.rcast_info_scroll = 0;
+        }
+        Ok(quit)
+    }
+
+    fn selected_rcast_url(&self) -> Option<String> {
+        self.rcast_list_state
+            .selected()
+            .and_then(|i| self.rcast_stations.get(i))
+            .map(|station| station.url.clone())
+    }
+
+    fn handle_rcast_key(
+        &mut self,
+        key: crossterm::event::KeyEvent,
     ) -> Result<bool, Box<dyn Error>> {
         match key.code {
+            KeyCode::PageDown => {
+                // The UI clamps this to the end of the description
+                self.rcast_info_scroll = self.rcast_info_scroll.saturating_add(INFO_SCROLL_STEP);
+            }
+            KeyCode::PageUp => {
+                self.rcast_info_scroll = self.rcast_info_scroll.saturating_sub(INFO_SCROLL_STEP);
+            }
             KeyCode::Esc => {
                 self.mode = AppMode::Normal;
                 // Ensure a station is selected in the normal list
@@ -782,41 +2281,37 @@ impl App {
             }
             KeyCode::Tab => {
                 // Toggle back to normal mode
-                self.mode = AppMode::Normal;
-                // Ensure a station is selected in the normal list
-                if !self.stations.is_empty() && self.list_state.selected().is_none() {
-                    self.list_state.select(Some(0));
-                }
-            }
-            KeyCode::Down => {
-                if !self.rcast_stations.is_empty() {
-                    let i = match self.rcast_list_state.selected() {
-                        Some(i) => {
-                            if i >= self.rcast_stations.len() - 1 {
-                                0
-                            } else {
-                                i + 1
-                            }
-                        }
-                        None => 0,
-                    };
-                    self.rcast_list_state.select(Some(i));
+                self.mode = AppMode::Normal;
+                // Ensure a station is selected in the normal list
+                if !self.stations.is_empty() && self.list_state.selected().is_none() {
+                    self.list_state.select(Some(0));
                 }
             }
-            KeyCode::Up => {
-                if !self.rcast_stations.is_empty() {
-                    let i = match self.rcast_list_state.selected() {
-                        Some(i) => {
-                            if i == 0 {
-                                self.rcast_stations.len() - 1
-                            } else {
-                                i - 1
-                            }
+            KeyCode::Down if !self.rcast_stations.is_empty() => {
+                let i = match self.rcast_list_state.selected() {
+                    Some(i) => {
+                        if i >= self.rcast_stations.len() - 1 {
+                            0
+                        } else {
+                            i + 1
                         }
-                        None => 0,
-                    };
-                    self.rcast_list_state.select(Some(i));
-                }
+                    }
+                    None => 0,
+                };
+                self.rcast_list_state.select(Some(i));
+            }
+            KeyCode::Up if !self.rcast_stations.is_empty() => {
+                let i = match self.rcast_list_state.selected() {
+                    Some(i) => {
+                        if i == 0 {
+                            self.rcast_stations.len() - 1
+                        } else {
+                            i - 1
+                        }
+                    }
+                    None => 0,
+                };
+                self.rcast_list_state.select(Some(i));
             }
             KeyCode::Enter => {
                 if let Some(i) = self.rcast_list_state.selected() {
@@ -824,41 +2319,82 @@ impl App {
                         // Clone the values to avoid borrowing issues
                         let name = self.rcast_stations[i].name.clone();
                         let url = self.rcast_stations[i].url.clone();
-                        let description = self.rcast_stations[i].description.clone();
 
-                        self.play_station(&name, &url, description.as_deref())?;
+                        self.play_station(&name, &url, None)?;
+                    }
+                }
+            }
+            KeyCode::Char('o') => {
+                // Cycle the sort order, keeping the same station highlighted
+                let sort = self.rcast_sort.map_or(RcastSort::Name, RcastSort::next);
+                let selected_url = self
+                    .rcast_list_state
+                    .selected()
+                    .and_then(|i| self.rcast_stations.get(i))
+                    .map(|station| station.url.clone());
+                sort.apply(&mut self.rcast_stations);
+                if let Some(url) = selected_url {
+                    let i = self.rcast_stations.iter().position(|s| s.url == url);
+                    self.rcast_list_state.select(i);
+                }
+                self.rcast_sort = Some(sort);
+            }
+            KeyCode::Char('x') => {
+                // Hide the selected directory station for good
+                let selected = self.rcast_list_state.selected().filter(|&i| {
+                    i < self.rcast_stations.len() && !self.rcast_stations[i].url.is_empty()
+                });
+                if let Some(i) = selected {
+                    let station = self.rcast_stations.remove(i);
+                    crate::db::hide_station(&self.conn, &station.url, &station.name)?;
+                    if i >= self.rcast_stations.len() {
+                        self.rcast_list_state
+                            .select(self.rcast_stations.len().checked_sub(1));
                     }
+                    self.set_status(format!(
+                        "Hid {} (unhide all from the command palette)",
+                        station.name
+                    ));
                 }
             }
             KeyCode::Char('r') => {
-                // Refresh the station list
-                self.refresh_rcast_stations()?;
+                // Refresh the station list, but don't hammer the directory server
+                let since_last = self.last_rcast_fetch.map(|fetched| fetched.elapsed());
+                match since_last {
+                    Some(elapsed) if elapsed < RCAST_REFRESH_COOLDOWN => {
+                        let remaining = (RCAST_REFRESH_COOLDOWN - elapsed).as_secs() + 1;
+                        self.set_status(format!("Please wait… (refresh in {}s)", remaining));
+                    }
+                    _ => self.refresh_rcast_stations()?,
+                }
             }
             KeyCode::Char('m') => {
                 // Toggle mute
                 if let Err(e) = self.player.toggle_mute(&self.visualizer) {
-                    eprintln!("Failed to toggle mute: {}", e);
+                    log::warn!("Failed to toggle mute: {}", e);
                 }
             }
             KeyCode::Char('+') | KeyCode::Char('=') => {
                 // Increase volume
                 if let Err(e) = self.player.volume_up(&self.visualizer) {
-                    eprintln!("Failed to increase volume: {}", e);
+                    log::warn!("Failed to increase volume: {}", e);
                 }
             }
             KeyCode::Char('-') => {
                 // Decrease volume
                 if let Err(e) = self.player.volume_down(&self.visualizer) {
-                    eprintln!("Failed to decrease volume: {}", e);
+                    log::warn!("Failed to decrease volume: {}", e);
                 }
             }
             KeyCode::Char('t') => {
                 // Toggle showing top stations in Stream info
                 self.show_top_stations = !self.show_top_stations;
             }
-            KeyCode::Char('V') => {
-                // Toggle visualization mode
-                self.show_visualizations = !self.show_visualizations;
+            KeyCode::Char('T') => self.cycle_top_stations_period(),
+            KeyCode::Char('V') => self.cycle_right_pane()?,
+            KeyCode::Char('H') => self.toggle_footer()?,
+            KeyCode::Char('?') => {
+                self.show_key_help = true;
             }
             KeyCode::Char('a') => {
                 // Add current station to saved stations
@@ -869,16 +2405,17 @@ impl App {
                         // Check if this URL already exists
                         if self.find_station_id_by_url(&station.url).is_none() {
                             // Only add the station if the URL doesn't exist yet
-                            crate::db::add_station(
+                            let id = crate::db::add_station(
                                 &self.conn,
                                 &station.name,
                                 &station.url,
                                 station.description.as_deref(),
                             )?;
+                            self.record_added_station(id)?;
                         }
 
-                        // Reload stations (this will also remove any duplicates)
-                        self.stations = crate::db::load_stations(&self.conn)?;
+                        // Reload stations
+                        self.reload_stations()?;
                     }
                 }
             }
@@ -886,11 +2423,8 @@ impl App {
                 return Ok(true);
             }
             KeyCode::Char('/') => {
-                // Enter search mode
-                self.mode = AppMode::Searching;
-                self.search_query.clear();
-                self.search_results.clear();
-                self.search_list_state.select(None);
+                // Enter search mode (Alt+/ brings back the last query)
+                self.enter_search_mode(key.modifiers.contains(KeyModifiers::ALT));
             }
             _ => {}
         }
@@ -903,53 +2437,214 @@ impl App {
         &mut self,
         name: &str,
         url: &str,
-        description: Option<&str>,
+        mpv_args: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        // A player left running by --detach would play over this one
+        if let Err(e) = crate::detach::stop_detached() {
+            log::warn!("Failed to stop detached player: {}", e);
+        }
+        // This replaces any station whose check hasn't finished
+        self.pending_play = None;
+
+        // Saved stations may need a login, and may have mirrors to fall back on
+        let saved_id = self.find_station_id_by_url(url);
+        let credentials = match saved_id {
+            Some(id) => crate::db::get_station_credentials(&self.conn, id)?,
+            None => None,
+        };
+        let candidates = match saved_id {
+            Some(id) => crate::db::stream_url_order(
+                url,
+                &crate::db::get_station_mirrors(&self.conn, id)?,
+                crate::db::get_working_url(&self.conn, id)?.as_deref(),
+            ),
+            None => vec![url.to_string()],
+        };
+
+        // Only stations on disk play offline
+        if crate::offline::is_offline() && !crate::offline::is_local_url(url) {
+            self.set_status(format!(
+                "{}: {} needs a connection",
+                crate::offline::OFFLINE_MESSAGE,
+                name
+            ));
+            return Ok(());
+        }
+
+        let play = PlayRequest {
+            name: name.to_string(),
+            url: url.to_string(),
+            mpv_args: mpv_args.map(str::to_string),
+            saved_id,
+            credentials,
+            candidates,
+        };
+
+        // Fail fast on dead stations rather than leaving mpv to hang on them.
+        // With mirrors, the first one that answers plays. Only HTTP(S)
+        // streams can be checked; mpv opens files, rtsp:// and the like itself.
+        // The check can take seconds, so poll_pending_play starts mpv once it's done.
+        if play.candidates.len() > 1
+            || (self.preflight_check && crate::stream_info::is_valid_stream_url(url))
+        {
+            let candidates = play.candidates.clone();
+            let credentials = play.credentials.clone();
+            let proxy = self.proxy.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let result = tokio::runtime::Runtime::new()
+                    .map_err(|e| e.to_string())
+                    .and_then(|rt| {
+                        rt.block_on(crate::stream_info::first_reachable(
+                            &candidates,
+                            proxy.as_deref(),
+                            credentials.as_ref(),
+                        ))
+                    });
+                let _ = tx.send(result);
+            });
+            self.pending_play = Some((play, rx));
+            return Ok(());
+        }
+
+        let stream_url = play.url.clone();
+        self.start_playback(play, stream_url)
+    }
+
+    // Play the checked station from whichever URL answered, or say why not
+    fn poll_pending_play(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(checked) = self
+            .pending_play
+            .as_ref()
+            .and_then(|(_, rx)| rx.try_recv().ok())
+        else {
+            return Ok(());
+        };
+        let Some((play, _)) = self.pending_play.take() else {
+            return Ok(());
+        };
+
+        if let Some(id) = play.saved_id {
+            self.record_check(id, checked.as_ref().map(|_| ()).map_err(String::as_str));
+        }
+        let stream_url = match checked {
+            Ok(reachable) => reachable,
+            Err(e) => {
+                self.set_status(if play.candidates.len() > 1 {
+                    format!("All {} mirrors unreachable: {}", play.candidates.len(), e)
+                } else {
+                    format!("Station unreachable: {}", e)
+                });
+                return Ok(());
+            }
+        };
+
+        // Start with the mirror that worked next time
+        if let Some(id) = play.saved_id.filter(|_| play.candidates.len() > 1) {
+            if play.candidates.first() != Some(&stream_url) {
+                crate::db::set_working_url(&self.conn, id, Some(&stream_url))?;
+            }
+        }
+
+        self.start_playback(play, stream_url)
+    }
+
+    // Start mpv on the station and begin tracking its stats
+    fn start_playback(
+        &mut self,
+        play: PlayRequest,
+        stream_url: String,
     ) -> Result<(), Box<dyn Error>> {
+        // Playing here ends a cast, or both would be heard
+        #[cfg(feature = "cast")]
+        self.stop_cast();
+
         // First play the station
-        self.player
-            .play_station(name.to_string(), url.to_string(), &self.visualizer)?;
+        self.player.play_station(
+            play.name.clone(),
+            stream_url.clone(),
+            play.mpv_args.as_deref(),
+            play.credentials.as_ref(),
+            &self.visualizer,
+        )?;
 
         // Make sure the visualizer is marked as playing
         self.visualizer.set_playing(true);
 
-        // Then handle the station ID for stats tracking
-        // First check if this URL already exists in the database
-        if let Some(id) = self.find_station_id_by_url(url) {
-            // URL already exists, use the existing station ID
-            self.current_station_id = Some(id);
-        } else {
-            // URL doesn't exist, add the new station
-            match crate::db::add_station(&self.conn, name, url, description) {
-                Ok(id) => {
-                    self.current_station_id = Some(id);
-                    // Update the local stations list to include the new station
-                    self.stations = crate::db::load_stations(&self.conn)?;
-                }
-                Err(_) => {
-                    // If adding fails for any reason, set current station ID to None
-                    self.current_station_id = None;
-                }
-            }
+        // Remember what was playing so ` can flip back to it
+        let playing_before = self.current_station_id;
+
+        // Stats and history are tracked for saved stations only. Anything
+        // else is a preview: it plays without touching the station list,
+        // and `a` in the RCast view (or Alt+F in search) saves it.
+        self.current_station_id = play.saved_id;
+        if self.current_station_id.is_none() {
+            self.set_status(format!("Previewing {} (not saved)", play.name));
+        } else if stream_url != play.url {
+            self.set_status(format!("Playing {} from mirror {}", play.name, stream_url));
         }
 
-        // Reset the stats timer if we have a valid station ID
-        if self.current_station_id.is_some() {
-            self.stats_last_update = Instant::now();
+        if playing_before.is_some() && playing_before != self.current_station_id {
+            self.previous_station_id = playing_before;
         }
 
+        // Start timing the play; update_station_stats records it in the
+        // history once it passes min_play_time, and record_history_song
+        // then backfills the song
+        self.current_history_id = None;
+        self.history_song_recorded = false;
+        self.play_counted = false;
+        self.play_started = Instant::now();
+
+        Ok(())
+    }
+
+    // Let undo remove a station that was just saved
+    fn record_added_station(&mut self, id: i32) -> Result<(), Box<dyn Error>> {
+        if let Some(station) = crate::db::get_station_by_id(&self.conn, id)? {
+            let added = StationSnapshot::take(&self.conn, station)?;
+            self.undo_stack.record(StationCommand::Add(Box::new(added)));
+        }
         Ok(())
     }
 
     // Helper method to find a station ID by its URL
     fn find_station_id_by_url(&self, url: &str) -> Option<i32> {
-        if let Ok(mut stmt) = self.conn.prepare("SELECT id FROM stations WHERE url = ?1") {
-            if let Ok(id_result) = stmt.query_map(params![url], |row| row.get::<_, i32>(0)) {
-                if let Some(station_id) = id_result.flatten().next() {
-                    return Some(station_id);
-                }
-            }
+        crate::db::get_station_by_url(&self.conn, url)
+            .ok()
+            .flatten()
+            .map(|station| station.id)
+    }
+
+    // Switch to search mode, optionally restoring the previous query
+    fn enter_search_mode(&mut self, restore_last: bool) {
+        self.mode = AppMode::Searching;
+        self.search_history.reset();
+        if restore_last {
+            self.search_query = self.search_history.latest().unwrap_or_default().to_string();
+            self.update_search_results();
+        } else {
+            self.search_query.clear();
+            self.search_results.clear();
+            self.search_list_state.select(None);
+        }
+    }
+
+    // Leave search mode, remembering the query for next time
+    fn exit_search_mode(&mut self) {
+        self.search_history.record(&self.search_query);
+        self.mode = AppMode::Normal;
+    }
+
+    // Show a query recalled from the search history, or an empty one
+    fn recall_search(&mut self, query: Option<String>) {
+        self.search_query = query.unwrap_or_default();
+        if self.search_query.is_empty() {
+            self.search_results.clear();
+            self.search_list_state.select(None);
+        } else {
+            self.update_search_results();
         }
-        None
     }
 
     // Handle search mode input events
@@ -957,10 +2652,27 @@ impl App {
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Result<(), Box<dyn Error>> {
+        // Up/Down step through past queries while the query is empty or
+        // already recalled; any other key keeps the recalled query
+        let recalling = self.search_query.is_empty() || self.search_history.is_browsing();
+        match key.code {
+            KeyCode::Up if recalling => {
+                let query = self.search_history.older().map(str::to_string);
+                self.recall_search(query);
+                return Ok(());
+            }
+            KeyCode::Down if self.search_history.is_browsing() => {
+                let query = self.search_history.newer().map(str::to_string);
+                self.recall_search(query);
+                return Ok(());
+            }
+            _ => self.search_history.reset(),
+        }
+
         match key.code {
             KeyCode::Esc => {
                 // Exit search mode and return to normal mode
-                self.mode = AppMode::Normal;
+                self.exit_search_mode();
             }
             KeyCode::Enter => {
                 // If we have a selected search result and we hit Enter, play that station
@@ -969,15 +2681,33 @@ impl App {
                         // Clone the values to avoid borrowing issues
                         let name = self.search_results[i].name.clone();
                         let url = self.search_results[i].url.clone();
-                        let description = self.search_results[i].description.clone();
+                        let mpv_args = self.search_results[i].mpv_args.clone();
 
-                        self.play_station(&name, &url, description.as_deref())?;
+                        self.play_station(
+                            &name,
+                            &url,
+                            mpv_args.as_deref(),
+                        )?;
 
                         // Exit search mode
-                        self.mode = AppMode::Normal;
+                        self.exit_search_mode();
                     }
                 }
             }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Clear the whole query and start over
+                self.search_query.clear();
+                self.update_search_results();
+            }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Toggle restricting results to favorites
+                self.search_favorites_only = !self.search_favorites_only;
+                self.update_search_results();
+            }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::ALT) => {
+                // Favorite the selected result (plain f is part of the query)
+                self.toggle_search_result_favorite()?;
+            }
             KeyCode::Char(c) => {
                 // Add character to search query
                 self.search_query.push(c);
@@ -988,9 +2718,9 @@ impl App {
                 self.search_query.pop();
                 self.update_search_results();
             }
-            KeyCode::Down => {
+            KeyCode::Down
                 // Navigate down in search results
-                if !self.search_results.is_empty() {
+                if !self.search_results.is_empty() => {
                     let i = match self.search_list_state.selected() {
                         Some(i) => {
                             if i >= self.search_results.len() - 1 {
@@ -1003,10 +2733,9 @@ impl App {
                     };
                     self.search_list_state.select(Some(i));
                 }
-            }
-            KeyCode::Up => {
+            KeyCode::Up
                 // Navigate up in search results
-                if !self.search_results.is_empty() {
+                if !self.search_results.is_empty() => {
                     let i = match self.search_list_state.selected() {
                         Some(i) => {
                             if i == 0 {
@@ -1019,12 +2748,53 @@ impl App {
                     };
                     self.search_list_state.select(Some(i));
                 }
-            }
             _ => {}
         }
         Ok(())
     }
 
+    // Toggle favorite on the selected search result. Directory results
+    // (id 0) aren't saved yet, so they're added first and then favorited.
+    fn toggle_search_result_favorite(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(i) = self.search_list_state.selected() else {
+            return Ok(());
+        };
+        let Some(station) = self.search_results.get(i).cloned() else {
+            return Ok(());
+        };
+
+        let (id, favorite, added) = if station.id != 0 {
+            (station.id, !station.favorite, false)
+        } else if let Some(id) = self.find_station_id_by_url(&station.url) {
+            (id, true, false)
+        } else {
+            let id = crate::db::add_station(
+                &self.conn,
+                &station.name,
+                &station.url,
+                station.description.as_deref(),
+            )?;
+            (id, true, true)
+        };
+        toggle_favorite(&self.conn, id, favorite)?;
+        // Recorded once favorited, so redo brings the star back too
+        if added {
+            self.record_added_station(id)?;
+        }
+        self.reload_stations()?;
+
+        // Rebuild the results so the star shows, keeping the cursor in place
+        self.update_search_results();
+        if !self.search_results.is_empty() {
+            self.search_list_state
+                .select(Some(i.min(self.search_results.len() - 1)));
+        }
+
+        let verb = if favorite { "Favorited" } else { "Unfavorited" };
+        self.set_status(format!("{} {}", verb, station.name));
+        Ok(())
+    }
+
     // Update search results based on current search query
     fn update_search_results(&mut self) {
         self.search_results.clear();
@@ -1043,8 +2813,10 @@ impl App {
         // Search for stations matching the query in both regular and RCast stations
         // First check in regular stations
         for station in &self.stations {
-            // Only add each URL once
-            if added_urls.contains(&station.url) {
+            // Only add each URL once, and skip non-favorites when restricted
+            if added_urls.contains(&station.url)
+                || (self.search_favorites_only && !station.favorite)
+            {
                 continue;
             }
 
@@ -1061,20 +2833,14 @@ impl App {
 
         // Then check in RCast stations
         for rcast_station in &self.rcast_stations {
-            // Skip if we already have this URL from local stations
-            if added_urls.contains(&rcast_station.url) {
+            // Online stations can't be favorites, and skip URLs we already have locally
+            if self.search_favorites_only || added_urls.contains(&rcast_station.url) {
                 continue;
             }
 
             if rcast_station.name.to_lowercase().contains(&query) {
-                // Convert RCast station to regular station
-                let station = Station {
-                    id: 0, // This will be assigned by the database if needed
-                    name: rcast_station.name.clone(),
-                    url: rcast_station.url.clone(),
-                    favorite: false,
-                    description: rcast_station.description.clone(),
-                };
+                // Convert RCast station to regular station (id 0 until it's saved)
+                let station = crate::rcast::rcast_to_db_station(rcast_station);
 
                 self.search_results.push(station);
                 added_urls.insert(rcast_station.url.clone());
@@ -1082,13 +2848,7 @@ impl App {
                 if desc.to_lowercase().contains(&query) && !added_urls.contains(&rcast_station.url)
                 {
                     // Convert RCast station to regular station
-                    let station = Station {
-                        id: 0,
-                        name: rcast_station.name.clone(),
-                        url: rcast_station.url.clone(),
-                        favorite: false,
-                        description: rcast_station.description.clone(),
-                    };
+                    let station = crate::rcast::rcast_to_db_station(rcast_station);
 
                     self.search_results.push(station);
                     added_urls.insert(rcast_station.url.clone());
@@ -1104,20 +2864,365 @@ impl App {
         }
     }
 
+    // Reload the library after an undo/redo and report what happened
+    fn finish_undo_redo(
+        &mut self,
+        action: &str,
+        result: Result<Option<String>, Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        match result {
+            Ok(Some(description)) => {
+                self.reload_stations()?;
+                self.selected_station_ids.clear();
+                if let Some(i) = self.list_state.selected() {
+                    if i >= self.stations.len() {
+                        self.list_state.select(self.stations.len().checked_sub(1));
+                    }
+                } else if !self.stations.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+                self.set_status(format!("{}: {}", action, description));
+            }
+            Ok(None) => self.set_status(format!("Nothing to {}", action.to_lowercase())),
+            Err(e) => self.set_status(format!("{} failed: {}", action, e)),
+        }
+        Ok(())
+    }
+
+    // Add stations from URLs on the clipboard: a single URL opens the Add
+    // popup to review, several are imported straight away
+    fn paste_stations(&mut self) -> Result<(), Box<dyn Error>> {
+        let text = match crate::clipboard::paste() {
+            Ok(text) => text,
+            Err(e) => {
+                self.set_status(format!("Could not read the clipboard: {}", e));
+                return Ok(());
+            }
+        };
+
+        let (urls, invalid) = crate::stream_info::split_url_list(&text);
+        match urls.as_slice() {
+            [] if invalid.is_empty() => self.set_status("Clipboard is empty"),
+            [] => self.set_status("No stream URLs on the clipboard"),
+            [url] if invalid.is_empty() => {
+                // Matched the same way as a bulk import, ignoring a trailing slash or fragment
+                let saved = crate::db::saved_url_index(&self.conn)?;
+                if let Some(&id) = saved.get(&crate::db::normalize_url(url)) {
+                    if let Some(station) = crate::db::get_station_by_id(&self.conn, id)? {
+                        self.set_status(format!("Already saved as {}", station.name));
+                        return Ok(());
+                    }
+                }
+                self.mode = AppMode::AddingStation;
+                self.add_station_name.clear();
+                self.add_station_url = url.clone();
+                self.add_station_desc.clear();
+                self.add_station_tags.clear();
+                self.autofill_station_from_url();
+                self.input_field = 0;
+                self.input_cursor = self.add_station_name.chars().count();
+            }
+            _ => self.preview_station_urls(urls, invalid.len())?,
+        }
+        Ok(())
+    }
+
+    // Show what a bulk import would add before writing anything
+    fn preview_station_urls(
+        &mut self,
+        urls: Vec<String>,
+        invalid: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        let preview = crate::db::preview_import(&self.conn, urls.iter().map(String::as_str))?;
+        if preview.new == 0 {
+            self.set_status(format!("Nothing to import. {}", preview.summary()));
+            return Ok(());
+        }
+        self.import_preview = preview;
+        self.pending_import = urls;
+        self.pending_import_invalid = invalid;
+        self.mode = AppMode::ConfirmingImport;
+        Ok(())
+    }
+
+    fn handle_confirming_import_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.mode = AppMode::Normal;
+                let urls = std::mem::take(&mut self.pending_import);
+                self.import_station_urls(&urls, self.pending_import_invalid)?;
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+                self.pending_import.clear();
+                self.set_status("Import cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Find/replace prompt for station URLs; Enter previews what would change
+    fn handle_rewriting_urls_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Tab => {
+                self.input_field = (self.input_field + 1) % 2;
+                self.input_cursor = self.rewrite_field_mut().chars().count();
+            }
+            KeyCode::Enter if !self.rewrite_from.is_empty() => {
+                let preview = crate::db::preview_url_rewrite(
+                    &self.conn,
+                    &self.rewrite_from,
+                    &self.rewrite_to,
+                )?;
+                if preview.is_empty() {
+                    self.set_status(format!("No station URLs contain {:?}", self.rewrite_from));
+                    return Ok(());
+                }
+                self.rewrite_preview = preview;
+                self.mode = AppMode::ConfirmingRewrite;
+            }
+            KeyCode::Char(c) => {
+                let cursor = self.input_cursor;
+                insert_char(self.rewrite_field_mut(), cursor, c);
+                self.input_cursor += 1;
+            }
+            KeyCode::Backspace if self.input_cursor > 0 => {
+                let cursor = self.input_cursor;
+                remove_char_before(self.rewrite_field_mut(), cursor);
+                self.input_cursor -= 1;
+            }
+            KeyCode::Left if self.input_cursor > 0 => {
+                self.input_cursor -= 1;
+            }
+            KeyCode::Right if self.input_cursor < self.rewrite_field_mut().chars().count() => {
+                self.input_cursor += 1;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // The rewrite prompt field currently focused by input_field
+    fn rewrite_field_mut(&mut self) -> &mut String {
+        if self.input_field == 0 {
+            &mut self.rewrite_from
+        } else {
+            &mut self.rewrite_to
+        }
+    }
+
+    fn handle_confirming_rewrite_mode(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.mode = AppMode::Normal;
+                self.rewrite_preview.clear();
+                let count =
+                    crate::db::rewrite_urls(&self.conn, &self.rewrite_from, &self.rewrite_to)?;
+                self.reload_stations()?;
+                self.set_status(format!("Rewrote the URLs of {} station(s)", count));
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.mode = AppMode::Normal;
+                self.rewrite_preview.clear();
+                self.set_status("Nothing was changed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Add every URL not saved yet in the background; poll_import reports
+    // progress and reloads the list once it's done
+    fn import_station_urls(
+        &mut self,
+        urls: &[String],
+        invalid: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.import_events.is_some() {
+            self.set_status("An import is already running");
+            return Ok(());
+        }
+        self.import_events = Some(import::spawn_url_import(
+            urls.to_vec(),
+            invalid,
+            self.proxy.clone(),
+        ));
+        self.import_progress = Some((0, urls.len()));
+        Ok(())
+    }
+
+    // Handle whatever a background import has reported since the last frame
+    fn poll_import(&mut self) -> Result<(), Box<dyn Error>> {
+        let events: Vec<ImportEvent> = match &self.import_events {
+            Some(rx) => rx.try_iter().collect(),
+            None => return Ok(()),
+        };
+        for event in events {
+            match event {
+                ImportEvent::Progress { done, total } => {
+                    self.import_progress = Some((done, total));
+                    self.set_status(format!("Imported {}/{}…", done, total));
+                }
+                ImportEvent::Finished(summary) => {
+                    self.import_events = None;
+                    self.import_progress = None;
+                    self.reload_stations()?;
+                    self.stats_cache_dirty = true;
+                    self.set_status(summary.message());
+                }
+                ImportEvent::Failed(e) => {
+                    self.import_events = None;
+                    self.import_progress = None;
+                    self.reload_stations()?;
+                    log::error!("Import failed: {}", e);
+                    self.set_status(format!("Import failed: {}", e));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Probe the URL in the add form and pre-fill empty name/description fields
+    fn autofill_station_from_url(&mut self) {
+        let url = self.add_station_url.trim().to_string();
+        if url.is_empty() {
+            return;
+        }
+
+        // Slow servers take seconds to answer; poll_autofill fills the form in
+        let proxy = self.proxy.clone();
+        let probed = url.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = tokio::runtime::Runtime::new()
+                .map_err(|e| e.to_string())
+                .and_then(|rt| {
+                    rt.block_on(crate::stream_info::probe_stream(&probed, proxy.as_deref()))
+                });
+            let _ = tx.send(result);
+        });
+        self.autofill = Some((url, rx));
+    }
+
+    // Fill in the Add form once its URL has been probed, unless the form
+    // was closed or the URL changed in the meantime
+    fn poll_autofill(&mut self) {
+        let Some(result) = self
+            .autofill
+            .as_ref()
+            .and_then(|(_, rx)| rx.try_recv().ok())
+        else {
+            return;
+        };
+        let Some((url, _)) = self.autofill.take() else {
+            return;
+        };
+        if self.mode != AppMode::AddingStation || self.add_station_url.trim() != url {
+            return;
+        }
+        let name_was_empty = self.add_station_name.is_empty();
+
+        match result {
+            // A web page that links to a stream: use the stream instead
+            Ok(headers) if headers.web_page => {
+                if let Some(name) = headers.name.filter(|_| self.add_station_name.is_empty()) {
+                    self.add_station_name = name;
+                }
+                match headers.page_stream_url {
+                    Some(stream_url) => {
+                        self.add_station_url = stream_url;
+                        self.set_status("That's a web page; using the stream it links to");
+                    }
+                    None => self.set_status(
+                        "That's a web page, not an audio stream. Paste the stream URL instead",
+                    ),
+                }
+            }
+            Ok(headers) => {
+                let mut filled = false;
+                if let Some(name) = headers.name.filter(|_| self.add_station_name.is_empty()) {
+                    self.add_station_name = name;
+                    filled = true;
+                }
+
+                // Fall back to the genre/bitrate when there's no description header
+                let description = headers.description.or_else(|| {
+                    let details: Vec<String> =
+                        headers.genre.into_iter().chain(headers.bitrate).collect();
+                    (!details.is_empty()).then(|| details.join(", "))
+                });
+                if let Some(desc) = description.filter(|_| self.add_station_desc.is_empty()) {
+                    self.add_station_desc = desc;
+                    filled = true;
+                }
+
+                if filled {
+                    self.set_status("Filled in details from the stream");
+                } else {
+                    self.set_status("Stream did not provide a name");
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to probe {}: {}", url, e);
+                self.set_status(format!("Could not read stream details: {}", e));
+            }
+        }
+
+        // The name field got focus while it was empty; move to the end of what arrived
+        if name_was_empty && self.input_field == 0 {
+            self.input_cursor = self.add_station_name.chars().count();
+        }
+    }
+
     // Function to refresh the RCast stations list
     fn refresh_rcast_stations(&mut self) -> Result<(), Box<dyn Error>> {
         // Set the loading flag and clear current stations
         self.rcast_loading = true;
+        self.last_rcast_fetch = Some(Instant::now());
         self.rcast_stations.clear();
+        self.rcast_info_scroll = 0;
+
+        if crate::offline::is_offline() {
+            self.rcast_stations.push(crate::rcast::RcastStation {
+                name: crate::offline::OFFLINE_MESSAGE.to_string(),
+                url: "".to_string(),
+                description: Some("Start without --offline to browse RCast".to_string()),
+                bitrate: None,
+                genre: None,
+                listeners: None,
+            });
+            self.rcast_loading = false;
+            return Ok(());
+        }
 
         // Create a new runtime for async operations
         match tokio::runtime::Runtime::new() {
             Ok(rt) => {
                 // Block on the async fetch operation
-                match rt.block_on(crate::rcast::fetch_stations()) {
+                match rt.block_on(crate::rcast::fetch_stations(self.proxy.as_deref())) {
                     Ok(stations) => {
-                        // Update stations with fetched data
-                        self.rcast_stations = stations;
+                        // Update stations with fetched data, minus any the user hid
+                        let hidden = crate::db::load_hidden_urls(&self.conn)?;
+                        self.rcast_stations = stations
+                            .into_iter()
+                            .filter(|station| !hidden.contains(&station.url))
+                            .collect();
+                        if let Some(sort) = self.rcast_sort {
+                            sort.apply(&mut self.rcast_stations);
+                        }
 
                         // If no stations fetched, add a message station
                         if self.rcast_stations.is_empty() {
@@ -1132,6 +3237,8 @@ impl App {
                         }
                     }
                     Err(e) => {
+                        log::warn!("Failed to fetch RCast stations: {}", e);
+
                         // Add an error message station
                         self.rcast_stations.push(crate::rcast::RcastStation {
                             name: "Error fetching stations".to_string(),
@@ -1170,58 +3277,57 @@ impl App {
     }
 }
 
+// Move a list selection one step, wrapping around at either end
+fn step_selection(state: &mut ListState, len: usize, down: bool) {
+    if len == 0 {
+        return;
+    }
+    let i = match state.selected() {
+        Some(i) if down => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    };
+    state.select(Some(i));
+}
+
+// Function to open (and initialize) the station database
+pub fn open_database() -> Result<Connection, Box<dyn Error>> {
+    // Get the database path
+    let db_path = get_database_path()?;
+
+    // Ensure the directory exists
+    if let Some(parent) = db_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Only a brand-new library is seeded, so check before opening creates it
+    let starter = if db_path.exists() {
+        Vec::new()
+    } else {
+        crate::seed::fetch_configured_starter_pack()
+    };
+
+    let conn = Connection::open(&db_path)?;
+    crate::db::init_db_seeded(&conn, &starter)?;
+    Ok(conn)
+}
+
 // Function to get the database path
 pub fn get_database_path() -> Result<PathBuf, Box<dyn Error>> {
-    // First, check if stations.db exists in the current directory
+    // A stations.db in the current directory is used unless the data
+    // directory was set explicitly
     let local_db = PathBuf::from("stations.db");
-    if local_db.exists() {
+    if local_db.exists() && std::env::var_os(crate::paths::DATA_DIR_ENV).is_none() {
         return Ok(local_db);
     }
 
-    // Next, check if we have an XDG_DATA_HOME environment variable
-    let data_dir = match std::env::var_os("XDG_DATA_HOME") {
-        Some(dir) => {
-            let mut path = PathBuf::from(dir);
-            path.push("radio_cli");
-            path
-        }
-        None => {
-            // If not, use the platform-specific data directory
-            #[cfg(target_os = "macos")]
-            {
-                let mut path = dirs_next::home_dir().ok_or("Could not find home directory")?;
-                path.push("Library");
-                path.push("Application Support");
-                path.push("radio_cli");
-                path
-            }
-            #[cfg(target_os = "linux")]
-            {
-                let mut path = dirs_next::home_dir().ok_or("Could not find home directory")?;
-                path.push(".local");
-                path.push("share");
-                path.push("radio_cli");
-                path
-            }
-            #[cfg(target_os = "windows")]
-            {
-                let mut path = dirs_next::data_dir().ok_or("Could not find data directory")?;
-                path.push("radio_cli");
-                path
-            }
-            #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-            {
-                let mut path = dirs_next::home_dir().ok_or("Could not find home directory")?;
-                path.push(".radio_cli");
-                path
-            }
-        }
-    };
-
-    // Create the directory if it doesn't exist
-    fs::create_dir_all(&data_dir)?;
+    Ok(crate::paths::data_dir()?.join("stations.db"))
+}
 
-    // Return the path to the database file
-    let db_path = data_dir.join("stations.db");
-    Ok(db_path)
+// Expand a leading "~/" in a user-typed path to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs_next::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
 }
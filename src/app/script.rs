@@ -0,0 +1,108 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+
+// Screen size used when running a key script
+pub const SCRIPT_WIDTH: u16 = 120;
+pub const SCRIPT_HEIGHT: u16 = 40;
+
+// Parse a key script: whitespace-separated keys such as `a Tab Enter Ctrl+R`,
+// with "quoted text" typed one character at a time and `#` starting a
+// comment that runs to the end of the line
+pub fn parse_key_script(script: &str) -> Result<Vec<KeyEvent>, String> {
+    let mut keys = Vec::new();
+
+    for line in script.lines() {
+        let mut rest = line.trim_start();
+        while !rest.is_empty() {
+            if rest.starts_with('#') {
+                break;
+            }
+
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted
+                    .find('"')
+                    .ok_or_else(|| format!("Unterminated quote in: {}", line))?;
+                keys.extend(
+                    quoted[..end]
+                        .chars()
+                        .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)),
+                );
+                rest = quoted[end + 1..].trim_start();
+                continue;
+            }
+
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            keys.push(parse_key(&rest[..end])?);
+            rest = rest[end..].trim_start();
+        }
+    }
+
+    Ok(keys)
+}
+
+// Parse one key name, with optional Ctrl+/Alt+/Shift+ prefixes
+fn parse_key(token: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut name = token;
+    loop {
+        if let Some(rest) = name.strip_prefix("Ctrl+") {
+            modifiers |= KeyModifiers::CONTROL;
+            name = rest;
+        } else if let Some(rest) = name.strip_prefix("Alt+") {
+            modifiers |= KeyModifiers::ALT;
+            name = rest;
+        } else if let Some(rest) = name.strip_prefix("Shift+") {
+            modifiers |= KeyModifiers::SHIFT;
+            name = rest;
+        } else {
+            break;
+        }
+    }
+
+    let code = match name {
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Space" => KeyCode::Char(' '),
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Delete" => KeyCode::Delete,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                // Terminals report Ctrl+R as Ctrl with a lowercase 'r'
+                (Some(c), None)
+                    if modifiers.contains(KeyModifiers::CONTROL)
+                        && !modifiers.contains(KeyModifiers::SHIFT) =>
+                {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                }
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(format!("Unknown key: {}", token)),
+            }
+        }
+    };
+
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+// Plain-text copy of a rendered screen, one line per row
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let row: String = (area.left()..area.right())
+            .map(|x| buffer.get(x, y).symbol.as_str())
+            .collect();
+        text.push_str(row.trim_end());
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,56 @@
+// How many past search queries are remembered
+pub const SEARCH_HISTORY_LIMIT: usize = 20;
+
+// Recent search queries, newest first. Like a shell history, Up on an empty
+// query steps back through them and Down steps forward again.
+#[derive(Debug, Default)]
+pub struct SearchHistory {
+    entries: Vec<String>,
+    cursor: Option<usize>, // Entry being shown while stepping through
+}
+
+impl SearchHistory {
+    // Remember a query, moving a repeated one to the front
+    pub fn record(&mut self, query: &str) {
+        self.cursor = None;
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.insert(0, query.to_string());
+        self.entries.truncate(SEARCH_HISTORY_LIMIT);
+    }
+
+    pub fn latest(&self) -> Option<&str> {
+        self.entries.first().map(String::as_str)
+    }
+
+    // Whether Up/Down are currently stepping through the history
+    pub fn is_browsing(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    // Stop stepping, e.g. once the recalled query is edited
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+
+    // Step to the next older query; stays on the oldest one
+    pub fn older(&mut self) -> Option<&str> {
+        let next = self.cursor.map_or(0, |i| i + 1);
+        if next < self.entries.len() {
+            self.cursor = Some(next);
+        }
+        self.cursor.map(|i| self.entries[i].as_str())
+    }
+
+    // Step to the next newer query; past the newest the query is empty again
+    pub fn newer(&mut self) -> Option<&str> {
+        self.cursor = match self.cursor {
+            Some(0) | None => None,
+            Some(i) => Some(i - 1),
+        };
+        self.cursor.map(|i| self.entries[i].as_str())
+    }
+}
@@ -0,0 +1,51 @@
+// Index of the Tags field in both the Add and Edit station forms
+pub const TAGS_FIELD: usize = 3;
+
+// Most suggestions shown under the Tags field at once
+const MAX_TAG_SUGGESTIONS: usize = 5;
+
+// Existing tags that complete the tag being typed (the text after the last
+// comma), ignoring case. Tags already in the field aren't offered again.
+pub fn suggest_tags(all_tags: &[String], text: &str) -> Vec<String> {
+    let (done, current) = split_current_tag(text);
+    if current.is_empty() {
+        return Vec::new();
+    }
+
+    let prefix = current.to_lowercase();
+    let entered: Vec<String> = crate::db::parse_tags(done)
+        .iter()
+        .map(|tag| tag.to_lowercase())
+        .collect();
+    all_tags
+        .iter()
+        .filter(|tag| tag.as_str() != current)
+        .filter(|tag| tag.to_lowercase().starts_with(&prefix))
+        .filter(|tag| !entered.contains(&tag.to_lowercase()))
+        .take(MAX_TAG_SUGGESTIONS)
+        .cloned()
+        .collect()
+}
+
+// The tags already entered, and the one being typed after the last comma
+fn split_current_tag(text: &str) -> (&str, &str) {
+    match text.rfind(',') {
+        Some(i) => (&text[..i], text[i + 1..].trim()),
+        None => ("", text.trim()),
+    }
+}
+
+// Whether the tag being typed is already a tag, ignoring case; Enter then
+// saves the form instead of completing it to a longer suggestion
+pub fn is_existing_tag(all_tags: &[String], text: &str) -> bool {
+    let current = split_current_tag(text).1.to_lowercase();
+    !current.is_empty() && all_tags.iter().any(|tag| tag.to_lowercase() == current)
+}
+
+// Replace the tag being typed with `tag`, ready for the next one
+pub fn complete_tag(text: &str, tag: &str) -> String {
+    match text.rfind(',') {
+        Some(i) => format!("{}, {}, ", &text[..i], tag),
+        None => format!("{}, ", tag),
+    }
+}
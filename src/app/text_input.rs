@@ -0,0 +1,22 @@
+// Editing for the popup input fields. Cursors count characters, not bytes,
+// the same way render_input_field draws them, so "Café" edits correctly.
+
+// Insert `c` before the character at `cursor`
+pub fn insert_char(text: &mut String, cursor: usize, c: char) {
+    let at = byte_offset(text, cursor);
+    text.insert(at, c);
+}
+
+// Remove the character before `cursor`, as Backspace does
+pub fn remove_char_before(text: &mut String, cursor: usize) {
+    if cursor > 0 {
+        let at = byte_offset(text, cursor - 1);
+        text.remove(at);
+    }
+}
+
+fn byte_offset(text: &str, cursor: usize) -> usize {
+    text.char_indices()
+        .nth(cursor)
+        .map_or(text.len(), |(i, _)| i)
+}
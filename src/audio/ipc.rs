@@ -0,0 +1,118 @@
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Request ids let us pick our reply out of mpv's event stream
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+// How long to wait for mpv to answer a command
+const IPC_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Client for mpv's JSON IPC protocol (--input-ipc-server)
+#[derive(Clone, Debug)]
+pub struct MpvIpc {
+    path: PathBuf,
+}
+
+impl MpvIpc {
+    // Create a client with a socket path unique to this process
+    pub fn for_current_process() -> Self {
+        #[cfg(windows)]
+        let path = PathBuf::from(format!(r"\\.\pipe\radio_cli_mpv_{}", std::process::id()));
+
+        #[cfg(not(windows))]
+        let path = std::env::temp_dir().join(format!("radio_cli_mpv_{}.sock", std::process::id()));
+
+        MpvIpc { path }
+    }
+
+    // The path to pass to mpv's --input-ipc-server
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    // Send a command and wait for its reply, returning the "data" field
+    pub fn command(&self, args: Value) -> Result<Value, String> {
+        let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let request = json!({ "command": args, "request_id": request_id });
+
+        let mut stream = self.connect()?;
+        stream
+            .write_all(format!("{}\n", request).as_bytes())
+            .map_err(|e| format!("Failed to write to mpv socket: {}", e))?;
+
+        #[cfg(unix)]
+        return read_reply(BufReader::new(stream), request_id);
+
+        // Named pipes have no read timeout, so the reply is waited for on a
+        // helper thread. One left behind by a hung mpv ends once mpv exits
+        // and the pipe closes.
+        #[cfg(windows)]
+        {
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(read_reply(BufReader::new(stream), request_id));
+            });
+            rx.recv_timeout(IPC_TIMEOUT)
+                .unwrap_or_else(|_| Err("mpv didn't answer in time".to_string()))
+        }
+    }
+
+    pub fn set_property(&self, name: &str, value: Value) -> Result<(), String> {
+        self.command(json!(["set_property", name, value]))
+            .map(|_| ())
+    }
+
+    pub fn get_property(&self, name: &str) -> Result<Value, String> {
+        self.command(json!(["get_property", name]))
+    }
+
+    #[cfg(unix)]
+    fn connect(&self) -> Result<std::os::unix::net::UnixStream, String> {
+        let stream = std::os::unix::net::UnixStream::connect(&self.path)
+            .map_err(|e| format!("Failed to connect to mpv socket: {}", e))?;
+        stream
+            .set_read_timeout(Some(IPC_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        stream
+            .set_write_timeout(Some(IPC_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        Ok(stream)
+    }
+
+    #[cfg(windows)]
+    fn connect(&self) -> Result<std::fs::File, String> {
+        // Named pipes can be opened like regular files
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to connect to mpv pipe: {}", e))
+    }
+}
+
+// Read lines until the reply to `request_id` shows up; mpv interleaves
+// events with replies
+fn read_reply(reader: impl BufRead, request_id: u64) -> Result<Value, String> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("Failed to read from mpv socket: {}", e))?;
+        let reply: Value = match serde_json::from_str(&line) {
+            Ok(reply) => reply,
+            Err(_) => continue,
+        };
+
+        if reply.get("request_id").and_then(Value::as_u64) != Some(request_id) {
+            continue;
+        }
+
+        return match reply.get("error").and_then(Value::as_str) {
+            Some("success") => Ok(reply.get("data").cloned().unwrap_or(Value::Null)),
+            Some(err) => Err(format!("mpv error: {}", err)),
+            None => Err("Malformed reply from mpv".to_string()),
+        };
+    }
+
+    Err("mpv closed the connection without replying".to_string())
+}
@@ -1,17 +1,78 @@
 use rand::Rng;
+#[cfg(not(feature = "skip_mpv"))]
+use serde_json::json;
+#[cfg(not(feature = "skip_mpv"))]
 use std::io::{BufRead, BufReader};
-use std::process::{Child, Command, Stdio};
+use std::process::Child;
+#[cfg(not(feature = "skip_mpv"))]
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+#[cfg(not(feature = "skip_mpv"))]
 use std::thread;
+use std::time::Duration;
+
+mod ipc;
+mod status;
+pub use ipc::MpvIpc;
+pub use status::parse_status_line;
 
 // No need for PI constant in this version
 
+// How often mpv is asked for the playback position
+#[cfg(not(feature = "skip_mpv"))]
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// How often to ask mpv whether the playing stream is still delivering audio
+#[cfg(not(feature = "skip_mpv"))]
+const CONNECTIVITY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Stars in the starfield: how many it starts with and the range the user can
+// pick from. While playing it grows up to a quarter more; while stopped it
+// thins out to a quarter.
+pub const DEFAULT_STAR_COUNT: usize = 200;
+pub const MIN_STAR_COUNT: usize = 50;
+pub const MAX_STAR_COUNT: usize = 500;
+
 #[derive(Clone)]
 pub struct StreamInfo {
     pub bitrate: String,
     pub format: String,
     pub station_name: String,
     pub current_song: Option<String>,
+    pub genre: Option<String>,              // From the icy-genre header
+    pub bitrate_from_headers: bool,         // icy-br was present, so ignore mpv's estimate
+    pub connectivity: Option<Connectivity>, // Result of the last liveness check, if any
+    pub position: Option<f64>,              // Seconds played, from mpv's time-pos
+    pub duration: Option<f64>,              // Length in seconds; None for live streams
+}
+
+// Whether audio is actually flowing, as last reported by mpv
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Streaming, // Playback is advancing
+    Buffering, // Waiting for the network to fill the cache
+    Stalled,   // Connected (or dead) but nothing is playing
+}
+
+impl Connectivity {
+    // Classify mpv's core-idle and paused-for-cache properties; anything we
+    // couldn't read counts against the stream
+    pub fn from_mpv(core_idle: Option<bool>, paused_for_cache: Option<bool>) -> Self {
+        match (core_idle, paused_for_cache) {
+            (_, Some(true)) => Connectivity::Buffering,
+            (Some(false), _) => Connectivity::Streaming,
+            _ => Connectivity::Stalled,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Connectivity::Streaming => "Streaming",
+            Connectivity::Buffering => "Buffering",
+            Connectivity::Stalled => "Stalled",
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -26,14 +87,31 @@ pub struct Star {
 
 #[derive(Clone)]
 pub struct AudioState {
-    pub stars: Vec<Star>, // Stars for the starfield effect
-    pub bass_impact: f64, // Bass impact value (0.0-1.0) for animations
+    pub stars: Vec<Star>,  // Stars for the starfield effect
+    pub star_count: usize, // How many stars the field starts with
+    pub bass_impact: f64,  // Bass impact value (0.0-1.0) for animations
     pub is_playing: bool,
     pub is_muted: bool,
     pub volume: u8, // Volume level (0-100)
     pub stream_info: Option<StreamInfo>,
     pub frame_count: u64, // Count frames for animations
     pub warp_speed: f64,  // Speed factor for the starfield (0.5-3.0)
+    pub vis_frozen: bool, // Animation paused; the audio keeps playing
+}
+
+// A fresh starfield of `count` stars
+fn random_stars<R: Rng>(rng: &mut R, count: usize) -> Vec<Star> {
+    (0..count)
+        .map(|_| Star {
+            // Random position in 3D space
+            x: rng.gen_range(-1.0..1.0), // X position (-1 to 1, center = 0)
+            y: rng.gen_range(-1.0..1.0), // Y position (-1 to 1, center = 0)
+            z: rng.gen_range(0.01..1.0), // Z position (depth, 0 = furthest)
+            brightness: rng.gen_range(0.2..1.0), // Random brightness
+            speed: rng.gen_range(0.005..0.02), // Speed factor
+            color: rng.gen_range(0..5),  // Random color (0-4)
+        })
+        .collect()
 }
 
 impl Default for AudioState {
@@ -44,24 +122,15 @@ impl Default for AudioState {
 
 impl AudioState {
     pub fn new() -> Self {
-        // Initialize stars for the starfield
-        let mut stars = Vec::with_capacity(200); // 200 stars in the field
-        let mut rng = rand::thread_rng();
-
-        for _ in 0..200 {
-            stars.push(Star {
-                // Random position in 3D space
-                x: rng.gen_range(-1.0..1.0), // X position (-1 to 1, center = 0)
-                y: rng.gen_range(-1.0..1.0), // Y position (-1 to 1, center = 0)
-                z: rng.gen_range(0.01..1.0), // Z position (depth, 0 = furthest)
-                brightness: rng.gen_range(0.2..1.0), // Random brightness
-                speed: rng.gen_range(0.005..0.02), // Speed factor
-                color: rng.gen_range(0..5),  // Random color (0-4)
-            });
-        }
+        Self::with_rng(&mut rand::thread_rng())
+    }
 
+    // Build the initial state from the given RNG, so a seeded one gives a
+    // reproducible starfield
+    pub fn with_rng<R: Rng>(rng: &mut R) -> Self {
         AudioState {
-            stars,
+            stars: random_stars(rng, DEFAULT_STAR_COUNT),
+            star_count: DEFAULT_STAR_COUNT,
             bass_impact: 0.0,
             is_playing: false,
             is_muted: false,
@@ -69,13 +138,31 @@ impl AudioState {
             stream_info: None,
             frame_count: 0,
             warp_speed: 1.0,
+            vis_frozen: false,
         }
     }
 
+    // Rebuild the starfield with a new number of stars, kept within
+    // MIN_STAR_COUNT-MAX_STAR_COUNT; returns the count used
+    pub fn set_star_count(&mut self, count: usize) -> usize {
+        self.star_count = count.clamp(MIN_STAR_COUNT, MAX_STAR_COUNT);
+        self.stars = random_stars(&mut rand::thread_rng(), self.star_count);
+        self.star_count
+    }
+
     pub fn update_visualization(&mut self) {
+        self.update_visualization_with(&mut rand::thread_rng());
+    }
+
+    // Advance the animation by one frame, drawing randomness from `rng`
+    pub fn update_visualization_with<R: Rng>(&mut self, rng: &mut R) {
+        // Frozen visualizations hold their last frame
+        if self.vis_frozen {
+            return;
+        }
+
         // Increment frame counter
         self.frame_count += 1;
-        let mut rng = rand::thread_rng();
 
         if self.is_playing {
             // 1. Update bass impact - affects starfield speed
@@ -114,7 +201,7 @@ impl AudioState {
             }
 
             // 4. Occasionally add new stars for visual variety
-            if rng.gen_bool(0.05) && self.stars.len() < 250 {
+            if rng.gen_bool(0.05) && self.stars.len() < self.star_count + self.star_count / 4 {
                 self.stars.push(Star {
                     x: rng.gen_range(-1.0..1.0),
                     y: rng.gen_range(-1.0..1.0),
@@ -141,7 +228,7 @@ impl AudioState {
             }
 
             // Gradually reduce star count when not playing
-            if rng.gen_bool(0.01) && self.stars.len() > 50 {
+            if rng.gen_bool(0.01) && self.stars.len() > self.star_count / 4 {
                 self.stars.pop();
             }
         }
@@ -180,34 +267,45 @@ impl AudioVisualizer {
         }
     }
 
-    pub fn set_muted(&self, muted: bool) {
-        if let Ok(mut state) = self.state.lock() {
-            state.is_muted = muted;
+    // Change the number of stars; returns the count actually used
+    pub fn set_star_count(&self, count: usize) -> usize {
+        match self.state.lock() {
+            Ok(mut state) => state.set_star_count(count),
+            Err(_) => count,
         }
     }
 
-    // Increase volume
-    pub fn increase_volume(&self) {
-        if let Ok(mut state) = self.state.lock() {
-            // Don't go above 100%
-            if state.volume < 100 {
-                state.volume = state.volume.saturating_add(5);
+    pub fn star_count(&self) -> usize {
+        self.state
+            .lock()
+            .map_or(DEFAULT_STAR_COUNT, |state| state.star_count)
+    }
+
+    // Pause or resume the animation; returns whether it's now frozen
+    pub fn toggle_frozen(&self) -> bool {
+        match self.state.lock() {
+            Ok(mut state) => {
+                state.vis_frozen = !state.vis_frozen;
+                state.vis_frozen
             }
+            Err(_) => false,
         }
     }
 
-    // Decrease volume
-    pub fn decrease_volume(&self) {
+    pub fn set_muted(&self, muted: bool) {
         if let Ok(mut state) = self.state.lock() {
-            // Don't go below 0%
-            if state.volume > 0 {
-                state.volume = state.volume.saturating_sub(5);
-            }
+            state.is_muted = muted;
+        }
+    }
+
+    // Set an absolute volume, clamped to 0-100%
+    pub fn set_volume(&self, volume: u8) {
+        if let Ok(mut state) = self.state.lock() {
+            state.volume = volume.min(100);
         }
     }
 
     // Get the current volume
-    #[allow(dead_code)]
     pub fn get_volume(&self) -> u8 {
         if let Ok(state) = self.state.lock() {
             state.volume
@@ -223,6 +321,11 @@ impl AudioVisualizer {
                 format,
                 station_name,
                 current_song: None,
+                genre: None,
+                bitrate_from_headers: false,
+                connectivity: None,
+                position: None,
+                duration: None,
             });
         }
     }
@@ -241,9 +344,73 @@ impl AudioVisualizer {
     }
 }
 
+// Quote an argument for a POSIX shell, leaving plain words alone
+pub fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+// The mpv argument that logs in to a stream with HTTP basic auth
+pub fn basic_auth_arg(credentials: &crate::db::Credentials) -> String {
+    use base64::Engine;
+    let token = base64::engine::general_purpose::STANDARD
+        .encode(format!("{}:{}", credentials.username, credentials.password));
+    format!("--http-header-fields=Authorization: Basic {}", token)
+}
+
+// Split a stored mpv argument string into individual arguments.
+// Whitespace separates arguments; single or double quotes group text containing spaces.
+pub fn split_mpv_args(args: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+
+    for c in args.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            None if c.is_whitespace() => {
+                if in_arg {
+                    result.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        result.push(current);
+    }
+    result
+}
+
 pub struct Player {
     pub current_player: Option<Child>,
     pub is_muted: bool,
+    pub ipc: MpvIpc,           // JSON IPC client for controlling the running mpv
+    pub proxy: Option<String>, // HTTP proxy passed to mpv, if any
+    pub debug_metadata: bool,  // Log every raw STATUS line from mpv
+    pub detach: bool,          // Leave mpv running on quit (--detach)
+    pub network_timeout: Option<Duration>, // Give up on a stream that sends nothing for this long
+    stopping: Arc<AtomicBool>, // Set before we kill mpv, so its exit isn't taken as the stream ending
+    stream_ended: Arc<AtomicBool>, // Set by the reader thread when mpv exits on its own
+    audio_started: Arc<AtomicBool>, // Set once mpv reports a codec, i.e. audio is arriving
 }
 
 impl Default for Player {
@@ -257,6 +424,14 @@ impl Player {
         Player {
             current_player: None,
             is_muted: false,
+            ipc: MpvIpc::for_current_process(),
+            proxy: None,
+            debug_metadata: false,
+            detach: false,
+            network_timeout: None,
+            stopping: Arc::new(AtomicBool::new(false)),
+            stream_ended: Arc::new(AtomicBool::new(false)),
+            audio_started: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -264,17 +439,21 @@ impl Player {
         &mut self,
         station_name: String,
         url: String,
+        mpv_args: Option<&str>,
+        credentials: Option<&crate::db::Credentials>,
         visualizer: &AudioVisualizer,
     ) -> Result<(), String> {
         // Kill any currently playing process
         self.stop();
 
         // Get the shared state handle for the background thread
+        #[cfg(not(feature = "skip_mpv"))]
         let state_handle = visualizer.get_state_handle();
 
         #[cfg(feature = "skip_mpv")]
         {
             // Simulation mode for Windows builds without MPV
+            let _ = (url, mpv_args, credentials);
             visualizer.set_stream_info(
                 station_name.clone(),
                 "Simulated".to_string(),
@@ -284,17 +463,66 @@ impl Player {
 
             // No actual player process in simulation mode
             // Just simulate playing
-            return Ok(());
+            Ok(())
         }
 
+        // Read the ICY headers ourselves; mpv takes a while to report a bitrate
+        #[cfg(not(feature = "skip_mpv"))]
+        {
+            let vis_state = Arc::clone(&state_handle);
+            let probe_url = url.clone();
+            let probe_station = station_name.clone();
+            let proxy = self.proxy.clone();
+            thread::spawn(move || {
+                let headers = match tokio::runtime::Runtime::new() {
+                    Ok(rt) => rt.block_on(crate::stream_info::probe_stream(
+                        &probe_url,
+                        proxy.as_deref(),
+                    )),
+                    Err(e) => Err(e.to_string()),
+                };
+
+                let headers = headers.map_err(|e| {
+                    log::warn!("Could not read ICY headers from {}: {}", probe_url, e);
+                });
+                if let (Ok(headers), Ok(mut state)) = (headers, vis_state.lock()) {
+                    // Skip if another station started while we were waiting
+                    if let Some(info) = state
+                        .stream_info
+                        .as_mut()
+                        .filter(|info| info.station_name == probe_station)
+                    {
+                        info.genre = headers.genre;
+                        if let Some(bitrate) = headers.bitrate {
+                            info.bitrate = bitrate;
+                            info.bitrate_from_headers = true;
+                        }
+                    }
+                }
+            });
+        }
+
+        #[cfg(not(feature = "skip_mpv"))]
+        let mut command = Command::new("mpv");
         #[cfg(not(feature = "skip_mpv"))]
-        match Command::new("mpv")
-            .arg("--term-status-msg=STATUS: ${metadata/StreamTitle:} FORMAT: ${audio-codec} BITRATE: ${audio-bitrate}")
-            .arg("--input-ipc-server=/tmp/mpvsocket_$$") // Create a socket for control, $$ is replaced with PID
-            .arg(url)
+        command
+            .arg(format!("--term-status-msg={}", status::STATUS_TEMPLATE)) // Machine-readable metadata line
+            .arg(format!("--input-ipc-server={}", self.ipc.path().display())) // Socket for JSON IPC control
+            .args(credentials.map(basic_auth_arg)) // Login for private streams, kept out of copied command lines
+            .args(self.mpv_args_for(&url, mpv_args, visualizer.get_volume())) // Volume, proxy, station args, URL
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn() {
+            .stderr(Stdio::null());
+
+        // A detachable player gets its own process group so closing the
+        // terminal doesn't take it down with us
+        #[cfg(all(unix, not(feature = "skip_mpv")))]
+        if self.detach {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        #[cfg(not(feature = "skip_mpv"))]
+        match command.spawn() {
             Ok(mut child) => {
                 // Get the stdout to read from it
                 let stdout = child.stdout.take().expect("Failed to get stdout");
@@ -303,84 +531,79 @@ impl Player {
                 visualizer.set_stream_info(
                     station_name.clone(),
                     "Detecting...".to_string(),
-                    "Detecting...".to_string()
+                    "Detecting...".to_string(),
                 );
                 visualizer.set_playing(true);
+                // A fresh mpv process always starts unmuted
+                visualizer.set_muted(false);
+
+                // Fresh flags for this process; the old reader may still be winding down
+                self.stopping = Arc::new(AtomicBool::new(false));
+                self.stream_ended.store(false, Ordering::SeqCst);
+                self.audio_started = Arc::new(AtomicBool::new(false));
+                let stopping = Arc::clone(&self.stopping);
+                let stream_ended = Arc::clone(&self.stream_ended);
+                let audio_started = Arc::clone(&self.audio_started);
+                let exited = Arc::new(AtomicBool::new(false));
+
+                spawn_ipc_poller(
+                    self.ipc.clone(),
+                    Arc::clone(&state_handle),
+                    station_name.clone(),
+                    Arc::clone(&self.stopping),
+                    Arc::clone(&exited),
+                );
 
                 // Spawn a thread to read mpv output
                 let vis_state = Arc::clone(&state_handle);
+                let debug_metadata = self.debug_metadata;
                 thread::spawn(move || {
                     let reader = BufReader::new(stdout);
                     for line in reader.lines().map_while(Result::ok) {
+                        if debug_metadata && line.contains("STATUS:") {
+                            log::info!("mpv status: {:?}", line);
+                        }
+
                         // Parse the line for stream metadata
-                        if line.starts_with("STATUS:") {
+                        if line.contains("STATUS:") {
                             if let Ok(mut state) = vis_state.lock() {
-                                // More robust metadata extraction
-                                let line_str = line.trim_start_matches("STATUS: ");
-
-                                // Find FORMAT: and BITRATE: sections more reliably
-                                let mut format = "Unknown".to_string();
-                                let mut bitrate = "Unknown".to_string();
-                                let mut song = None;
-
-                                // Extract format
-                                if let Some(format_idx) = line_str.find("FORMAT:") {
-                                    // Find the end of the format value (next keyword or end of string)
-                                    let format_start = format_idx + "FORMAT:".len();
-                                    let format_end = line_str[format_start..]
-                                        .find("BITRATE:")
-                                        .map_or(line_str.len(), |pos| format_start + pos);
-
-                                    // Extract and trim the format value
-                                    format = line_str[format_start..format_end].trim().to_string();
-                                }
-
-                                // Extract bitrate
-                                if let Some(bitrate_idx) = line_str.find("BITRATE:") {
-                                    // Get the rest of the line after BITRATE:
-                                    let bitrate_start = bitrate_idx + "BITRATE:".len();
-                                    let bitrate_value = line_str[bitrate_start..].trim();
-
-                                    // Check if the bitrate value is not empty
-                                    if !bitrate_value.is_empty() {
-                                        bitrate = format!("{} kbps", bitrate_value);
-                                    }
-                                }
-
-                                // Extract song
-                                // The song title is everything before FORMAT: or BITRATE:, whichever comes first
-                                let first_keyword = std::cmp::min(
-                                    line_str.find("FORMAT:").unwrap_or(line_str.len()),
-                                    line_str.find("BITRATE:").unwrap_or(line_str.len())
-                                );
-                                let potential_song = line_str[..first_keyword].trim();
-                                if !potential_song.is_empty() {
-                                    song = Some(potential_song.to_string());
+                                let (song, format, bitrate) = parse_status_line(&line);
+                                if format != "Unknown" {
+                                    audio_started.store(true, Ordering::SeqCst);
                                 }
 
                                 // Update the stream info
                                 if let Some(info) = &mut state.stream_info {
                                     info.format = format;
-                                    info.bitrate = bitrate;
+                                    if !info.bitrate_from_headers {
+                                        info.bitrate = bitrate;
+                                    }
                                     info.current_song = song;
                                 }
                             }
                         }
                     }
+
+                    // stdout closed: mpv has exited. Unless we killed it, the
+                    // stream ended by itself and the app should hear about it.
+                    if !stopping.load(Ordering::SeqCst) {
+                        stream_ended.store(true, Ordering::SeqCst);
+                    }
+                    exited.store(true, Ordering::SeqCst);
                 });
 
                 self.current_player = Some(child);
                 Ok(())
-            },
+            }
             Err(e) => {
-                eprintln!("Failed to start player: {} (make sure mpv is installed)", e);
+                log::error!("Failed to start player: {} (make sure mpv is installed)", e);
                 visualizer.set_stream_info(
                     station_name,
                     "Error".to_string(),
-                    format!("Failed to start: {}", e)
+                    format!("Failed to start: {}", e),
                 );
                 Err(e.to_string())
-            },
+            }
         }
     }
 
@@ -388,7 +611,9 @@ impl Player {
         #[cfg(not(feature = "skip_mpv"))]
         if let Some(mut player) = self.current_player.take() {
             // Kill the player process
+            self.stopping.store(true, Ordering::SeqCst);
             let _ = player.kill();
+            let _ = player.wait();
         }
 
         #[cfg(feature = "skip_mpv")]
@@ -401,187 +626,269 @@ impl Player {
         self.is_muted = false;
     }
 
-    #[allow(unused_variables)]
-    pub fn toggle_mute(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
-        // Toggle the mute state
-        self.is_muted = !self.is_muted;
+    // The mpv arguments for playing a URL, apart from the IPC socket and
+    // status line that only matter to us
+    pub fn mpv_args_for(&self, url: &str, mpv_args: Option<&str>, volume: u8) -> Vec<String> {
+        let mut args = vec![format!("--volume={}", volume)]; // Start at the volume the UI shows
+        args.extend(
+            crate::proxy::proxy_for(self.proxy.as_deref(), url).map(crate::proxy::mpv_proxy_arg),
+        ); // Route through the proxy if set
+        args.extend(
+            self.network_timeout
+                .map(|timeout| format!("--network-timeout={}", timeout.as_secs())),
+        ); // Don't sit on "Detecting..." forever
+        args.extend(split_mpv_args(mpv_args.unwrap_or_default())); // Per-station extra arguments
+        args.push(url.to_string());
+        args
+    }
 
-        // Update the mute state in the visualizer
-        visualizer.set_muted(self.is_muted);
+    // A shell command line that plays a URL the way we would
+    pub fn mpv_command_line(&self, url: &str, mpv_args: Option<&str>, volume: u8) -> String {
+        std::iter::once("mpv".to_string())
+            .chain(self.mpv_args_for(url, mpv_args, volume))
+            .map(|arg| shell_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 
+    // Check whether mpv exited by itself since the last call. Returns its
+    // exit status (true for a clean exit) and forgets the finished process.
+    pub fn take_stream_ended(&mut self) -> Option<bool> {
+        if !self.stream_ended.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+        let mut player = self.current_player.take()?;
+        let success = player
+            .wait()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        Some(success)
+    }
+
+    // Whether the current (or just finished) mpv ever got audio from its stream
+    pub fn audio_started(&self) -> bool {
+        self.audio_started.load(Ordering::SeqCst)
+    }
+
+    // Let go of the running player without stopping it, returning its PID.
+    // mpv's stdout is a pipe to our reader thread, which goes away with us,
+    // so mpv is told to stop writing to the terminal first.
+    pub fn release(&mut self) -> Option<u32> {
+        let child = self.current_player.take()?;
+        self.stopping.store(true, Ordering::SeqCst);
+        #[cfg(not(feature = "skip_mpv"))]
+        if let Err(e) = self.ipc.set_property("terminal", json!(false)) {
+            log::warn!("Failed to silence the detached player: {}", e);
+        }
+        Some(child.id())
+    }
+
+    pub fn toggle_mute(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
         #[cfg(feature = "skip_mpv")]
         {
-            // Nothing to do in simulation mode
-            return Ok(());
+            // Nothing to send in simulation mode, just flip the indicator
+            self.is_muted = !self.is_muted;
+            visualizer.set_muted(self.is_muted);
+            Ok(())
         }
 
         #[cfg(not(feature = "skip_mpv"))]
-        if let Some(child) = &mut self.current_player {
-            // Try to send a mute command to the MPV process using echo
-            // This works by sending 'm' command to the input pipe
+        {
+            if self.current_player.is_none() {
+                return Err("No player is currently running".to_string());
+            }
 
-            // We'll try to use echo or printf with a pipe to mpv
-            // This is safer and works across different platforms
+            let target = !self.is_muted;
+            self.ipc.set_property("mute", json!(target))?;
 
-            #[cfg(target_os = "macos")]
-            let mute_result = {
-                // On macOS, just update the visual indicator without actually muting
-                // This is because macOS process control is more restrictive
-                Ok(())
-            };
+            // Read back what mpv actually applied so the indicator stays in sync
+            let muted = self
+                .ipc
+                .get_property("mute")
+                .ok()
+                .and_then(|value| value.as_bool())
+                .unwrap_or(target);
 
-            #[cfg(target_os = "linux")]
-            let mute_result = {
-                // On Linux, we can try to send a command to MPV's input pipe if it exists
-                // Try to find the mpv socket if it exists
-                let player_pid = child.id();
-                if let Some(pid) = Some(player_pid) {
-                    // MPV creates socket in /tmp/
-                    if let Ok(sockets) = std::fs::read_dir("/tmp") {
-                        for entry in sockets.filter_map(Result::ok) {
-                            if let Ok(fname) = entry.file_name().into_string() {
-                                if fname.starts_with(&format!("mpvsocket_{}", pid)) {
-                                    // Found the socket, try to send a mute command
-                                    let result = std::process::Command::new("echo")
-                                        .arg("cycle mute")
-                                        .arg("|")
-                                        .arg("socat")
-                                        .arg("-")
-                                        .arg(format!("UNIX-CONNECT:/tmp/{}", fname))
-                                        .status();
-
-                                    if result.is_err() {
-                                        eprintln!("Failed to send mute command to MPV socket");
-                                    }
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-                // Even if we fail to send the actual command, return OK for the UI
-                Ok(())
-            };
+            self.is_muted = muted;
+            visualizer.set_muted(muted);
+            Ok(())
+        }
+    }
 
-            #[cfg(target_os = "windows")]
-            let mute_result = {
-                // On Windows, just update the visual indicator
-                Ok(())
-            };
+    // Re-read the now-playing title and bitrate from mpv, for when the
+    // stdout status line hasn't caught up with the stream
+    pub fn refresh_metadata(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
+        #[cfg(feature = "skip_mpv")]
+        {
+            let _ = visualizer;
+            Ok(())
+        }
 
-            #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-            let mute_result = {
-                // For other platforms, just update the visual indicator
-                Ok(())
-            };
+        #[cfg(not(feature = "skip_mpv"))]
+        {
+            if self.current_player.is_none() {
+                return Err("No player is currently running".to_string());
+            }
 
-            // Return the result, but for most platforms this will just be a visual mute
-            mute_result
-        } else {
-            Err("No player is currently running".to_string())
+            // mpv exposes ICY titles as icy-title; other streams may carry a
+            // StreamTitle tag instead
+            let title = ["metadata/by-key/icy-title", "metadata/by-key/StreamTitle"]
+                .iter()
+                .filter_map(|name| self.ipc.get_property(name).ok())
+                .filter_map(|value| value.as_str().map(str::trim).map(str::to_string))
+                .find(|title| !title.is_empty());
+
+            // audio-bitrate is in bits per second
+            let bitrate = self
+                .ipc
+                .get_property("audio-bitrate")
+                .ok()
+                .and_then(|value| value.as_f64())
+                .filter(|bps| *bps > 0.0)
+                .map(|bps| format!("{} kbps", (bps / 1000.0).round()));
+
+            if let Ok(mut state) = visualizer.state.lock() {
+                if let Some(info) = &mut state.stream_info {
+                    if title.is_some() {
+                        info.current_song = title;
+                    }
+                    if let Some(bitrate) = bitrate {
+                        if !info.bitrate_from_headers {
+                            info.bitrate = bitrate;
+                        }
+                    }
+                }
+            }
+            Ok(())
         }
     }
 
-    // Update metadata - called periodically by the App
-    #[allow(dead_code)]
-    pub fn update_metadata(&mut self, _visualizer: &AudioVisualizer) {
-        // Nothing to do here with the command-line approach,
-        // since metadata updates are handled in the background thread
-        // that reads from mpv's stdout in the play_station function.
-        // This function is included for API compatibility.
-    }
-
     // Increase volume
-    #[allow(unused_variables)]
     pub fn volume_up(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
+        let volume = visualizer.get_volume().saturating_add(5);
+        self.set_volume(volume, visualizer)
+    }
+
+    // Decrease volume
+    pub fn volume_down(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
+        let volume = visualizer.get_volume().saturating_sub(5);
+        self.set_volume(volume, visualizer)
+    }
+
+    // Set an absolute volume (0-100%)
+    pub fn set_volume(&mut self, volume: u8, visualizer: &AudioVisualizer) -> Result<(), String> {
+        let volume = volume.min(100);
+
         #[cfg(feature = "skip_mpv")]
         {
             // Update volume in the visualizer even in simulation mode
-            visualizer.increase_volume();
-            return Ok(());
+            visualizer.set_volume(volume);
+            Ok(())
         }
 
         #[cfg(not(feature = "skip_mpv"))]
-        if let Some(child) = &mut self.current_player {
-            // Try to send a volume-up command to MPV
-            // This is a visual-only change for most platforms
-            eprintln!("Volume up");
-
-            #[cfg(target_os = "linux")]
-            {
-                // On Linux, try to send volume command to MPV's socket if it exists
-                let id = child.id();
-                if let Ok(sockets) = std::fs::read_dir("/tmp") {
-                    for entry in sockets.filter_map(Result::ok) {
-                        if let Ok(fname) = entry.file_name().into_string() {
-                            if fname.starts_with(&format!("mpvsocket_{}", id)) {
-                                // Found the socket, try to send a volume command
-                                let _ = std::process::Command::new("echo")
-                                    .arg("add volume 5")
-                                    .arg("|")
-                                    .arg("socat")
-                                    .arg("-")
-                                    .arg(format!("UNIX-CONNECT:/tmp/{}", fname))
-                                    .status();
-                                break;
-                            }
-                        }
-                    }
-                }
+        {
+            if self.current_player.is_none() {
+                return Err("No player is currently running".to_string());
             }
 
+            self.ipc.set_property("volume", json!(volume))?;
+
             // Update volume in the visualizer state
-            visualizer.increase_volume();
+            visualizer.set_volume(volume);
             Ok(())
-        } else {
-            Err("No player is currently running".to_string())
         }
     }
 
-    // Decrease volume
-    #[allow(unused_variables)]
-    pub fn volume_down(&mut self, visualizer: &AudioVisualizer) -> Result<(), String> {
+    // Seek relative to the current position, for streams that support it
+    pub fn seek(&mut self, seconds: f64) -> Result<(), String> {
         #[cfg(feature = "skip_mpv")]
         {
-            // Update volume in the visualizer even in simulation mode
-            visualizer.decrease_volume();
-            return Ok(());
+            let _ = seconds;
+            Err("Seek not supported in simulation mode".to_string())
         }
 
         #[cfg(not(feature = "skip_mpv"))]
-        if let Some(child) = &mut self.current_player {
-            // Try to send a volume-down command to MPV
-            // This is a visual-only change for most platforms
-            eprintln!("Volume down");
+        {
+            if self.current_player.is_none() {
+                return Err("No player is currently running".to_string());
+            }
+
+            // Live streams report themselves as not seekable
+            let seekable = self
+                .ipc
+                .get_property("seekable")
+                .ok()
+                .and_then(|value| value.as_bool())
+                .unwrap_or(false);
+            if !seekable {
+                return Err("Seek not supported for this stream".to_string());
+            }
+
+            self.ipc
+                .command(json!(["seek", seconds, "relative"]))
+                .map(|_| ())
+        }
+    }
+}
+
+// Ask mpv for what its status line doesn't carry: the playback position
+// every second and, now and then, whether audio is still flowing. This runs
+// on its own thread, beside the stdout reader, so a slow IPC answer never
+// holds up the UI. It ends with the player.
+#[cfg(not(feature = "skip_mpv"))]
+fn spawn_ipc_poller(
+    ipc: MpvIpc,
+    state: Arc<Mutex<AudioState>>,
+    station_name: String,
+    stopping: Arc<AtomicBool>,
+    exited: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut connectivity_checked = std::time::Instant::now();
+        loop {
+            thread::sleep(PROGRESS_POLL_INTERVAL);
+            if stopping.load(Ordering::SeqCst) || exited.load(Ordering::SeqCst) {
+                break;
+            }
 
-            #[cfg(target_os = "linux")]
+            let read = |name: &str| ipc.get_property(name).ok();
+            let position = read("time-pos").and_then(|value| value.as_f64());
+            // Live streams report no duration (or zero)
+            let duration = read("duration")
+                .and_then(|value| value.as_f64())
+                .filter(|secs| *secs > 0.0);
+            let connectivity = if connectivity_checked.elapsed() >= CONNECTIVITY_CHECK_INTERVAL {
+                connectivity_checked = std::time::Instant::now();
+                Some(Connectivity::from_mpv(
+                    read("core-idle").and_then(|value| value.as_bool()),
+                    read("paused-for-cache").and_then(|value| value.as_bool()),
+                ))
+            } else {
+                None
+            };
+
+            let mut state = match state.lock() {
+                Ok(state) => state,
+                Err(_) => break,
+            };
+            // Skip if another station started while we were asking
+            if let Some(info) = state
+                .stream_info
+                .as_mut()
+                .filter(|info| info.station_name == station_name)
             {
-                // On Linux, try to send volume command to MPV's socket if it exists
-                let id = child.id();
-                if let Ok(sockets) = std::fs::read_dir("/tmp") {
-                    for entry in sockets.filter_map(Result::ok) {
-                        if let Ok(fname) = entry.file_name().into_string() {
-                            if fname.starts_with(&format!("mpvsocket_{}", id)) {
-                                // Found the socket, try to send a volume command
-                                let _ = std::process::Command::new("echo")
-                                    .arg("add volume -5")
-                                    .arg("|")
-                                    .arg("socat")
-                                    .arg("-")
-                                    .arg(format!("UNIX-CONNECT:/tmp/{}", fname))
-                                    .status();
-                                break;
-                            }
-                        }
+                info.position = position;
+                info.duration = duration;
+                if let Some(connectivity) = connectivity {
+                    if connectivity == Connectivity::Stalled
+                        && info.connectivity != Some(Connectivity::Stalled)
+                    {
+                        log::warn!("Stream for {} has stalled", info.station_name);
                     }
+                    info.connectivity = Some(connectivity);
                 }
             }
-
-            // Update volume in the visualizer state
-            visualizer.decrease_volume();
-            Ok(())
-        } else {
-            Err("No player is currently running".to_string())
         }
-    }
+    });
 }
@@ -0,0 +1,58 @@
+// Parsing of the status line mpv prints via --term-status-msg.
+// Fields are separated by the ASCII record separator, which never shows up in
+// stream titles, so no amount of "FORMAT:" in a song name can confuse the parser.
+
+const STATUS_MARKER: &str = "STATUS:";
+pub const FIELD_SEPARATOR: char = '\x1e';
+
+// The --term-status-msg template matching parse_status_line
+#[cfg(not(feature = "skip_mpv"))]
+pub const STATUS_TEMPLATE: &str =
+    "STATUS:\x1e${metadata/StreamTitle:}\x1e${audio-codec}\x1e${audio-bitrate}";
+
+// Split a status line into (song, format, bitrate); missing values become "Unknown"
+pub fn parse_status_line(line: &str) -> (Option<String>, String, String) {
+    let body = match line.find(STATUS_MARKER) {
+        Some(start) => &line[start + STATUS_MARKER.len()..],
+        None => line,
+    };
+    let body = body.strip_prefix(FIELD_SEPARATOR).unwrap_or(body);
+
+    // Split from the right so the codec and bitrate are always the last two fields
+    let mut fields = body.rsplitn(3, FIELD_SEPARATOR);
+    let (bitrate, format, song) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(bitrate), Some(format), Some(song)) => (bitrate, format, song),
+        // Truncated line: whatever is there is the song
+        (Some(song), _, _) => ("", "", song),
+        _ => ("", "", ""),
+    };
+
+    let song = clean(song);
+    let song = (!song.is_empty()).then_some(song);
+    let format = known_or_unknown(clean(format));
+    let bitrate = match known_or_unknown(clean(bitrate)) {
+        b if b == "Unknown" || b.ends_with("kbps") => b,
+        b => format!("{} kbps", b),
+    };
+
+    (song, format, bitrate)
+}
+
+// Blank out control characters (stray \r, tabs) and trim surrounding whitespace
+fn clean(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// mpv prints "(unavailable)" for properties it doesn't know yet
+fn known_or_unknown(value: String) -> String {
+    if value.is_empty() || value == "(unavailable)" {
+        "Unknown".to_string()
+    } else {
+        value
+    }
+}
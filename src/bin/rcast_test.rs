@@ -1,14 +1,17 @@
 use std::error::Error;
 
 // Import the main crate's modules
-use radio_cli::rcast;
+use radio_cli::{proxy, rcast};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     println!("Testing RCast station fetching...");
 
     // Try to fetch stations
-    match rcast::fetch_stations().await {
+    // Honor proxy environment variables like the app does
+    let proxy = proxy::resolve_proxy(None);
+
+    match rcast::fetch_stations(proxy.as_deref()).await {
         Ok(stations) => {
             println!("Successfully fetched {} stations:", stations.len());
 
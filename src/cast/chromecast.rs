@@ -0,0 +1,512 @@
+use super::{CastDevice, CastProtocol, REQUEST_TIMEOUT};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration, Instant};
+
+// Chromecasts announce themselves over mDNS
+const MDNS_ADDR: &str = "224.0.0.251:5353";
+const SERVICE: &str = "_googlecast._tcp.local";
+const DEFAULT_PORT: u16 = 8009;
+
+// DNS record types the answers are made of
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+
+// Cast protocol endpoints and channels
+const SENDER: &str = "sender-0";
+const RECEIVER: &str = "receiver-0";
+const CONNECTION_NS: &str = "urn:x-cast:com.google.cast.tp.connection";
+const HEARTBEAT_NS: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const RECEIVER_NS: &str = "urn:x-cast:com.google.cast.receiver";
+const MEDIA_NS: &str = "urn:x-cast:com.google.cast.media";
+
+// Google's Default Media Receiver, which plays any URL it's given
+const MEDIA_RECEIVER_APP: &str = "CC1AD845";
+
+// Starting the receiver app on the device can take a while
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+// The protocol caps messages at 64 KiB
+const MAX_MESSAGE: usize = 64 * 1024;
+
+// A DNS query asking who offers the Cast service
+pub fn mdns_query() -> Vec<u8> {
+    // ID 0, no flags, one question
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in SERVICE.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&1u16.to_be_bytes()); // class IN
+    packet
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *packet.get(pos)?,
+        *packet.get(pos + 1)?,
+    ]))
+}
+
+// A (possibly compressed) domain name at `pos`, and where the data after it starts
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+    loop {
+        let len = *packet.get(pos)? as usize;
+        if len == 0 {
+            pos += 1;
+            break;
+        }
+        if len & 0xc0 == 0xc0 {
+            // Pointer to a name earlier in the packet
+            jumps += 1;
+            if jumps > 16 {
+                return None;
+            }
+            end.get_or_insert(pos + 2);
+            pos = ((len & 0x3f) << 8) | *packet.get(pos + 1)? as usize;
+            continue;
+        }
+        let label = packet.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    Some((labels.join("."), end.unwrap_or(pos)))
+}
+
+// The friendly name from a Cast TXT record ("fn=Living Room")
+fn txt_friendly_name(data: &[u8]) -> Option<String> {
+    let mut pos = 0;
+    while let Some(&len) = data.get(pos) {
+        let entry = data.get(pos + 1..pos + 1 + len as usize)?;
+        if let Some(name) = entry.strip_prefix(b"fn=") {
+            return Some(String::from_utf8_lossy(name).into_owned());
+        }
+        pos += 1 + len as usize;
+    }
+    None
+}
+
+// The Cast devices in an mDNS answer. Devices that leave out their
+// address are reached at the address the answer came from.
+pub fn parse_mdns_response(packet: &[u8], sender: IpAddr) -> Vec<CastDevice> {
+    let mut instances = Vec::new();
+    let mut services: HashMap<String, (String, u16)> = HashMap::new();
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut hosts: HashMap<String, Ipv4Addr> = HashMap::new();
+
+    let Some(questions) = read_u16(packet, 4) else {
+        return Vec::new();
+    };
+    // Answers, authority and additional records are all worth reading
+    let count = |at| read_u16(packet, at).map_or(0, usize::from);
+    let records = count(6) + count(8) + count(10);
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        let Some((_, after)) = read_name(packet, pos) else {
+            return Vec::new();
+        };
+        pos = after + 4;
+    }
+    for _ in 0..records {
+        let Some((name, after)) = read_name(packet, pos) else {
+            break;
+        };
+        let (Some(kind), Some(len)) = (read_u16(packet, after), read_u16(packet, after + 8)) else {
+            break;
+        };
+        let start = after + 10;
+        let Some(data) = packet.get(start..start + len as usize) else {
+            break;
+        };
+        pos = start + data.len();
+
+        match kind {
+            TYPE_PTR if name.eq_ignore_ascii_case(SERVICE) => {
+                if let Some((instance, _)) = read_name(packet, start) {
+                    instances.push(instance);
+                }
+            }
+            TYPE_SRV => {
+                if let (Some(port), Some((target, _))) =
+                    (read_u16(packet, start + 4), read_name(packet, start + 6))
+                {
+                    services.insert(name.to_ascii_lowercase(), (target, port));
+                }
+            }
+            TYPE_TXT => {
+                if let Some(friendly) = txt_friendly_name(data) {
+                    names.insert(name.to_ascii_lowercase(), friendly);
+                }
+            }
+            TYPE_A if data.len() == 4 => {
+                hosts.insert(
+                    name.to_ascii_lowercase(),
+                    Ipv4Addr::new(data[0], data[1], data[2], data[3]),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    instances
+        .into_iter()
+        .map(|instance| {
+            let key = instance.to_ascii_lowercase();
+            let (ip, port) = match services.get(&key) {
+                Some((target, port)) => (
+                    hosts
+                        .get(&target.to_ascii_lowercase())
+                        .map_or(sender, |&ip| IpAddr::V4(ip)),
+                    *port,
+                ),
+                None => (sender, DEFAULT_PORT),
+            };
+            let name = names.remove(&key).unwrap_or_else(|| {
+                // Instance names look like "Chromecast-1a2b3c._googlecast._tcp.local"
+                instance.split('.').next().unwrap_or(&instance).to_string()
+            });
+            CastDevice {
+                name,
+                protocol: CastProtocol::Chromecast {
+                    address: SocketAddr::new(ip, port),
+                },
+            }
+        })
+        .collect()
+}
+
+// Find Chromecasts on the local network
+pub fn discover(wait: Duration) -> Result<Vec<CastDevice>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("mDNS failed: {}", e))?;
+    socket
+        .send_to(&mdns_query(), MDNS_ADDR)
+        .map_err(|e| format!("mDNS failed: {}", e))?;
+
+    let deadline = Instant::now() + wait;
+    let mut devices: Vec<CastDevice> = Vec::new();
+    let mut buf = [0u8; 9000];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if left.is_zero() || socket.set_read_timeout(Some(left)).is_err() {
+            break;
+        }
+        let Ok((len, from)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        for device in parse_mdns_response(&buf[..len], from.ip()) {
+            if !devices
+                .iter()
+                .any(|known| known.protocol == device.protocol)
+            {
+                devices.push(device);
+            }
+        }
+    }
+    Ok(devices)
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_string(buf: &mut Vec<u8>, field: u8, text: &str) {
+    buf.push(field << 3 | 2);
+    put_varint(buf, text.len() as u64);
+    buf.extend_from_slice(text.as_bytes());
+}
+
+// A length-prefixed CastMessage protobuf carrying a JSON payload
+pub fn encode_message(source: &str, destination: &str, namespace: &str, payload: &str) -> Vec<u8> {
+    let mut body = vec![0x08, 0x00]; // protocol_version: CASTV2_1_0
+    put_string(&mut body, 2, source);
+    put_string(&mut body, 3, destination);
+    put_string(&mut body, 4, namespace);
+    body.extend_from_slice(&[0x28, 0x00]); // payload_type: STRING
+    put_string(&mut body, 6, payload);
+
+    let mut frame = (body.len() as u32).to_be_bytes().to_vec();
+    frame.extend(body);
+    frame
+}
+
+// The parts of a received CastMessage we act on
+#[derive(Debug, Default, PartialEq)]
+pub struct CastMessage {
+    pub source: String,
+    pub namespace: String,
+    pub payload: String,
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// Decode a CastMessage body (without its length prefix)
+pub fn decode_message(body: &[u8]) -> Option<CastMessage> {
+    let mut message = CastMessage::default();
+    let mut pos = 0;
+    while pos < body.len() {
+        let key = read_varint(body, &mut pos)?;
+        match key & 7 {
+            0 => {
+                read_varint(body, &mut pos)?;
+            }
+            1 => pos += 8,
+            5 => pos += 4,
+            2 => {
+                let len = read_varint(body, &mut pos)? as usize;
+                let bytes = body.get(pos..pos.checked_add(len)?)?;
+                pos += len;
+                let text = String::from_utf8_lossy(bytes).into_owned();
+                match key >> 3 {
+                    2 => message.source = text,
+                    4 => message.namespace = text,
+                    6 => message.payload = text,
+                    _ => {}
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(message)
+}
+
+// The session and transport IDs of a running Default Media Receiver, from
+// a RECEIVER_STATUS payload
+pub fn media_receiver_session(status: &Value) -> Option<(String, String)> {
+    status["status"]["applications"]
+        .as_array()?
+        .iter()
+        .find(|app| app["appId"] == MEDIA_RECEIVER_APP)
+        .and_then(|app| {
+            Some((
+                app["sessionId"].as_str()?.to_string(),
+                app["transportId"].as_str()?.to_string(),
+            ))
+        })
+}
+
+// Receivers want a hint about what they're about to play
+pub fn content_type(url: &str) -> &'static str {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    if path.ends_with(".m3u8") {
+        "application/x-mpegURL"
+    } else if path.ends_with(".aac") || path.ends_with(".aacp") {
+        "audio/aac"
+    } else if path.ends_with(".ogg") || path.ends_with(".oga") || path.ends_with(".opus") {
+        "audio/ogg"
+    } else if path.ends_with(".flac") {
+        "audio/flac"
+    } else {
+        "audio/mpeg"
+    }
+}
+
+// A TLS connection to a device's Cast endpoint
+struct Connection {
+    stream: native_tls::TlsStream<TcpStream>,
+}
+
+impl Connection {
+    fn open(address: SocketAddr) -> Result<Self, String> {
+        let tcp = TcpStream::connect_timeout(&address, REQUEST_TIMEOUT)
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+        let _ = tcp.set_write_timeout(Some(REQUEST_TIMEOUT));
+        let _ = tcp.set_read_timeout(Some(REQUEST_TIMEOUT));
+        // Devices present self-signed certificates
+        let connector = native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()
+            .map_err(|e| format!("TLS setup failed: {}", e))?;
+        let stream = connector
+            .connect(&address.ip().to_string(), tcp)
+            .map_err(|e| format!("TLS handshake failed: {}", e))?;
+        let mut connection = Connection { stream };
+        connection.send(RECEIVER, CONNECTION_NS, json!({ "type": "CONNECT" }))?;
+        Ok(connection)
+    }
+
+    fn send(&mut self, destination: &str, namespace: &str, payload: Value) -> Result<(), String> {
+        let frame = encode_message(SENDER, destination, namespace, &payload.to_string());
+        self.stream
+            .write_all(&frame)
+            .map_err(|e| format!("Failed to send: {}", e))
+    }
+
+    fn receive(&mut self, deadline: Instant) -> Result<CastMessage, String> {
+        let left = deadline
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+            .ok_or("The device didn't answer in time")?;
+        let _ = self.stream.get_ref().set_read_timeout(Some(left));
+
+        let mut len = [0u8; 4];
+        self.stream
+            .read_exact(&mut len)
+            .map_err(|e| format!("Failed to read: {}", e))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_MESSAGE {
+            return Err("The device sent an oversized message".to_string());
+        }
+        let mut body = vec![0u8; len];
+        self.stream
+            .read_exact(&mut body)
+            .map_err(|e| format!("Failed to read: {}", e))?;
+        decode_message(&body).ok_or_else(|| "The device sent a malformed message".to_string())
+    }
+
+    // Read until `pick` recognizes a message, answering the device's
+    // heartbeat pings meanwhile so it doesn't drop the connection
+    fn wait_for<T>(
+        &mut self,
+        deadline: Instant,
+        mut pick: impl FnMut(&str, &Value) -> Option<Result<T, String>>,
+    ) -> Result<T, String> {
+        loop {
+            let message = self.receive(deadline)?;
+            let Ok(payload) = serde_json::from_str::<Value>(&message.payload) else {
+                continue;
+            };
+            if message.namespace == HEARTBEAT_NS && payload["type"] == "PING" {
+                self.send(&message.source, HEARTBEAT_NS, json!({ "type": "PONG" }))?;
+                continue;
+            }
+            if let Some(result) = pick(&message.namespace, &payload) {
+                return result;
+            }
+        }
+    }
+
+    // The running Default Media Receiver, if any, once the device reports
+    // its status for `request_id`
+    fn receiver_status(
+        &mut self,
+        deadline: Instant,
+        request_id: u32,
+    ) -> Result<Option<(String, String)>, String> {
+        self.wait_for(deadline, |namespace, payload| {
+            if namespace != RECEIVER_NS || payload["requestId"] != request_id {
+                return None;
+            }
+            match receiver_error(payload) {
+                Some(e) => Some(Err(e)),
+                None if payload["type"] == "RECEIVER_STATUS" => {
+                    Some(Ok(media_receiver_session(payload)))
+                }
+                None => None,
+            }
+        })
+    }
+}
+
+// Why the receiver turned a request down, if it did
+fn receiver_error(payload: &Value) -> Option<String> {
+    match payload["type"].as_str()? {
+        "LAUNCH_ERROR" => Some(format!(
+            "The media receiver didn't start ({})",
+            payload["reason"].as_str().unwrap_or("no reason given")
+        )),
+        "INVALID_REQUEST" => Some("The device refused the request".to_string()),
+        _ => None,
+    }
+}
+
+// Start the Default Media Receiver on a device and have it play a stream.
+// It keeps playing after we hang up.
+pub fn cast(address: SocketAddr, url: &str, title: &str) -> Result<(), String> {
+    let deadline = Instant::now() + LAUNCH_TIMEOUT;
+    let mut connection = Connection::open(address)?;
+    connection.send(
+        RECEIVER,
+        RECEIVER_NS,
+        json!({ "type": "LAUNCH", "appId": MEDIA_RECEIVER_APP, "requestId": 1 }),
+    )?;
+    // The device may report its status a few times before the app is up
+    let (session_id, transport_id) = connection.wait_for(deadline, |namespace, payload| {
+        if namespace != RECEIVER_NS {
+            return None;
+        }
+        match receiver_error(payload) {
+            Some(e) => Some(Err(e)),
+            None => media_receiver_session(payload).map(Ok),
+        }
+    })?;
+
+    connection.send(&transport_id, CONNECTION_NS, json!({ "type": "CONNECT" }))?;
+    connection.send(
+        &transport_id,
+        MEDIA_NS,
+        json!({
+            "type": "LOAD",
+            "requestId": 2,
+            "sessionId": session_id,
+            "autoplay": true,
+            "media": {
+                "contentId": url,
+                "contentType": content_type(url),
+                "streamType": "LIVE",
+                "metadata": { "metadataType": 0, "title": title },
+            },
+        }),
+    )?;
+    connection.wait_for(deadline, |namespace, payload| {
+        if namespace != MEDIA_NS || payload["requestId"] != 2 {
+            return None;
+        }
+        match payload["type"].as_str() {
+            Some("MEDIA_STATUS") => Some(Ok(())),
+            Some(other) => Some(Err(format!(
+                "The device couldn't play the stream ({})",
+                other
+            ))),
+            None => None,
+        }
+    })
+}
+
+// Close the Default Media Receiver if it's running
+pub fn stop(address: SocketAddr) -> Result<(), String> {
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut connection = Connection::open(address)?;
+    connection.send(
+        RECEIVER,
+        RECEIVER_NS,
+        json!({ "type": "GET_STATUS", "requestId": 1 }),
+    )?;
+    let Some((session_id, _)) = connection.receiver_status(deadline, 1)? else {
+        return Ok(());
+    };
+    connection.send(
+        RECEIVER,
+        RECEIVER_NS,
+        json!({ "type": "STOP", "sessionId": session_id, "requestId": 2 }),
+    )?;
+    connection.receiver_status(deadline, 2).map(|_| ())
+}
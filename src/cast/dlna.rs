@@ -0,0 +1,226 @@
+use super::{CastDevice, CastProtocol, REQUEST_TIMEOUT};
+use std::collections::HashSet;
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+// DLNA renderers (network speakers, TVs, receivers) are found over SSDP
+// and driven through their UPnP AVTransport service
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+// The value of a header in an SSDP response, matched case-insensitively
+pub fn ssdp_header<'a>(response: &'a str, name: &str) -> Option<&'a str> {
+    response.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+// The text between <tag> and </tag>, if there is one
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    Some(xml[start..end].trim())
+}
+
+// Read a renderer's name and AVTransport control URL from its device
+// description; relative URLs are resolved against the description's location
+pub fn parse_device_description(xml: &str, location: &str) -> Option<CastDevice> {
+    let control = xml.split("<service>").skip(1).find_map(|service| {
+        tag_text(service, "serviceType")
+            .filter(|kind| kind.starts_with("urn:schemas-upnp-org:service:AVTransport:"))?;
+        tag_text(service, "controlURL")
+    })?;
+    let base = tag_text(xml, "URLBase").unwrap_or(location);
+    let control_url = reqwest::Url::parse(base).ok()?.join(control).ok()?;
+    let name = tag_text(xml, "friendlyName")
+        .filter(|name| !name.is_empty())
+        .map(xml_unescape)
+        .unwrap_or_else(|| control_url.host_str().unwrap_or("Unknown").to_string());
+    Some(CastDevice {
+        name,
+        protocol: CastProtocol::Dlna {
+            control_url: control_url.to_string(),
+        },
+    })
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// A SOAP request body calling an AVTransport action with the given arguments
+pub fn soap_envelope(action: &str, args: &[(&str, &str)]) -> String {
+    let args: String = args
+        .iter()
+        .map(|(name, value)| format!("<{0}>{1}</{0}>", name, xml_escape(value)))
+        .collect();
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+            r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+            r#"<s:Body><u:{action} xmlns:u="{service}">{args}</u:{action}></s:Body>"#,
+            r#"</s:Envelope>"#
+        ),
+        action = action,
+        service = AV_TRANSPORT,
+        args = args
+    )
+}
+
+// Minimal DIDL-Lite metadata so renderers show the station's name
+fn stream_metadata(url: &str, title: &str) -> String {
+    format!(
+        concat!(
+            r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" "#,
+            r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+            r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/">"#,
+            r#"<item id="0" parentID="-1" restricted="1"><dc:title>{}</dc:title>"#,
+            r#"<upnp:class>object.item.audioItem.audioBroadcast</upnp:class>"#,
+            r#"<res protocolInfo="http-get:*:*:*">{}</res></item></DIDL-Lite>"#
+        ),
+        xml_escape(title),
+        xml_escape(url)
+    )
+}
+
+fn build_client() -> Result<reqwest::Client, String> {
+    // Renderers are on the LAN, never behind the stream proxy
+    reqwest::Client::builder()
+        .no_proxy()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build client: {}", e))
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = Result<T, String>>) -> Result<T, String> {
+    tokio::runtime::Runtime::new()
+        .map_err(|e| e.to_string())?
+        .block_on(future)
+}
+
+async fn send_action(
+    client: &reqwest::Client,
+    control_url: &str,
+    action: &str,
+    args: &[(&str, &str)],
+) -> Result<(), String> {
+    client
+        .post(control_url)
+        .header("Content-Type", r#"text/xml; charset="utf-8""#)
+        .header("SOAPAction", format!("\"{}#{}\"", AV_TRANSPORT, action))
+        .body(soap_envelope(action, args))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("{} failed: {}", action, e))?;
+    Ok(())
+}
+
+// Find renderers on the local network
+pub fn discover(wait: Duration) -> Result<Vec<CastDevice>, String> {
+    let client = build_client()?;
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("SSDP failed: {}", e))?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: {}\r\n\r\n",
+        SSDP_ADDR,
+        wait.as_secs().max(1),
+        AV_TRANSPORT
+    );
+    socket
+        .send_to(search.as_bytes(), SSDP_ADDR)
+        .map_err(|e| format!("SSDP failed: {}", e))?;
+
+    // Collect every answer until the wait is over
+    let deadline = Instant::now() + wait;
+    let mut locations = Vec::new();
+    let mut buf = [0u8; 2048];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        if left.is_zero() || socket.set_read_timeout(Some(left)).is_err() {
+            break;
+        }
+        let Ok((len, _)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        let response = String::from_utf8_lossy(&buf[..len]);
+        if let Some(location) = ssdp_header(&response, "location") {
+            if !locations.iter().any(|known| known == location) {
+                locations.push(location.to_string());
+            }
+        }
+    }
+
+    // A device can answer for several of its services
+    let mut seen = HashSet::new();
+    let devices = block_on(async {
+        let mut devices = Vec::new();
+        for location in &locations {
+            let description = match client.get(location).send().await {
+                Ok(response) => response.text().await.unwrap_or_default(),
+                Err(e) => {
+                    log::warn!("Failed to describe renderer at {}: {}", location, e);
+                    continue;
+                }
+            };
+            if let Some(device) = parse_device_description(&description, location) {
+                devices.push(device);
+            }
+        }
+        Ok(devices)
+    })?;
+    Ok(devices
+        .into_iter()
+        .filter(|device| seen.insert(device.protocol.clone()))
+        .collect())
+}
+
+// Have a renderer play a stream
+pub fn cast(control_url: &str, url: &str, title: &str) -> Result<(), String> {
+    let client = build_client()?;
+    let metadata = stream_metadata(url, title);
+    block_on(async {
+        send_action(
+            &client,
+            control_url,
+            "SetAVTransportURI",
+            &[
+                ("InstanceID", "0"),
+                ("CurrentURI", url),
+                ("CurrentURIMetaData", &metadata),
+            ],
+        )
+        .await?;
+        send_action(
+            &client,
+            control_url,
+            "Play",
+            &[("InstanceID", "0"), ("Speed", "1")],
+        )
+        .await
+    })
+}
+
+pub fn stop(control_url: &str) -> Result<(), String> {
+    let client = build_client()?;
+    block_on(send_action(
+        &client,
+        control_url,
+        "Stop",
+        &[("InstanceID", "0")],
+    ))
+}
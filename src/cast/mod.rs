@@ -0,0 +1,210 @@
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+pub mod chromecast;
+pub mod dlna;
+
+// How long to wait for devices to answer a search
+const DISCOVERY_TIME: Duration = Duration::from_secs(3);
+
+// Devices on the LAN answer quickly or not at all
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How a device is reached and told what to play
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CastProtocol {
+    // Chromecasts and Google/Nest speakers, spoken to over the Cast protocol
+    Chromecast { address: SocketAddr },
+    // DLNA/UPnP renderers; SOAP requests go to their AVTransport endpoint
+    Dlna { control_url: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CastDevice {
+    pub name: String,
+    pub protocol: CastProtocol,
+}
+
+impl CastDevice {
+    // The device's name with what kind of device it is, for the picker
+    pub fn label(&self) -> String {
+        let kind = match self.protocol {
+            CastProtocol::Chromecast { .. } => "Chromecast",
+            CastProtocol::Dlna { .. } => "DLNA",
+        };
+        format!("{} ({})", self.name, kind)
+    }
+}
+
+// Requests for the cast worker; devices can take seconds to answer, so
+// they never run on the UI thread
+enum Job {
+    Cast {
+        device: CastDevice,
+        url: String,
+        title: String,
+    },
+    Stop(CastDevice),
+}
+
+// A finished cast request and how it went
+pub enum CastEvent {
+    Started {
+        device: CastDevice,
+        title: String,
+        result: Result<(), String>,
+    },
+    Stopped {
+        device: CastDevice,
+        result: Result<(), String>,
+    },
+}
+
+// Devices found so far, the one highlighted in the picker and the one
+// currently playing a station
+#[derive(Default)]
+pub struct CastSession {
+    pub devices: Vec<CastDevice>,
+    pub selected: usize,
+    pub target: Option<CastDevice>,
+    discovery: Option<Receiver<Result<Vec<CastDevice>, String>>>,
+    jobs: Option<(Sender<Job>, Receiver<CastEvent>)>,
+}
+
+impl CastSession {
+    // Search the network in the background; poll picks up the result
+    pub fn start_discovery(&mut self) {
+        if self.discovery.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(discover(DISCOVERY_TIME));
+        });
+        self.discovery = Some(rx);
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.discovery.is_some()
+    }
+
+    // The result of a finished search: the number of devices found, or why it failed
+    pub fn poll(&mut self) -> Option<Result<usize, String>> {
+        let result = self.discovery.as_ref()?.try_recv().ok()?;
+        self.discovery = None;
+        Some(result.map(|devices| {
+            self.devices = devices;
+            self.selected = self.selected.min(self.devices.len().saturating_sub(1));
+            self.devices.len()
+        }))
+    }
+
+    pub fn selected_device(&self) -> Option<&CastDevice> {
+        self.devices.get(self.selected)
+    }
+
+    // Have a device play a stream; poll_job reports how it went
+    pub fn request_cast(&mut self, device: CastDevice, url: &str, title: &str) {
+        self.send_job(Job::Cast {
+            device,
+            url: url.to_string(),
+            title: title.to_string(),
+        });
+    }
+
+    pub fn request_stop(&mut self, device: CastDevice) {
+        self.send_job(Job::Stop(device));
+    }
+
+    pub fn poll_job(&mut self) -> Option<CastEvent> {
+        self.jobs.as_ref()?.1.try_recv().ok()
+    }
+
+    // Jobs run one at a time and in order, so a stop sent right after a
+    // cast ends it instead of racing it
+    fn send_job(&mut self, job: Job) {
+        let (jobs, _) = self.jobs.get_or_insert_with(|| {
+            let (job_tx, job_rx) = mpsc::channel::<Job>();
+            let (event_tx, event_rx) = mpsc::channel();
+            thread::spawn(move || {
+                for job in job_rx {
+                    let event = match job {
+                        Job::Cast { device, url, title } => CastEvent::Started {
+                            result: cast(&device, &url, &title),
+                            device,
+                            title,
+                        },
+                        Job::Stop(device) => CastEvent::Stopped {
+                            result: stop(&device),
+                            device,
+                        },
+                    };
+                    if event_tx.send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+            (job_tx, event_rx)
+        });
+        if jobs.send(job).is_err() {
+            log::warn!("Cast worker stopped unexpectedly");
+            self.jobs = None;
+        }
+    }
+}
+
+fn ensure_online() -> Result<(), String> {
+    if crate::offline::is_offline() {
+        return Err(crate::offline::OFFLINE_MESSAGE.to_string());
+    }
+    Ok(())
+}
+
+// Find Chromecasts and DLNA renderers on the local network. Both searches
+// run at once; either failing alone still shows what the other found.
+pub fn discover(wait: Duration) -> Result<Vec<CastDevice>, String> {
+    ensure_online()?;
+    let chromecasts = thread::spawn(move || chromecast::discover(wait));
+    let renderers = dlna::discover(wait);
+    let chromecasts = chromecasts
+        .join()
+        .unwrap_or_else(|_| Err("Chromecast search crashed".to_string()));
+
+    match (chromecasts, renderers) {
+        (Err(chromecast_error), Err(dlna_error)) => {
+            Err(format!("{}; {}", chromecast_error, dlna_error))
+        }
+        (chromecasts, renderers) => {
+            for error in [&chromecasts, &renderers]
+                .into_iter()
+                .filter_map(|r| r.as_ref().err())
+            {
+                log::warn!("Cast device search: {}", error);
+            }
+            let mut devices = chromecasts.unwrap_or_default();
+            devices.extend(renderers.unwrap_or_default());
+            Ok(devices)
+        }
+    }
+}
+
+// Have a device play a stream
+pub fn cast(device: &CastDevice, url: &str, title: &str) -> Result<(), String> {
+    ensure_online()?;
+    match &device.protocol {
+        CastProtocol::Chromecast { address } => chromecast::cast(*address, url, title),
+        CastProtocol::Dlna { control_url } => dlna::cast(control_url, url, title),
+    }
+    .map_err(|e| format!("{}: {}", device.name, e))
+}
+
+pub fn stop(device: &CastDevice) -> Result<(), String> {
+    ensure_online()?;
+    match &device.protocol {
+        CastProtocol::Chromecast { address } => chromecast::stop(*address),
+        CastProtocol::Dlna { control_url } => dlna::stop(control_url),
+    }
+    .map_err(|e| format!("{}: {}", device.name, e))
+}
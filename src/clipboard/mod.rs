@@ -0,0 +1,88 @@
+use base64::Engine;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Clipboard tools tried in order, with the arguments that make them read stdin
+#[cfg(target_os = "macos")]
+const COPY_COMMANDS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+#[cfg(windows)]
+const COPY_COMMANDS: &[(&str, &[&str])] = &[("clip", &[])];
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY_COMMANDS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+
+// Clipboard tools tried in order, with the arguments that make them print
+// the clipboard to stdout
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[(&str, &[&str])] = &[("pbpaste", &[])];
+#[cfg(windows)]
+const PASTE_COMMANDS: &[(&str, &[&str])] =
+    &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE_COMMANDS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+];
+
+// Copy text to the system clipboard. Falls back to the OSC 52 terminal
+// escape (which also works over SSH) when no clipboard tool is installed.
+// Returns how the text was copied, for the status message.
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    for (program, args) in COPY_COMMANDS {
+        if pipe_to(program, args, text).is_ok() {
+            return Ok(program);
+        }
+    }
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)
+        .and_then(|_| stdout.flush())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok("terminal")
+}
+
+// Read text from the system clipboard. Unlike copying there's no terminal
+// fallback, so this needs one of the clipboard tools.
+pub fn paste() -> Result<String, String> {
+    for (program, args) in PASTE_COMMANDS {
+        let output = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        if let Ok(output) = output {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
+    }
+    Err("No clipboard tool found".to_string())
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
@@ -1,5 +1,8 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Result};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::path::Path;
 use std::time::SystemTime;
 
 #[derive(Clone, Debug)]
@@ -9,16 +12,235 @@ pub struct Station {
     pub url: String,
     pub favorite: bool,
     pub description: Option<String>,
+    pub mpv_args: Option<String>, // Extra command-line arguments passed to mpv
+    pub pinned: bool,             // Always listed first, independent of favorite
+    pub last_check_status: Option<String>, // CHECK_OK or why the last reachability check failed
+    pub last_check_time: Option<i64>, // Unix timestamp of that check
 }
 
-pub struct StationStats {
+// last_check_status of a station that answered with audio
+pub const CHECK_OK: &str = "ok";
+
+impl Station {
+    // Whether the last reachability check found the station dead
+    pub fn check_failed(&self) -> bool {
+        self.last_check_status
+            .as_deref()
+            .is_some_and(|status| status != CHECK_OK)
+    }
+}
+
+// HTTP basic auth login for a protected stream. Kept out of Station so
+// passwords don't travel through exports and the UI; undo keeps its own copy.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+// Columns selected whenever a full Station is loaded
+const STATION_COLUMNS: &str =
+    "id, name, url, favorite, description, mpv_args, pinned, last_check_status, last_check_time";
+
+// Build a Station from a row selected with STATION_COLUMNS
+fn station_from_row(row: &rusqlite::Row) -> rusqlite::Result<Station> {
+    Ok(Station {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        favorite: row.get::<_, i32>(3)? != 0,
+        description: row.get(4)?,
+        mpv_args: row.get(5)?,
+        pinned: row.get::<_, i32>(6)? != 0,
+        last_check_status: row.get(7)?,
+        last_check_time: row.get(8)?,
+    })
+}
+
+// Add a column to an existing table if it isn't there yet (simple schema migration)
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .flatten()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// A single play of a station, with the song that was on when it started
+pub struct HistoryEntry {
+    #[allow(dead_code)]
+    pub id: i64,
     #[allow(dead_code)]
+    pub station_id: i32,
+    pub station_name: String,
+    pub started_at: i64,      // Unix timestamp when playback started
+    pub song: Option<String>, // Stream title, backfilled once metadata arrives
+}
+
+pub struct StationStats {
     pub station_id: i32,
     pub total_play_time: i64,     // Total play time in seconds
     pub last_played: Option<i64>, // Unix timestamp of last play
 }
 
+// Everything the UI shows from the stats tables, loaded in one go so that
+// drawing a frame never has to touch the database
+#[derive(Default)]
+pub struct StatsSnapshot {
+    pub station_stats: HashMap<i32, StationStats>,
+    pub station_ids_by_url: HashMap<String, i32>,
+    pub top_stations: Vec<(Station, i64)>,
+    pub top_stations_week: Vec<(Station, i64)>,
+    pub top_stations_month: Vec<(Station, i64)>,
+    pub recent_history: Vec<HistoryEntry>,
+    pub daily_listening: Vec<u64>, // Seconds listened per day, oldest first
+}
+
+// Days covered by the listening sparkline, and how many of them need some
+// listening before it says anything worth a row of screen
+pub const LISTENING_TREND_DAYS: usize = 14;
+pub const MIN_ACTIVE_DAYS: usize = 3;
+
+// Time window for the top stations ranking
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TopStationsPeriod {
+    #[default]
+    AllTime,
+    Week,
+    Month,
+}
+
+impl TopStationsPeriod {
+    // The period after this one when cycling the view
+    pub fn next(self) -> Self {
+        match self {
+            TopStationsPeriod::AllTime => TopStationsPeriod::Week,
+            TopStationsPeriod::Week => TopStationsPeriod::Month,
+            TopStationsPeriod::Month => TopStationsPeriod::AllTime,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TopStationsPeriod::AllTime => "All Time",
+            TopStationsPeriod::Week => "Past Week",
+            TopStationsPeriod::Month => "Past Month",
+        }
+    }
+
+    // Earliest play start counted by this period, or None for all time
+    pub fn since(self, now: i64) -> Option<i64> {
+        match self {
+            TopStationsPeriod::AllTime => None,
+            TopStationsPeriod::Week => Some(now - 7 * 86400),
+            TopStationsPeriod::Month => Some(now - 30 * 86400),
+        }
+    }
+}
+
+impl StatsSnapshot {
+    pub fn load(conn: &Connection) -> Result<Self, Box<dyn Error>> {
+        let mut stmt =
+            conn.prepare("SELECT station_id, total_play_time, last_played FROM station_stats")?;
+        let station_stats = stmt
+            .query_map([], |row| {
+                Ok(StationStats {
+                    station_id: row.get(0)?,
+                    total_play_time: row.get(1)?,
+                    last_played: row.get(2)?,
+                })
+            })?
+            .map(|stats| stats.map(|stats| (stats.station_id, stats)))
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        // With duplicate URLs the oldest station wins, matching get_station_by_url
+        let mut station_ids_by_url = HashMap::new();
+        let mut stmt = conn.prepare("SELECT id, url FROM stations ORDER BY id")?;
+        for row in stmt.query_map([], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?))
+        })? {
+            let (id, url) = row?;
+            station_ids_by_url.entry(url).or_insert(id);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let top_since = |period: TopStationsPeriod| match period.since(now) {
+            Some(since) => get_top_stations_since(conn, since, 5),
+            None => get_top_stations(conn, 5),
+        };
+
+        Ok(StatsSnapshot {
+            station_stats,
+            station_ids_by_url,
+            top_stations: top_since(TopStationsPeriod::AllTime)?,
+            top_stations_week: top_since(TopStationsPeriod::Week)?,
+            top_stations_month: top_since(TopStationsPeriod::Month)?,
+            recent_history: get_recent_history(conn, 10)?,
+            daily_listening: listening_by_day(
+                conn,
+                now,
+                chrono::Local::now().offset().local_minus_utc() as i64,
+                LISTENING_TREND_DAYS,
+            )?,
+        })
+    }
+
+    // Whether there's enough history for the listening sparkline
+    pub fn has_listening_trend(&self) -> bool {
+        self.daily_listening
+            .iter()
+            .filter(|&&secs| secs > 0)
+            .count()
+            >= MIN_ACTIVE_DAYS
+    }
+
+    pub fn top_stations_for(&self, period: TopStationsPeriod) -> &[(Station, i64)] {
+        match period {
+            TopStationsPeriod::AllTime => &self.top_stations,
+            TopStationsPeriod::Week => &self.top_stations_week,
+            TopStationsPeriod::Month => &self.top_stations_month,
+        }
+    }
+
+    pub fn stats_for(&self, station_id: i32) -> Option<&StationStats> {
+        self.station_stats.get(&station_id)
+    }
+
+    pub fn stats_for_url(&self, url: &str) -> Option<&StationStats> {
+        self.station_ids_by_url
+            .get(url)
+            .and_then(|id| self.stats_for(*id))
+    }
+}
+
+// A station for the first-run seed of an empty library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedStation {
+    pub name: String,
+    pub url: String,
+    pub description: Option<String>,
+}
+
 pub fn init_db(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    init_db_seeded(conn, &[])
+}
+
+// Set up the schema and, for an empty library, add the starter stations.
+// Without any, the built-in list is used.
+pub fn init_db_seeded(conn: &Connection, starter: &[SeedStation]) -> Result<(), Box<dyn Error>> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS stations (
             id INTEGER PRIMARY KEY,
@@ -30,6 +252,19 @@ pub fn init_db(conn: &Connection) -> Result<(), Box<dyn Error>> {
         [],
     )?;
 
+    // Columns added after the original schema
+    ensure_column(conn, "stations", "mpv_args", "TEXT")?;
+    ensure_column(conn, "stations", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    // Basic auth for private streams, stored in plain text
+    ensure_column(conn, "stations", "username", "TEXT")?;
+    ensure_column(conn, "stations", "password", "TEXT")?;
+    // Fallback stream URLs, one per line, and whichever of them last worked
+    ensure_column(conn, "stations", "mirror_urls", "TEXT")?;
+    ensure_column(conn, "stations", "working_url", "TEXT")?;
+    // Result of the last reachability check (pre-flight, detail page or a failed play)
+    ensure_column(conn, "stations", "last_check_status", "TEXT")?;
+    ensure_column(conn, "stations", "last_check_time", "INTEGER")?;
+
     // Create stats table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS station_stats (
@@ -41,8 +276,64 @@ pub fn init_db(conn: &Connection) -> Result<(), Box<dyn Error>> {
         [],
     )?;
 
+    // Create play history table (one row per time a station is started)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS play_history (
+            id INTEGER PRIMARY KEY,
+            station_id INTEGER NOT NULL,
+            started_at INTEGER NOT NULL,
+            song TEXT,
+            FOREIGN KEY (station_id) REFERENCES stations(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    // Listening time per entry, for rankings over a time window
+    ensure_column(
+        conn,
+        "play_history",
+        "play_time",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    // Create settings table (simple key/value store for app preferences)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Free-form tags, any number per station
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS station_tags (
+            station_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (station_id, tag),
+            FOREIGN KEY (station_id) REFERENCES stations(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Directory stations the user never wants to see again
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS hidden_stations (
+            url TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            hidden_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     let count: i32 = conn.query_row("SELECT COUNT(*) FROM stations", [], |row| row.get(0))?;
-    if count == 0 {
+    if count == 0 && !starter.is_empty() {
+        for station in starter {
+            conn.execute(
+                "INSERT INTO stations (name, url, description) VALUES (?1, ?2, ?3)",
+                params![station.name, station.url, station.description],
+            )?;
+        }
+    } else if count == 0 {
         let stations = vec![
             // Original stations with descriptions
             (
@@ -78,19 +369,11 @@ pub fn init_db(conn: &Connection) -> Result<(), Box<dyn Error>> {
 }
 
 pub fn load_stations(conn: &Connection) -> Result<Vec<Station>, Box<dyn Error>> {
-    // Remove any duplicate URLs before loading stations
-    remove_duplicate_urls(conn)?;
-
-    let mut stmt = conn.prepare("SELECT id, name, url, favorite, description FROM stations")?;
-    let station_iter = stmt.query_map([], |row| {
-        Ok(Station {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            url: row.get(2)?,
-            favorite: row.get::<_, i32>(3)? != 0,
-            description: row.get(4)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM stations ORDER BY pinned DESC, id",
+        STATION_COLUMNS
+    ))?;
+    let station_iter = stmt.query_map([], station_from_row)?;
     let mut stations = Vec::new();
     for station in station_iter {
         stations.push(station?);
@@ -98,6 +381,58 @@ pub fn load_stations(conn: &Connection) -> Result<Vec<Station>, Box<dyn Error>>
     Ok(stations)
 }
 
+pub fn get_station_by_id(
+    conn: &Connection,
+    station_id: i32,
+) -> Result<Option<Station>, Box<dyn Error>> {
+    let station = conn
+        .query_row(
+            &format!("SELECT {} FROM stations WHERE id = ?1", STATION_COLUMNS),
+            params![station_id],
+            station_from_row,
+        )
+        .optional()?;
+    Ok(station)
+}
+
+// Look up a station by stream URL; with duplicates, the oldest one wins
+pub fn get_station_by_url(conn: &Connection, url: &str) -> Result<Option<Station>, Box<dyn Error>> {
+    let station = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM stations WHERE url = ?1 ORDER BY id LIMIT 1",
+                STATION_COLUMNS
+            ),
+            params![url],
+            station_from_row,
+        )
+        .optional()?;
+    Ok(station)
+}
+
+// Remember how a station's reachability check went: Ok, or the error
+pub fn record_check(
+    conn: &Connection,
+    station_id: i32,
+    result: Result<(), &str>,
+    checked_at: i64,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE stations SET last_check_status = ?1, last_check_time = ?2 WHERE id = ?3",
+        params![result.err().unwrap_or(CHECK_OK), checked_at, station_id],
+    )?;
+    Ok(())
+}
+
+// Pin a station to the top of the list, or unpin it
+pub fn set_pinned(conn: &Connection, station_id: i32, pinned: bool) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE stations SET pinned = ?1 WHERE id = ?2",
+        params![pinned as i32, station_id],
+    )?;
+    Ok(())
+}
+
 pub fn toggle_favorite(
     conn: &Connection,
     station_id: i32,
@@ -110,6 +445,34 @@ pub fn toggle_favorite(
     Ok(())
 }
 
+// Favorite every station that has been played for at least min_seconds.
+// Returns how many stations were newly marked.
+pub fn favorite_played_stations(
+    conn: &Connection,
+    min_seconds: i64,
+) -> Result<usize, Box<dyn Error>> {
+    let updated = conn.execute(
+        "UPDATE stations SET favorite = 1
+         WHERE favorite = 0
+           AND id IN (SELECT station_id FROM station_stats WHERE total_play_time >= ?1)",
+        params![min_seconds],
+    )?;
+    Ok(updated)
+}
+
+// If `err` means the database can't be written to (read-only file or full
+// disk), describe it for the warning banner. Other errors give None.
+pub fn write_failure_message(err: &(dyn Error + 'static)) -> Option<&'static str> {
+    match err.downcast_ref::<rusqlite::Error>()? {
+        rusqlite::Error::SqliteFailure(e, _) => match e.code {
+            rusqlite::ErrorCode::ReadOnly => Some("Database is read-only — changes won't be saved"),
+            rusqlite::ErrorCode::DiskFull => Some("Disk is full — changes won't be saved"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub fn add_station(
     conn: &Connection,
     name: &str,
@@ -128,6 +491,58 @@ pub fn add_station(
 
 pub fn delete_station(conn: &Connection, station_id: i32) -> Result<(), Box<dyn Error>> {
     conn.execute("DELETE FROM stations WHERE id = ?1", params![station_id])?;
+    // Foreign keys aren't enforced, so clean up the stats row ourselves
+    conn.execute(
+        "DELETE FROM station_stats WHERE station_id = ?1",
+        params![station_id],
+    )?;
+    conn.execute(
+        "DELETE FROM station_tags WHERE station_id = ?1",
+        params![station_id],
+    )?;
+    // The password stays in the OS keyring so undo can bring the login back;
+    // forget_station_secret drops it once that's no longer possible
+    Ok(())
+}
+
+// Remove a deleted station's password from the OS keyring
+pub fn forget_station_secret(station_id: i32) {
+    #[cfg(feature = "keyring")]
+    if let Err(e) = crate::secrets::delete(station_id) {
+        log::warn!("Could not remove the password from the OS keyring: {}", e);
+    }
+    #[cfg(not(feature = "keyring"))]
+    let _ = station_id;
+}
+
+// Re-insert a previously deleted station under its original id
+pub fn restore_station(conn: &Connection, station: &Station) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "INSERT INTO stations (id, name, url, favorite, description, mpv_args, pinned)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            station.id,
+            station.name,
+            station.url,
+            station.favorite as i32,
+            station.description,
+            station.mpv_args,
+            station.pinned as i32
+        ],
+    )?;
+    Ok(())
+}
+
+// Put back a station's stats exactly as they were
+pub fn restore_station_stats(
+    conn: &Connection,
+    stats: &StationStats,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "INSERT OR REPLACE INTO station_stats (station_id, total_play_time, last_played)
+         VALUES (?1, ?2, ?3)",
+        params![stats.station_id, stats.total_play_time, stats.last_played],
+    )?;
     Ok(())
 }
 
@@ -137,14 +552,795 @@ pub fn update_station(
     name: &str,
     url: &str,
     description: Option<&str>,
+    mpv_args: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE stations SET name = ?1, url = ?2, description = ?3, mpv_args = ?4 WHERE id = ?5",
+        params![name, url, description, mpv_args, station_id],
+    )?;
+    Ok(())
+}
+
+// Split a comma-separated tag list, dropping blanks and repeats (ignoring case)
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+pub fn get_station_tags(conn: &Connection, station_id: i32) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag FROM station_tags WHERE station_id = ?1 ORDER BY tag COLLATE NOCASE",
+    )?;
+    let tags = stmt
+        .query_map(params![station_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(tags)
+}
+
+// Replace a station's tags
+pub fn set_station_tags(
+    conn: &Connection,
+    station_id: i32,
+    tags: &[String],
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "DELETE FROM station_tags WHERE station_id = ?1",
+        params![station_id],
+    )?;
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO station_tags (station_id, tag) VALUES (?1, ?2)",
+            params![station_id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+// Every tag in use, alphabetically
+pub fn get_all_tags(conn: &Connection) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut stmt =
+        conn.prepare("SELECT DISTINCT tag FROM station_tags ORDER BY tag COLLATE NOCASE")?;
+    let tags = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(tags)
+}
+
+// Every tag in use with how many stations carry it, alphabetically
+pub fn get_tag_counts(conn: &Connection) -> Result<Vec<(String, usize)>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag, COUNT(*) FROM station_tags GROUP BY tag ORDER BY tag COLLATE NOCASE",
+    )?;
+    let counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(counts)
+}
+
+pub fn get_stations_with_tag(conn: &Connection, tag: &str) -> Result<Vec<Station>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM stations
+         WHERE id IN (SELECT station_id FROM station_tags WHERE tag = ?1)
+         ORDER BY name COLLATE NOCASE",
+        STATION_COLUMNS
+    ))?;
+    let stations = stmt
+        .query_map(params![tag], station_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(stations)
+}
+
+// The login stored for a station, if it has a username
+pub fn get_station_credentials(
+    conn: &Connection,
+    station_id: i32,
+) -> Result<Option<Credentials>, Box<dyn Error>> {
+    let row: Option<(Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT username, password FROM stations WHERE id = ?1",
+            params![station_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let (username, password) = match row {
+        Some((Some(username), password)) if !username.is_empty() => (username, password),
+        _ => return Ok(None),
+    };
+
+    // A password left in the database (saved before the keyring was
+    // available, or when it failed) wins over the keyring
+    // A locked or missing keyring leaves the stream without a password
+    // rather than failing whatever needed the login
+    #[cfg(feature = "keyring")]
+    let password = match password {
+        Some(password) => Some(password),
+        None => crate::secrets::get(station_id).unwrap_or_else(|e| {
+            log::warn!("Could not read the OS keyring: {}", e);
+            None
+        }),
+    };
+
+    Ok(Some(Credentials {
+        username,
+        password: password.unwrap_or_default(),
+    }))
+}
+
+// Store or clear (with None) a station's login. With the keyring feature
+// the password goes to the OS keyring and only the username is kept here.
+pub fn set_station_credentials(
+    conn: &Connection,
+    station_id: i32,
+    credentials: Option<&Credentials>,
+) -> Result<(), Box<dyn Error>> {
+    #[cfg(not(feature = "keyring"))]
+    let stored_password = credentials.map(|c| c.password.as_str());
+
+    #[cfg(feature = "keyring")]
+    let stored_password = {
+        let saved = match credentials {
+            Some(c) => crate::secrets::set(station_id, &c.password),
+            None => crate::secrets::delete(station_id),
+        };
+        match saved {
+            Ok(()) => None,
+            // No keyring service running; keep the password in the database
+            Err(e) => {
+                log::warn!("Could not use the OS keyring: {}", e);
+                credentials.map(|c| c.password.as_str())
+            }
+        }
+    };
+
+    conn.execute(
+        "UPDATE stations SET username = ?1, password = ?2 WHERE id = ?3",
+        params![
+            credentials.map(|c| c.username.as_str()),
+            stored_password,
+            station_id
+        ],
+    )?;
+    Ok(())
+}
+
+// Split mirror URLs typed as a list separated by spaces, commas or newlines
+pub fn parse_mirror_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for url in text.split(|c: char| c == ',' || c.is_whitespace()) {
+        if !url.is_empty() && !urls.iter().any(|known| known == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+// A station's fallback stream URLs, tried in order when the main one fails
+pub fn get_station_mirrors(
+    conn: &Connection,
+    station_id: i32,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mirrors: Option<String> = conn
+        .query_row(
+            "SELECT mirror_urls FROM stations WHERE id = ?1",
+            params![station_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(mirrors
+        .as_deref()
+        .map(parse_mirror_urls)
+        .unwrap_or_default())
+}
+
+// Replace a station's mirrors, forgetting which one worked last if they changed
+pub fn set_station_mirrors(
+    conn: &Connection,
+    station_id: i32,
+    mirrors: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let stored = Some(mirrors.join("\n")).filter(|text| !text.is_empty());
+    conn.execute(
+        "UPDATE stations
+         SET working_url = CASE WHEN mirror_urls IS ?1 THEN working_url END,
+             mirror_urls = ?1
+         WHERE id = ?2",
+        params![stored, station_id],
+    )?;
+    Ok(())
+}
+
+// The stream URL (main or mirror) a station last played from
+pub fn get_working_url(
+    conn: &Connection,
+    station_id: i32,
+) -> Result<Option<String>, Box<dyn Error>> {
+    Ok(conn
+        .query_row(
+            "SELECT working_url FROM stations WHERE id = ?1",
+            params![station_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+pub fn set_working_url(
+    conn: &Connection,
+    station_id: i32,
+    url: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE stations SET working_url = ?1 WHERE id = ?2",
+        params![url, station_id],
+    )?;
+    Ok(())
+}
+
+// The order to try a station's stream URLs in: the one that worked last,
+// then the main URL, then the mirrors as listed
+pub fn stream_url_order(url: &str, mirrors: &[String], working: Option<&str>) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    let known = std::iter::once(url).chain(mirrors.iter().map(String::as_str));
+    let working = working.filter(|working| known.clone().any(|url| url == *working));
+    for candidate in working.into_iter().chain(known) {
+        if !order.iter().any(|url| url == candidate) {
+            order.push(candidate.to_string());
+        }
+    }
+    order
+}
+
+// Play history functions
+
+pub fn add_history_entry(
+    conn: &Connection,
+    station_id: i32,
+    started_at: i64,
+    song: Option<&str>,
+) -> Result<i64, Box<dyn Error>> {
+    conn.execute(
+        "INSERT INTO play_history (station_id, started_at, song) VALUES (?1, ?2, ?3)",
+        params![station_id, started_at, song],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// Fill in the song for an entry whose metadata arrived after playback started
+pub fn update_history_song(
+    conn: &Connection,
+    entry_id: i64,
+    song: &str,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "UPDATE play_history SET song = ?1 WHERE id = ?2",
+        params![song, entry_id],
+    )?;
+    Ok(())
+}
+
+// Add listening time to a history entry
+pub fn add_history_play_time(
+    conn: &Connection,
+    entry_id: i64,
+    seconds: i64,
 ) -> Result<(), Box<dyn Error>> {
     conn.execute(
-        "UPDATE stations SET name = ?1, url = ?2, description = ?3 WHERE id = ?4",
-        params![name, url, description, station_id],
+        "UPDATE play_history SET play_time = play_time + ?1 WHERE id = ?2",
+        params![seconds, entry_id],
+    )?;
+    Ok(())
+}
+
+// Seconds listened on each of the last `days` days, oldest first and
+// ending with today. Days start at midnight `utc_offset` seconds from UTC.
+pub fn listening_by_day(
+    conn: &Connection,
+    now: i64,
+    utc_offset: i64,
+    days: usize,
+) -> Result<Vec<u64>, Box<dyn Error>> {
+    let today = (now + utc_offset).div_euclid(86400);
+    let first_day = today - days as i64 + 1;
+    let mut totals = vec![0u64; days];
+
+    let mut stmt = conn.prepare(
+        "SELECT (started_at + ?1) / 86400 AS day, SUM(play_time)
+         FROM play_history
+         WHERE started_at + ?1 >= ?2 * 86400
+         GROUP BY day",
+    )?;
+    let rows = stmt.query_map(params![utc_offset, first_day], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+    })?;
+    for row in rows {
+        let (day, seconds) = row?;
+        if let Some(total) = usize::try_from(day - first_day)
+            .ok()
+            .and_then(|index| totals.get_mut(index))
+        {
+            *total = seconds.max(0) as u64;
+        }
+    }
+    Ok(totals)
+}
+
+pub fn get_recent_history(
+    conn: &Connection,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT h.id, h.station_id, s.name, h.started_at, h.song
+         FROM play_history h
+         JOIN stations s ON s.id = h.station_id
+         ORDER BY h.started_at DESC, h.id DESC
+         LIMIT ?1",
+    )?;
+
+    let entries = stmt.query_map(params![limit as i64], |row| {
+        Ok(HistoryEntry {
+            id: row.get(0)?,
+            station_id: row.get(1)?,
+            station_name: row.get(2)?,
+            started_at: row.get(3)?,
+            song: row.get(4)?,
+        })
+    })?;
+
+    let mut history = Vec::new();
+    for entry in entries {
+        history.push(entry?);
+    }
+    Ok(history)
+}
+
+// The latest plays of one station, newest first
+pub fn get_station_history(
+    conn: &Connection,
+    station_id: i32,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT h.id, h.station_id, s.name, h.started_at, h.song
+         FROM play_history h
+         JOIN stations s ON s.id = h.station_id
+         WHERE h.station_id = ?1
+         ORDER BY h.started_at DESC, h.id DESC
+         LIMIT ?2",
     )?;
+
+    let entries = stmt.query_map(params![station_id, limit as i64], |row| {
+        Ok(HistoryEntry {
+            id: row.get(0)?,
+            station_id: row.get(1)?,
+            station_name: row.get(2)?,
+            started_at: row.get(3)?,
+            song: row.get(4)?,
+        })
+    })?;
+
+    let mut history = Vec::new();
+    for entry in entries {
+        history.push(entry?);
+    }
+    Ok(history)
+}
+
+// How many times a station has been played long enough to count
+pub fn count_station_plays(conn: &Connection, station_id: i32) -> Result<usize, Box<dyn Error>> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM play_history WHERE station_id = ?1",
+        params![station_id],
+        |row| row.get(0),
+    )?;
+    Ok(count as usize)
+}
+
+// Backup and restore helpers
+
+// Flush any write-ahead log into the main database file so it can be copied safely
+pub fn checkpoint(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
     Ok(())
 }
 
+// Check that a file is a readable SQLite database with a usable stations table
+pub fn validate_database_file(path: &Path) -> Result<(), Box<dyn Error>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let integrity: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(format!("Database integrity check failed: {}", integrity).into());
+    }
+
+    let mut stmt = conn.prepare("PRAGMA table_info(stations)")?;
+    let columns: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<_, _>>()?;
+
+    for required in ["id", "name", "url", "favorite", "description"] {
+        if !columns.iter().any(|c| c == required) {
+            return Err(format!("Not a radio_cli database (stations.{} missing)", required).into());
+        }
+    }
+
+    Ok(())
+}
+
+// JSON export/import of the full library
+
+// Version of the JSON document produced by export_json
+const JSON_EXPORT_VERSION: i64 = 1;
+
+// Counts reported after a JSON import
+pub struct JsonImportSummary {
+    pub added: usize,
+    pub merged: usize,
+    pub settings: usize,
+}
+
+// What to do when an imported station has the same URL as a saved one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImportConflict {
+    // Keep the saved name/description/mpv args
+    #[default]
+    Skip,
+    // Replace the saved name/description/mpv args with the imported ones
+    Overwrite,
+    // Add the imported station as a second entry
+    Keep,
+}
+
+impl ImportConflict {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "skip" => Some(ImportConflict::Skip),
+            "overwrite" => Some(ImportConflict::Overwrite),
+            "keep" => Some(ImportConflict::Keep),
+            _ => None,
+        }
+    }
+}
+
+// Stations as a JSON array, for --list --json
+pub fn stations_json(stations: &[Station]) -> Value {
+    Value::Array(
+        stations
+            .iter()
+            .map(|station| {
+                json!({
+                    "id": station.id,
+                    "name": station.name,
+                    "url": station.url,
+                    "favorite": station.favorite,
+                    "description": station.description,
+                })
+            })
+            .collect(),
+    )
+}
+
+// Export stations (with their stats) and settings as a JSON document
+pub fn export_json(conn: &Connection) -> Result<Value, Box<dyn Error>> {
+    let mut stations = Vec::new();
+    for station in load_stations(conn)? {
+        let stats = get_station_stats(conn, station.id)?;
+        stations.push(json!({
+            "name": station.name,
+            "url": station.url,
+            "favorite": station.favorite,
+            "pinned": station.pinned,
+            "description": station.description,
+            "mpv_args": station.mpv_args,
+            "mirrors": get_station_mirrors(conn, station.id)?,
+            "total_play_time": stats.as_ref().map_or(0, |s| s.total_play_time),
+            "last_played": stats.and_then(|s| s.last_played),
+        }));
+    }
+
+    let mut settings = serde_json::Map::new();
+    let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    for row in rows {
+        let (key, value) = row?;
+        settings.insert(key, Value::String(value));
+    }
+
+    Ok(json!({
+        "version": JSON_EXPORT_VERSION,
+        "stations": stations,
+        "settings": settings,
+    }))
+}
+
+// The station entries of a JSON library document
+fn json_stations(doc: &Value) -> Result<&Vec<Value>, Box<dyn Error>> {
+    Ok(doc
+        .get("stations")
+        .and_then(Value::as_array)
+        .ok_or("Invalid library file: missing \"stations\" array")?)
+}
+
+// A stream URL reduced to what identifies it, so that "HTTP://Host:80/live/"
+// and "http://host/live" count as the same station: scheme and host are
+// lowercased and the default port, fragment and trailing slash dropped
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            let mut normalized = parsed.to_string();
+            if normalized.ends_with('/') && parsed.query().is_none() {
+                normalized.pop();
+            }
+            normalized
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+// Saved station ids by normalized URL; with duplicates the oldest wins
+pub fn saved_url_index(conn: &Connection) -> Result<HashMap<String, i32>, Box<dyn Error>> {
+    let mut stmt = conn.prepare("SELECT id, url FROM stations ORDER BY id DESC")?;
+    let index = stmt
+        .query_map([], |row| {
+            Ok((normalize_url(&row.get::<_, String>(1)?), row.get(0)?))
+        })?
+        .collect::<rusqlite::Result<HashMap<String, i32>>>()?;
+    Ok(index)
+}
+
+// What an import would do, worked out before anything is written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportPreview {
+    pub new: usize,
+    pub existing: usize, // Already in the library (or repeated in the import)
+}
+
+impl ImportPreview {
+    pub fn total(&self) -> usize {
+        self.new + self.existing
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Found {} stations: {} new, {} already in library",
+            self.total(),
+            self.new,
+            self.existing
+        )
+    }
+}
+
+// Check which of `urls` are already saved, by normalized URL
+pub fn preview_import<'a>(
+    conn: &Connection,
+    urls: impl IntoIterator<Item = &'a str>,
+) -> Result<ImportPreview, Box<dyn Error>> {
+    let mut seen: HashSet<String> = saved_url_index(conn)?.into_keys().collect();
+    let mut preview = ImportPreview::default();
+    for url in urls {
+        if seen.insert(normalize_url(url)) {
+            preview.new += 1;
+        } else {
+            preview.existing += 1;
+        }
+    }
+    Ok(preview)
+}
+
+// Preview importing a JSON library document; entries without a URL are ignored
+pub fn preview_json_import(
+    conn: &Connection,
+    doc: &Value,
+) -> Result<ImportPreview, Box<dyn Error>> {
+    let urls = json_stations(doc)?
+        .iter()
+        .filter_map(|entry| entry.get("url").and_then(Value::as_str))
+        .filter(|url| !url.is_empty());
+    preview_import(conn, urls)
+}
+
+// Merge a JSON document from export_json into the database.
+// Stations are matched by URL and `on_conflict` decides what happens to the
+// saved one; unless both are kept, the larger play time and the latest
+// last_played win, so importing the same file twice changes nothing, and a
+// favorite mark is never lost.
+// Settings are only added when they aren't already set locally.
+// `progress(done, total)` is called as it works through the stations.
+pub fn import_json(
+    conn: &Connection,
+    doc: &Value,
+    on_conflict: ImportConflict,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<JsonImportSummary, Box<dyn Error>> {
+    let stations = json_stations(doc)?;
+    let total = stations.len();
+    let mut saved = saved_url_index(conn)?;
+
+    let mut summary = JsonImportSummary {
+        added: 0,
+        merged: 0,
+        settings: 0,
+    };
+
+    for (done, entry) in stations.iter().enumerate() {
+        progress(done, total);
+        let name = entry.get("name").and_then(Value::as_str);
+        let url = entry.get("url").and_then(Value::as_str);
+        let (name, url) = match (name, url) {
+            (Some(name), Some(url)) if !url.is_empty() => (name, url),
+            _ => continue, // Skip malformed entries
+        };
+        let description = entry.get("description").and_then(Value::as_str);
+        let mpv_args = entry.get("mpv_args").and_then(Value::as_str);
+        let favorite = entry
+            .get("favorite")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let pinned = entry
+            .get("pinned")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let play_time = entry
+            .get("total_play_time")
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+        let last_played = entry.get("last_played").and_then(Value::as_i64);
+        // Older exports have no mirrors; None leaves a saved station's alone
+        let mirrors: Option<Vec<String>> =
+            entry.get("mirrors").and_then(Value::as_array).map(|urls| {
+                urls.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            });
+
+        let existing = match on_conflict {
+            ImportConflict::Keep => None,
+            _ => saved.get(&normalize_url(url)).copied(),
+        };
+
+        let station_id = match existing {
+            Some(id) => {
+                if on_conflict == ImportConflict::Overwrite {
+                    update_station(conn, id, name, url, description, mpv_args)?;
+                    if let Some(mirrors) = &mirrors {
+                        set_station_mirrors(conn, id, mirrors)?;
+                    }
+                }
+                if favorite {
+                    toggle_favorite(conn, id, true)?;
+                }
+                if pinned {
+                    set_pinned(conn, id, true)?;
+                }
+                summary.merged += 1;
+                id
+            }
+            None => {
+                let id = add_station(conn, name, url, description)?;
+                saved.entry(normalize_url(url)).or_insert(id);
+                conn.execute(
+                    "UPDATE stations SET favorite = ?1, mpv_args = ?2, pinned = ?3 WHERE id = ?4",
+                    params![favorite as i32, mpv_args, pinned as i32, id],
+                )?;
+                set_station_mirrors(conn, id, mirrors.as_deref().unwrap_or_default())?;
+                summary.added += 1;
+                id
+            }
+        };
+
+        if play_time > 0 || last_played.is_some() {
+            conn.execute(
+                "INSERT INTO station_stats (station_id, total_play_time, last_played)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(station_id) DO UPDATE SET
+                     total_play_time = MAX(total_play_time, excluded.total_play_time),
+                     last_played = CASE
+                         WHEN excluded.last_played IS NULL THEN last_played
+                         WHEN last_played IS NULL THEN excluded.last_played
+                         ELSE MAX(last_played, excluded.last_played)
+                     END",
+                params![station_id, play_time, last_played],
+            )?;
+        }
+    }
+    progress(total, total);
+
+    if let Some(settings) = doc.get("settings").and_then(Value::as_object) {
+        for (key, value) in settings {
+            if let Some(value) = value.as_str() {
+                summary.settings += conn.execute(
+                    "INSERT OR IGNORE INTO settings (key, value) VALUES (?1, ?2)",
+                    params![key, value],
+                )?;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// Settings functions
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
+    let mut values = stmt.query_map(params![key], |row| row.get::<_, String>(0))?;
+
+    if let Some(value) = values.next() {
+        return Ok(Some(value?));
+    }
+
+    Ok(None)
+}
+
+// Read an on/off setting; unset or unrecognised values count as off
+pub fn get_bool_setting(conn: &Connection, key: &str) -> Result<bool, Box<dyn Error>> {
+    get_bool_setting_or(conn, key, false)
+}
+
+// Read an on/off setting that falls back to `default` when unset or unrecognised
+pub fn get_bool_setting_or(
+    conn: &Connection,
+    key: &str,
+    default: bool,
+) -> Result<bool, Box<dyn Error>> {
+    Ok(
+        match get_setting(conn, key)?
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("1" | "true" | "on" | "yes") => true,
+            Some("0" | "false" | "off" | "no") => false,
+            _ => default,
+        },
+    )
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<(), Box<dyn Error>> {
+    conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+// Hide a directory station (by URL) from future listings
+pub fn hide_station(conn: &Connection, url: &str, name: &str) -> Result<(), Box<dyn Error>> {
+    conn.execute(
+        "INSERT OR REPLACE INTO hidden_stations (url, name, hidden_at) VALUES (?1, ?2, ?3)",
+        params![url, name, chrono::Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+pub fn load_hidden_urls(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut stmt = conn.prepare("SELECT url FROM hidden_stations")?;
+    let urls = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(urls)
+}
+
+// Show every hidden directory station again; returns how many were hidden
+pub fn unhide_all_stations(conn: &Connection) -> Result<usize, Box<dyn Error>> {
+    Ok(conn.execute("DELETE FROM hidden_stations", [])?)
+}
+
 // Station usage statistics functions
 
 pub fn update_station_stats(
@@ -204,12 +1400,73 @@ pub fn get_station_stats(
     Ok(None)
 }
 
+// Header row of the listening stats CSV
+pub const STATS_CSV_HEADER: &str = "station_name,url,total_play_time_seconds,last_played_iso";
+
+// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Listening stats as CSV, most played first. Stations never played are
+// left out unless include_unplayed, in which case their stats are blank.
+pub fn stats_csv(conn: &Connection, include_unplayed: bool) -> Result<String, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT s.name, s.url, st.total_play_time, st.last_played
+         FROM stations s
+         {} JOIN station_stats st ON s.id = st.station_id
+         ORDER BY st.total_play_time IS NULL, st.total_play_time DESC, s.id",
+        if include_unplayed { "LEFT" } else { "" }
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<i64>>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+
+    let mut csv = String::from(STATS_CSV_HEADER);
+    csv.push('\n');
+    for row in rows {
+        let (name, url, play_time, last_played) = row?;
+        let last_played = last_played
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+            .map(|datetime| datetime.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&name),
+            csv_field(&url),
+            play_time.map(|secs| secs.to_string()).unwrap_or_default(),
+            last_played.unwrap_or_default()
+        ));
+    }
+    Ok(csv)
+}
+
+// Write the listening stats CSV to path; returns how many rows were written
+pub fn export_stats_csv(
+    conn: &Connection,
+    path: &Path,
+    include_unplayed: bool,
+) -> Result<usize, Box<dyn Error>> {
+    let csv = stats_csv(conn, include_unplayed)?;
+    std::fs::write(path, &csv)?;
+    Ok(csv.lines().count() - 1)
+}
+
 pub fn get_top_stations(
     conn: &Connection,
     limit: usize,
 ) -> Result<Vec<(Station, i64)>, Box<dyn Error>> {
     let mut stmt = conn.prepare(
-        "SELECT s.id, s.name, s.url, s.favorite, s.description, st.total_play_time
+        "SELECT s.id, s.name, s.url, s.favorite, s.description, s.mpv_args, s.pinned,
+                s.last_check_status, s.last_check_time, st.total_play_time
          FROM stations s
          JOIN station_stats st ON s.id = st.station_id
          ORDER BY st.total_play_time DESC
@@ -217,16 +1474,37 @@ pub fn get_top_stations(
     )?;
 
     let results = stmt.query_map(params![limit as i64], |row| {
-        Ok((
-            Station {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                url: row.get(2)?,
-                favorite: row.get::<_, i32>(3)? != 0,
-                description: row.get(4)?,
-            },
-            row.get::<_, i64>(5)?,
-        ))
+        Ok((station_from_row(row)?, row.get::<_, i64>(9)?))
+    })?;
+
+    let mut stations = Vec::new();
+    for result in results {
+        stations.push(result?);
+    }
+
+    Ok(stations)
+}
+
+// Like get_top_stations, but only counting plays started at or after `since`
+pub fn get_top_stations_since(
+    conn: &Connection,
+    since: i64,
+    limit: usize,
+) -> Result<Vec<(Station, i64)>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.name, s.url, s.favorite, s.description, s.mpv_args, s.pinned,
+                s.last_check_status, s.last_check_time, SUM(h.play_time) AS window_play_time
+         FROM play_history h
+         JOIN stations s ON s.id = h.station_id
+         WHERE h.started_at >= ?1
+         GROUP BY s.id
+         HAVING window_play_time > 0
+         ORDER BY window_play_time DESC
+         LIMIT ?2",
+    )?;
+
+    let results = stmt.query_map(params![since, limit as i64], |row| {
+        Ok((station_from_row(row)?, row.get::<_, i64>(9)?))
     })?;
 
     let mut stations = Vec::new();
@@ -247,14 +1525,164 @@ pub fn format_play_time(seconds: i64) -> String {
     }
 }
 
-// Function to find and remove duplicate URLs in the stations database
-pub fn remove_duplicate_urls(conn: &Connection) -> Result<(), Box<dyn Error>> {
-    // First find all duplicate URLs
+// Describe a unix timestamp relative to now ("2 hours ago", "yesterday"),
+// falling back to the date for anything older than a week
+pub fn format_relative_time(timestamp: i64) -> String {
+    format_relative_time_at(timestamp, chrono::Utc::now().timestamp())
+}
+
+// format_relative_time against an explicit "now", for callers that already have one
+pub fn format_relative_time_at(timestamp: i64, now: i64) -> String {
+    let plural = |n: i64, unit: &str| {
+        if n == 1 {
+            format!("1 {} ago", unit)
+        } else {
+            format!("{} {}s ago", n, unit)
+        }
+    };
+
+    let elapsed = now - timestamp;
+    if elapsed < 60 {
+        "just now".to_string()
+    } else if elapsed < 3600 {
+        plural(elapsed / 60, "minute")
+    } else if elapsed < 86400 {
+        plural(elapsed / 3600, "hour")
+    } else if elapsed < 2 * 86400 {
+        "yesterday".to_string()
+    } else if elapsed < 7 * 86400 {
+        plural(elapsed / 86400, "day")
+    } else {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .map(|datetime| datetime.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+// A duplicated URL and the ids of the extra stations using it
+pub type DuplicateUrl = (String, Vec<i32>);
+
+// Report which stations remove_duplicate_urls would delete, without deleting anything.
+// Returns each duplicated URL with the ids that would go (the lowest id is kept).
+pub fn remove_duplicate_urls_dry_run(
+    conn: &Connection,
+) -> Result<Vec<DuplicateUrl>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT url, id FROM stations
+         WHERE url IN (SELECT url FROM stations GROUP BY url HAVING COUNT(*) > 1)
+         ORDER BY url, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+    })?;
+
+    let mut duplicates: Vec<DuplicateUrl> = Vec::new();
+    for row in rows {
+        let (url, id) = row?;
+        match duplicates.last_mut() {
+            // Rows for the same URL are adjacent; everything after the first is a duplicate
+            Some((last_url, ids)) if *last_url == url => ids.push(id),
+            _ => duplicates.push((url, Vec::new())),
+        }
+    }
+
+    Ok(duplicates)
+}
+
+// A station whose URL or mirrors rewrite_urls changes
+pub struct UrlRewrite {
+    pub station_id: i32,
+    pub name: String,
+    pub old_url: String,
+    pub new_url: String,
+    pub old_mirrors: Vec<String>,
+    pub new_mirrors: Vec<String>,
+}
+
+impl UrlRewrite {
+    // Every URL that changes, as (old, new), the main URL first
+    pub fn changes(&self) -> Vec<(&str, &str)> {
+        std::iter::once((&self.old_url, &self.new_url))
+            .chain(self.old_mirrors.iter().zip(&self.new_mirrors))
+            .filter(|(old, new)| old != new)
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+            .collect()
+    }
+}
+
+// The stations whose URL or mirrors contain `from`, with the URLs `to`
+// would give them
+pub fn preview_url_rewrite(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+) -> Result<Vec<UrlRewrite>, Box<dyn Error>> {
+    if from.is_empty() {
+        return Err("The text to replace can't be empty".into());
+    }
+    let mut stmt = conn.prepare("SELECT id, name, url, mirror_urls FROM stations ORDER BY id")?;
+    let rows = stmt.query_map([], |row| {
+        let mirrors: Option<String> = row.get(3)?;
+        Ok(UrlRewrite {
+            station_id: row.get(0)?,
+            name: row.get(1)?,
+            old_url: row.get(2)?,
+            new_url: String::new(),
+            old_mirrors: mirrors
+                .as_deref()
+                .map(parse_mirror_urls)
+                .unwrap_or_default(),
+            new_mirrors: Vec::new(),
+        })
+    })?;
+    let mut rewrites = Vec::new();
+    for row in rows {
+        let mut rewrite = row?;
+        rewrite.new_url = rewrite.old_url.replace(from, to);
+        rewrite.new_mirrors = rewrite
+            .old_mirrors
+            .iter()
+            .map(|mirror| mirror.replace(from, to))
+            .collect();
+        if !rewrite.changes().is_empty() {
+            rewrites.push(rewrite);
+        }
+    }
+    Ok(rewrites)
+}
+
+// Replace `from` with `to` in every station URL and mirror, all or nothing,
+// for when a provider moves its streams to a new domain. Returns how many
+// stations changed.
+pub fn rewrite_urls(conn: &Connection, from: &str, to: &str) -> Result<usize, Box<dyn Error>> {
+    let rewrites = preview_url_rewrite(conn, from, to)?;
+    let tx = conn.unchecked_transaction()?;
+    for rewrite in &rewrites {
+        // The mirror that worked last is one of these URLs, so it moves too
+        let mirrors = Some(rewrite.new_mirrors.join("\n")).filter(|text| !text.is_empty());
+        tx.execute(
+            "UPDATE stations
+             SET url = ?1, mirror_urls = ?2, working_url = REPLACE(working_url, ?3, ?4)
+             WHERE id = ?5",
+            params![rewrite.new_url, mirrors, from, to, rewrite.station_id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(rewrites.len())
+}
+
+// Function to find and remove duplicate URLs in the stations database.
+// Deletes every station whose URL is already used by an older station.
+// Only run on explicit request; returns how many stations were removed.
+pub fn remove_duplicate_urls(conn: &Connection) -> Result<usize, Box<dyn Error>> {
+    // First find all duplicate URLs; the lowest id is the oldest station,
+    // whether or not a newer one is pinned
     let mut find_stmt = conn.prepare(
         "SELECT url, COUNT(*) as count, MIN(id) as min_id 
          FROM stations 
          GROUP BY url 
-         HAVING count > 1",
+         HAVING count > 1
+         ORDER BY min_id",
     )?;
 
     // Collect all duplicates first to avoid borrowing issues
@@ -278,18 +1706,99 @@ pub fn remove_duplicate_urls(conn: &Connection) -> Result<(), Box<dyn Error>> {
     drop(find_stmt);
 
     // Process each duplicate URL
-    for (url, count, min_id) in duplicates_to_process {
-        // Log information about the duplicates (for debugging)
-        eprintln!("Found {} duplicate entries for URL: {}", count, url);
-
+    let mut removed = 0;
+    for (url, _count, min_id) in duplicates_to_process {
         // Delete all occurrences of this URL except the one with the minimum ID
         let deleted = conn.execute(
             "DELETE FROM stations WHERE url = ?1 AND id != ?2",
             params![url, min_id],
         )?;
+        log::info!(
+            "Removed {} duplicate(s) of {} (kept id {})",
+            deleted,
+            url,
+            min_id
+        );
+        removed += deleted;
+    }
+
+    Ok(removed)
+}
 
-        eprintln!("Removed {} duplicate entries", deleted);
+// What --dedupe compares to decide two stations are the same
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DedupeBy {
+    #[default]
+    Url,
+    Name,
+    Both,
+}
+
+impl DedupeBy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "url" => Some(DedupeBy::Url),
+            "name" => Some(DedupeBy::Name),
+            "both" => Some(DedupeBy::Both),
+            _ => None,
+        }
     }
+}
 
-    Ok(())
+// A station name reduced for comparison, so "BBC  Radio 1" and
+// "bbc radio 1 " match: case and runs of whitespace are ignored
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// The letter or digit a station is indexed under: the first one in its
+// name, uppercased, so "jazz24" files under J and "The Jazz Groove" under T
+pub fn name_initial(name: &str) -> Option<char> {
+    name.chars().find(|c| c.is_alphanumeric()).map(fold_initial)
+}
+
+// Case folding shared by name_initial and the Alt+letter keys, so é finds "Été FM"
+pub fn fold_initial(c: char) -> char {
+    c.to_uppercase().next().unwrap_or(c)
+}
+
+// Stations sharing a name: the oldest, which is kept, and the later ones
+#[derive(Clone, Debug)]
+pub struct DuplicateName {
+    pub keep: Station,
+    pub extras: Vec<Station>,
+}
+
+// Group stations with the same normalized name, oldest first. Nothing is
+// deleted: different streams can legitimately share a name, so the caller
+// decides per group.
+pub fn find_duplicate_names(conn: &Connection) -> Result<Vec<DuplicateName>, Box<dyn Error>> {
+    // By id rather than load_stations' order, which puts pinned stations first
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM stations ORDER BY id",
+        STATION_COLUMNS
+    ))?;
+    let stations = stmt
+        .query_map([], station_from_row)?
+        .collect::<rusqlite::Result<Vec<Station>>>()?;
+
+    let mut groups: Vec<DuplicateName> = Vec::new();
+    let mut group_by_name: HashMap<String, usize> = HashMap::new();
+    for station in stations {
+        match group_by_name.get(&normalize_name(&station.name)) {
+            Some(&index) => groups[index].extras.push(station),
+            None => {
+                group_by_name.insert(normalize_name(&station.name), groups.len());
+                groups.push(DuplicateName {
+                    keep: station,
+                    extras: Vec::new(),
+                });
+            }
+        }
+    }
+    groups.retain(|group| !group.extras.is_empty());
+    Ok(groups)
 }
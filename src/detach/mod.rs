@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Where the PID of a detached player is kept
+// (~/.cache/radio_cli/player.pid on Linux)
+pub fn pid_file_path() -> Option<PathBuf> {
+    crate::paths::cache_dir().map(|dir| dir.join("player.pid"))
+}
+
+// Remember a player left running by --detach, and its IPC socket so
+// --stop can clean it up
+pub fn write_pid(pid: u32, socket: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let path = pid_file_path().ok_or("Could not find a cache directory for the PID file")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format!("{}\n{}\n", pid, socket.display()))?;
+    Ok(path)
+}
+
+// Stop the detached player, if any, and remove the PID file and the
+// player's IPC socket. Returns the PID that was stopped.
+pub fn stop_detached() -> Result<Option<u32>, Box<dyn Error>> {
+    let path = match pid_file_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(None),
+    };
+
+    let contents = fs::read_to_string(&path)?;
+    fs::remove_file(&path)?;
+    let mut lines = contents.lines();
+    let pid: u32 = lines
+        .next()
+        .and_then(|line| line.trim().parse().ok())
+        .ok_or_else(|| format!("Invalid PID file {}", path.display()))?;
+    // PID files from older versions have no socket line
+    let socket = lines.next().map(str::trim).filter(|line| !line.is_empty());
+
+    // The PID may have been reused since the player exited; only kill mpv
+    if !is_mpv(pid) {
+        return Ok(None);
+    }
+
+    #[cfg(unix)]
+    let status = Command::new("kill").arg(pid.to_string()).status()?;
+    #[cfg(windows)]
+    let status = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F"])
+        .status()?;
+
+    if status.success() {
+        // Named pipes on Windows vanish with the process; sockets stay on disk
+        #[cfg(unix)]
+        if let Some(socket) = socket {
+            let _ = fs::remove_file(socket);
+        }
+        Ok(Some(pid))
+    } else {
+        Err(format!("Failed to stop player process {}", pid).into())
+    }
+}
+
+// Check that a PID still belongs to mpv, where the platform lets us
+fn is_mpv(pid: u32) -> bool {
+    let proc_dir = PathBuf::from("/proc");
+    if !proc_dir.exists() {
+        // No /proc to check against (macOS, Windows); trust the PID file
+        return true;
+    }
+    fs::read_to_string(proc_dir.join(pid.to_string()).join("comm"))
+        .map(|name| name.trim() == "mpv")
+        .unwrap_or(false)
+}
@@ -0,0 +1,48 @@
+use std::process::{Command, Stdio};
+use std::thread;
+
+// Settings key: command a station can be handed to, e.g. "vlc {url}".
+// {url} and {name} are replaced with the station's; without {url} the URL
+// is added as the last argument.
+pub const EXTERNAL_COMMAND_SETTING: &str = "external_command";
+
+// Turn the template into a program and its arguments. It's split like mpv
+// arguments, before substituting, and run without a shell, so a station's
+// URL or name can never inject anything.
+pub fn build_command(template: &str, url: &str, name: &str) -> Result<Vec<String>, String> {
+    let mut args = crate::audio::split_mpv_args(template);
+    if args.is_empty() {
+        return Err("The external command is empty".to_string());
+    }
+    if !args.iter().any(|arg| arg.contains("{url}")) {
+        args.push("{url}".to_string());
+    }
+    Ok(args
+        .into_iter()
+        .map(|arg| arg.replace("{url}", url).replace("{name}", name))
+        .collect())
+}
+
+// Start the external command for a station without waiting for it.
+// Returns the program name for the status message.
+pub fn open(template: &str, url: &str, name: &str) -> Result<String, String> {
+    let args = build_command(template, url, name)?;
+    let program = args[0].clone();
+    let mut child = Command::new(&program)
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not run {}: {}", program, e))?;
+
+    // Reap it whenever it exits, so it doesn't linger as a zombie
+    thread::spawn(move || {
+        if let Ok(status) = child.wait() {
+            if !status.success() {
+                log::warn!("External command exited with {}", status);
+            }
+        }
+    });
+    Ok(program)
+}
@@ -4,6 +4,24 @@ pub mod rcast;
 // Re-export other modules
 pub mod app;
 pub mod audio;
+#[cfg(feature = "cast")]
+pub mod cast;
+pub mod clipboard;
 pub mod db;
+pub mod detach;
+pub mod external;
+pub mod logging;
+pub mod metadata;
+pub mod offline;
+pub mod paths;
+pub mod playlist;
+pub mod proxy;
+#[cfg(feature = "scrobble")]
+pub mod scrobble;
+#[cfg(feature = "keyring")]
+pub mod secrets;
+pub mod seed;
+pub mod stream_info;
 pub mod ui;
+pub mod undo;
 pub mod visualizations;
@@ -0,0 +1,39 @@
+use log::LevelFilter;
+use simplelog::{ConfigBuilder, WriteLogger};
+use std::env;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+// Where debug logs are written (~/.cache/radio_cli/radio_cli.log on Linux)
+pub fn log_path() -> Option<PathBuf> {
+    crate::paths::cache_dir().map(|dir| dir.join("radio_cli.log"))
+}
+
+// Start logging to the log file if RUST_LOG is set or --verbose was given.
+// Returns the log file path when logging is enabled.
+pub fn init(verbose: bool) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let level = match env::var("RUST_LOG")
+        .ok()
+        .and_then(|value| value.trim().parse::<LevelFilter>().ok())
+    {
+        Some(level) => level,
+        None if verbose => LevelFilter::Debug,
+        None => return Ok(None),
+    };
+
+    let path = log_path().ok_or("Could not find a cache directory for the log file")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+    // Keep other crates (reqwest, hyper, ...) quiet unless they have something important to say
+    let config = ConfigBuilder::new()
+        .add_filter_allow_str("radio_cli")
+        .build();
+    WriteLogger::init(level, config, file)?;
+
+    log::info!("radio_cli {} started", env!("CARGO_PKG_VERSION"));
+    Ok(Some(path))
+}
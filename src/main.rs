@@ -1,15 +1,43 @@
 mod app;
+// Simulation mode leaves the mpv plumbing unused
+#[cfg_attr(feature = "skip_mpv", allow(dead_code, unused_imports))]
 mod audio;
+#[cfg(feature = "cast")]
+mod cast;
+mod clipboard;
 mod db;
+mod detach;
+mod external;
+mod logging;
+mod metadata;
+mod offline;
+mod paths;
+mod playlist;
+mod proxy;
 mod rcast;
+#[cfg(feature = "scrobble")]
+mod scrobble;
+#[cfg(feature = "keyring")]
+mod secrets;
+mod seed;
+mod stream_info;
 mod ui;
+mod undo;
 mod visualizations;
 
 use std::env;
 use std::error::Error;
+use std::io::IsTerminal;
+use visualizations::{export, ColorMode, VisualizationType};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Frames rendered by --export-vis unless --frames is given
+const DEFAULT_EXPORT_FRAMES: usize = 120;
+
+// Listening time needed before --favorite-played marks a station
+const DEFAULT_FAVORITE_PLAYED_MINUTES: i64 = 10;
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Check for command-line arguments
     let args: Vec<String> = env::args().collect();
@@ -17,13 +45,54 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Default setting for visualizations (disabled by default)
     let mut show_visualizations = false;
     let mut test_duplicate_removal = false;
+    let mut dedupe = false;
+    let mut dry_run = false;
+    let mut doctor = false;
+    let mut verbose = false;
+    let mut debug_metadata = false;
+    let mut detach = false;
+    let mut offline = false;
+    let mut stop = false;
+    let mut unhide_all = false;
+    let mut list = false;
+    let mut add_spec: Option<String> = None;
+    let mut list_json = false;
+    let mut favorites_only = false;
+    let mut favorite_played: Option<i64> = None;
+    let mut proxy_setting: Option<String> = None;
+    let mut settings_to_set: Vec<(String, String)> = Vec::new();
+    let mut export_db_path: Option<String> = None;
+    let mut import_db_path: Option<String> = None;
+    let mut export_json_path: Option<String> = None;
+    let mut export_stats_path: Option<String> = None;
+    let mut include_unplayed = false;
+    let mut import_json_path: Option<String> = None;
+    let mut on_conflict: Option<db::ImportConflict> = None;
+    let mut dedupe_by = db::DedupeBy::default();
+    let mut rewrite_urls: Option<(String, String)> = None;
+    let mut script_path: Option<String> = None;
+    let mut dump_screen = false;
+    let mut export_vis_path: Option<String> = None;
+    let mut vis_type_name: Option<String> = None;
+    let mut vis_frames: usize = DEFAULT_EXPORT_FRAMES;
+    let mut vis_seed: u64 = 0;
+    let mut color_mode: Option<ColorMode> = None;
+    let mut theme: Option<ui::Theme> = None;
 
     // Check for args
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
             "--version" | "-v" => {
-                println!("RadioCLI v{}", VERSION);
+                if args.iter().any(|arg| arg == "--verbose") {
+                    print_build_info();
+                } else {
+                    println!("RadioCLI v{}", VERSION);
+                }
+                return Ok(());
+            }
+            "--about" => {
+                print_build_info();
                 return Ok(());
             }
             "--help" | "-h" => {
@@ -31,9 +100,72 @@ fn main() -> Result<(), Box<dyn Error>> {
                 println!("Usage: radio_cli [OPTIONS]");
                 println!("\nOptions:");
                 println!("  -v, --version    Print version information");
+                println!("  --about          Print build details for bug reports (same as --version --verbose)");
                 println!("  -h, --help       Print this help message");
-                println!("  --vis            Enable visualizations (disabled by default)");
+                println!(
+                    "  --vis            Show the visualization this run (V cycles vis/stats/split)"
+                );
                 println!("  --test-dupes     Run a test to verify duplicate URL removal");
+                println!("  --dedupe         Remove stations that share a URL (keeps the oldest)");
+                println!(
+                    "  --by=url|name|both  What --dedupe compares; name asks before each removal"
+                );
+                println!(
+                    "  --verbose        Write a debug log to the cache directory (or set RUST_LOG)"
+                );
+                println!("  --debug-metadata  Log every raw mpv status line (implies --verbose)");
+                println!("  --16color        Map visualization colors to the 16 ANSI colors");
+                println!("  --truecolor      Use full RGB colors even if COLORTERM doesn't say so");
+                println!("  --theme <NAME>   Colors for this run: default, terminal or base16");
+                println!("  --detach         Keep playing after quitting (PID saved for --stop)");
+                println!("  --stop           Stop a player left running by --detach");
+                println!(
+                    "  --favorite-played [MIN]  Favorite stations played for at least MIN minutes (default {})",
+                    DEFAULT_FAVORITE_PLAYED_MINUTES
+                );
+                println!("  --doctor         Check the database and remove duplicate stations");
+                println!("  --unhide-all     Show directory stations hidden with 'x' again");
+                println!("  --list           Print the saved stations and exit");
+                println!(
+                    "  --add \"NAME|URL|DESC\"  Save a station and print its id (DESC is optional)"
+                );
+                println!("  --json           With --list, print JSON instead of a table");
+                println!("  --favorites      With --list, only print favorites");
+                println!("  --rewrite-urls <FROM> <TO>  Replace FROM with TO in every station URL");
+                println!(
+                    "  --dry-run        With --dedupe/--doctor/--rewrite-urls, only list the changes"
+                );
+                println!("  --proxy <URL>    Save an HTTP proxy for streams and directory fetches");
+                println!(
+                    "                   (overrides HTTP_PROXY/HTTPS_PROXY; use 'none' to disable,"
+                );
+                println!("                   'env' to go back to the environment variables)");
+                println!("                   Hosts listed in NO_PROXY are always reached directly");
+                println!("  --set KEY=VALUE  Save a setting (an empty VALUE removes it)");
+                println!("  --offline        Don't touch the network: no directory, seeding,");
+                println!("                   checks or scrobbling; only file:// stations play");
+                println!("  --export-db <PATH>  Back up the whole database to PATH");
+                println!("  --import-db <PATH>  Replace the database with a backup from PATH");
+                println!("  --export-json <PATH>  Export stations, stats and settings as JSON");
+                println!("  --import-json <PATH>  Merge a JSON export into the library (by URL)");
+                println!(
+                    "  --export-stats <PATH>  Export listening stats as CSV (played stations;"
+                );
+                println!("                   add --include-unplayed for every station)");
+                println!(
+                    "  --on-conflict=skip|overwrite|keep  For stations whose URL is already saved:"
+                );
+                println!("                   keep the saved details, replace them, or add both");
+                println!(
+                    "  --export-vis <PATH>  Render visualization frames to PATH: a .cast file"
+                );
+                println!("                   (asciicast) or a directory of .ans frames");
+                println!("  --vis-type <NAME>  Visualization to export (starfield, bar-spectrum, wave-forms, radial-spectrum, plasma, text-scroller)");
+                println!(
+                    "  --frames <N>     Number of frames to export (default {})",
+                    DEFAULT_EXPORT_FRAMES
+                );
+                println!("  --seed <N>       Random seed for the export (same seed, same frames)");
                 return Ok(());
             }
             "--vis" => {
@@ -42,6 +174,232 @@ fn main() -> Result<(), Box<dyn Error>> {
             "--test-dupes" => {
                 test_duplicate_removal = true;
             }
+            "--dedupe" => {
+                dedupe = true;
+            }
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--doctor" => {
+                doctor = true;
+            }
+            "--verbose" => {
+                verbose = true;
+            }
+            "--debug-metadata" => {
+                debug_metadata = true;
+            }
+            "--detach" => {
+                detach = true;
+            }
+            "--stop" => {
+                stop = true;
+            }
+            "--offline" => {
+                offline = true;
+            }
+            "--unhide-all" => {
+                unhide_all = true;
+            }
+            "--list" => {
+                list = true;
+            }
+            "--add" => {
+                i += 1;
+                match args.get(i) {
+                    Some(spec) => add_spec = Some(spec.clone()),
+                    None => {
+                        eprintln!("--add requires \"name|url|description\"");
+                        return Ok(());
+                    }
+                }
+            }
+            "--json" => {
+                list_json = true;
+            }
+            "--favorites" => {
+                favorites_only = true;
+            }
+            "--favorite-played" => {
+                // Optional threshold in minutes
+                match args.get(i + 1).map(|arg| arg.parse::<i64>()) {
+                    Some(Ok(minutes)) => {
+                        match Some(minutes)
+                            .filter(|m| *m > 0)
+                            .and_then(|m| m.checked_mul(60))
+                        {
+                            Some(seconds) => favorite_played = Some(seconds),
+                            None => {
+                                eprintln!("--favorite-played needs a positive number of minutes");
+                                return Ok(());
+                            }
+                        }
+                        i += 1;
+                    }
+                    _ => favorite_played = Some(DEFAULT_FAVORITE_PLAYED_MINUTES * 60),
+                }
+            }
+            "--export-db" | "--import-db" => {
+                let flag = args[i].clone();
+                i += 1;
+                match args.get(i) {
+                    Some(path) if flag == "--export-db" => export_db_path = Some(path.clone()),
+                    Some(path) => import_db_path = Some(path.clone()),
+                    None => {
+                        eprintln!("{} requires a file path", flag);
+                        return Ok(());
+                    }
+                }
+            }
+            "--export-json" | "--import-json" => {
+                let flag = args[i].clone();
+                i += 1;
+                match args.get(i) {
+                    Some(path) if flag == "--export-json" => export_json_path = Some(path.clone()),
+                    Some(path) => import_json_path = Some(path.clone()),
+                    None => {
+                        eprintln!("{} requires a file path", flag);
+                        return Ok(());
+                    }
+                }
+            }
+            "--export-stats" => {
+                i += 1;
+                match args.get(i) {
+                    Some(path) => export_stats_path = Some(path.clone()),
+                    None => {
+                        eprintln!("--export-stats requires a file path");
+                        return Ok(());
+                    }
+                }
+            }
+            "--include-unplayed" => {
+                include_unplayed = true;
+            }
+            arg if arg.starts_with("--on-conflict") => {
+                // Accept both --on-conflict=POLICY and --on-conflict POLICY
+                let value = match arg.strip_prefix("--on-conflict=") {
+                    Some(value) => Some(value.to_string()),
+                    None if arg == "--on-conflict" => {
+                        i += 1;
+                        args.get(i).cloned()
+                    }
+                    None => None,
+                };
+                match value.as_deref().and_then(db::ImportConflict::parse) {
+                    Some(policy) => on_conflict = Some(policy),
+                    None => {
+                        eprintln!("--on-conflict must be skip, overwrite or keep");
+                        return Ok(());
+                    }
+                }
+            }
+            // Accept both --by=FIELD and --by FIELD
+            arg if arg == "--by" || arg.starts_with("--by=") => {
+                let value = match arg.strip_prefix("--by=") {
+                    Some(value) => Some(value.to_string()),
+                    None => {
+                        i += 1;
+                        args.get(i).cloned()
+                    }
+                };
+                match value.as_deref().and_then(db::DedupeBy::parse) {
+                    Some(by) => dedupe_by = by,
+                    None => {
+                        eprintln!("--by must be url, name or both");
+                        return Ok(());
+                    }
+                }
+            }
+            // Undocumented: drive the UI from a key script (for demos and UI tests)
+            "--script" => {
+                i += 1;
+                match args.get(i) {
+                    Some(path) => script_path = Some(path.clone()),
+                    None => {
+                        eprintln!("--script requires a file path");
+                        return Ok(());
+                    }
+                }
+            }
+            "--dump" => {
+                dump_screen = true;
+            }
+            "--16color" => {
+                color_mode = Some(ColorMode::Ansi16);
+            }
+            "--theme" => {
+                i += 1;
+                match args.get(i).and_then(|name| ui::Theme::from_name(name)) {
+                    Some(choice) => theme = Some(choice),
+                    None => {
+                        eprintln!("--theme must be default, terminal or base16");
+                        return Ok(());
+                    }
+                }
+            }
+            "--truecolor" => {
+                color_mode = Some(ColorMode::TrueColor);
+            }
+            "--export-vis" | "--vis-type" | "--frames" | "--seed" => {
+                let flag = args[i].clone();
+                i += 1;
+                let value = match args.get(i) {
+                    Some(value) => value.clone(),
+                    None => {
+                        eprintln!("{} requires a value", flag);
+                        return Ok(());
+                    }
+                };
+                let parsed = match flag.as_str() {
+                    "--export-vis" => {
+                        export_vis_path = Some(value);
+                        true
+                    }
+                    "--vis-type" => {
+                        vis_type_name = Some(value);
+                        true
+                    }
+                    "--frames" => value.parse().map(|n| vis_frames = n).is_ok(),
+                    _ => value.parse().map(|n| vis_seed = n).is_ok(),
+                };
+                if !parsed {
+                    eprintln!("{} requires a number", flag);
+                    return Ok(());
+                }
+            }
+            "--set" => {
+                i += 1;
+                match args.get(i).and_then(|arg| arg.split_once('=')) {
+                    Some((key, value)) => {
+                        settings_to_set.push((key.trim().to_string(), value.to_string()))
+                    }
+                    None => {
+                        eprintln!("--set requires KEY=VALUE");
+                        return Ok(());
+                    }
+                }
+            }
+            "--rewrite-urls" => match (args.get(i + 1), args.get(i + 2)) {
+                (Some(from), Some(to)) => {
+                    rewrite_urls = Some((from.clone(), to.clone()));
+                    i += 2;
+                }
+                _ => {
+                    eprintln!("--rewrite-urls requires the text to find and its replacement");
+                    return Ok(());
+                }
+            },
+            "--proxy" => {
+                i += 1;
+                match args.get(i) {
+                    Some(value) => proxy_setting = Some(value.clone()),
+                    None => {
+                        eprintln!("--proxy requires a URL (or 'none'/'env')");
+                        return Ok(());
+                    }
+                }
+            }
             _ => {
                 eprintln!("Unknown option: {}", args[i]);
                 eprintln!("Try 'radio_cli --help' for more information.");
@@ -51,14 +409,550 @@ fn main() -> Result<(), Box<dyn Error>> {
         i += 1;
     }
 
+    // Before anything opens the database, which may fetch a starter pack
+    offline::set_offline(offline);
+
+    // Logging is off unless asked for; the TUI hides anything printed to stderr
+    if let Err(e) = logging::init(verbose || debug_metadata) {
+        eprintln!("Could not start logging: {}", e);
+    }
+
+    if stop {
+        match detach::stop_detached()? {
+            Some(pid) => println!("Stopped detached player (PID {})", pid),
+            None => println!("No detached player is running"),
+        }
+        return Ok(());
+    }
+
     // Test duplicate removal if requested
     if test_duplicate_removal {
         return test_duplicate_url_removal();
     }
 
+    if doctor {
+        return run_doctor(dry_run);
+    }
+    if let Some(min_seconds) = favorite_played {
+        let conn = app::open_database()?;
+        let count = db::favorite_played_stations(&conn, min_seconds)?;
+        println!(
+            "Marked {} station(s) played for at least {} minute(s) as favorites.",
+            count,
+            min_seconds / 60
+        );
+        return Ok(());
+    }
+    if dedupe {
+        return dedupe_stations(dry_run, dedupe_by);
+    }
+    if let Some((from, to)) = rewrite_urls {
+        return rewrite_station_urls(&from, &to, dry_run);
+    }
+    if list {
+        return list_stations(list_json, favorites_only);
+    }
+    if let Some(spec) = add_spec {
+        return add_station_from_spec(&spec);
+    }
+    if unhide_all {
+        let conn = app::open_database()?;
+        let count = db::unhide_all_stations(&conn)?;
+        println!("Unhid {} directory station(s).", count);
+        return Ok(());
+    }
+
+    // Database backup and restore run without the UI
+    if let Some(path) = export_db_path {
+        return export_database(&path);
+    }
+    if let Some(path) = import_db_path {
+        return import_database(&path);
+    }
+
+    if let Some(path) = export_json_path {
+        let conn = app::open_database()?;
+        let doc = db::export_json(&conn)?;
+        std::fs::write(&path, serde_json::to_string_pretty(&doc)?)?;
+        println!("Exported library to {}", path);
+        return Ok(());
+    }
+    if let Some(path) = export_stats_path {
+        let conn = app::open_database()?;
+        let rows = db::export_stats_csv(&conn, std::path::Path::new(&path), include_unplayed)?;
+        println!("Exported stats for {} station(s) to {}", rows, path);
+        return Ok(());
+    }
+    if let Some(path) = import_json_path {
+        let conn = app::open_database()?;
+        let doc: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let preview = db::preview_json_import(&conn, &doc)?;
+        println!("{}", preview.summary());
+        let policy = match on_conflict {
+            Some(policy) => policy,
+            None => match ask_import_conflict(preview)? {
+                Some(policy) => policy,
+                None => {
+                    println!("Import cancelled");
+                    return Ok(());
+                }
+            },
+        };
+        // Large libraries take a while; show how far along we are
+        let show_progress = std::io::stderr().is_terminal();
+        let summary = db::import_json(&conn, &doc, policy, |done, total| {
+            if show_progress && (done % 25 == 0 || done == total) {
+                eprint!("\rImported {}/{}…", done, total);
+                if done == total {
+                    eprintln!();
+                }
+            }
+        })?;
+        println!(
+            "Imported {}: {} new stations, {} merged, {} settings",
+            path, summary.added, summary.merged, summary.settings
+        );
+        return Ok(());
+    }
+
+    // Save any settings given on the command line
+    if !settings_to_set.is_empty() {
+        let conn = app::open_database()?;
+        for (key, value) in &settings_to_set {
+            if value.is_empty() {
+                db::delete_setting(&conn, key)?;
+            } else {
+                db::set_setting(&conn, key, value)?;
+            }
+        }
+        println!("Saved {} setting(s)", settings_to_set.len());
+        return Ok(());
+    }
+
+    // Save the proxy override before starting the UI
+    if let Some(proxy) = proxy_setting {
+        let conn = app::open_database()?;
+        if proxy == "env" {
+            db::delete_setting(&conn, proxy::PROXY_SETTING)?;
+        } else {
+            db::set_setting(&conn, proxy::PROXY_SETTING, &proxy)?;
+        }
+    }
+
+    if let Some(path) = export_vis_path {
+        // Recordings are usually played back elsewhere, so default to full color
+        let colors = color_mode.unwrap_or(ColorMode::TrueColor);
+        return export_visualization(
+            &path,
+            vis_type_name.as_deref(),
+            vis_frames,
+            vis_seed,
+            colors,
+        );
+    }
+
+    if let Some(path) = script_path {
+        let keys = app::parse_key_script(&std::fs::read_to_string(&path)?)?;
+        let mut app = app::App::new(show_visualizations)?;
+        let screen = app.run_script(&keys, app::SCRIPT_WIDTH, app::SCRIPT_HEIGHT)?;
+        if dump_screen {
+            print!("{}", app::buffer_to_text(&screen));
+        }
+        return Ok(());
+    }
+
+    // Never take over a pipe or a redirected stdin with raw mode
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        eprintln!("{}", app::NEEDS_TERMINAL);
+        std::process::exit(1);
+    }
+
     // Create and run the application
     let mut app = app::App::new(show_visualizations)?;
-    app.run()
+    app.player.debug_metadata = debug_metadata;
+    app.player.detach = detach;
+    if let Some(theme) = theme {
+        app.theme = theme;
+    }
+    app.color_mode = color_mode.unwrap_or_else(|| app.theme.color_mode(ColorMode::detect()));
+    if let Err(e) = app.run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// Print the library for scripts and grep: a table, or JSON with --json
+fn list_stations(json: bool, favorites_only: bool) -> Result<(), Box<dyn Error>> {
+    let conn = app::open_database()?;
+    let mut stations = db::load_stations(&conn)?;
+    if favorites_only {
+        stations.retain(|station| station.favorite);
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&db::stations_json(&stations))?
+        );
+        return Ok(());
+    }
+
+    let width = |header: &str, column: &dyn Fn(&db::Station) -> usize| {
+        stations
+            .iter()
+            .map(column)
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let id_width = width("ID", &|station| station.id.to_string().len());
+    let name_width = width("NAME", &|station| station.name.chars().count());
+    let url_width = width("URL", &|station| station.url.chars().count());
+    println!(
+        "{:>id_width$}  ★  {:<name_width$}  {:<url_width$}  DESCRIPTION",
+        "ID", "NAME", "URL"
+    );
+    for station in &stations {
+        let line = format!(
+            "{:>id_width$}  {}  {:<name_width$}  {:<url_width$}  {}",
+            station.id,
+            if station.favorite { "★" } else { " " },
+            station.name,
+            station.url,
+            station.description.as_deref().unwrap_or("")
+        );
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
+
+// Save a station given as "name|url|description" and print its id.
+// Bad input and URLs that are already saved exit non-zero.
+fn add_station_from_spec(spec: &str) -> Result<(), Box<dyn Error>> {
+    let station = match seed::parse_station_spec(spec) {
+        Ok(station) => station,
+        Err(e) => {
+            eprintln!("Cannot add station: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let conn = app::open_database()?;
+    if let Some(id) = db::saved_url_index(&conn)?.get(&db::normalize_url(&station.url)) {
+        eprintln!("{} is already saved (station {})", station.url, id);
+        std::process::exit(1);
+    }
+    let id = db::add_station(
+        &conn,
+        &station.name,
+        &station.url,
+        station.description.as_deref(),
+    )?;
+    println!("{}", id);
+    Ok(())
+}
+
+// Copy the active database to a backup file
+fn export_database(dest: &str) -> Result<(), Box<dyn Error>> {
+    let conn = app::open_database()?;
+    db::checkpoint(&conn)?;
+    drop(conn);
+
+    let db_path = app::get_database_path()?;
+    std::fs::copy(&db_path, dest)?;
+    println!("Exported {} to {}", db_path.display(), dest);
+    Ok(())
+}
+
+// Render frames of a visualization to an asciicast file or a frame directory
+fn export_visualization(
+    path: &str,
+    vis_type_name: Option<&str>,
+    frames: usize,
+    seed: u64,
+    colors: ColorMode,
+) -> Result<(), Box<dyn Error>> {
+    let vis_type = match vis_type_name {
+        Some(name) => match VisualizationType::from_name(name) {
+            Some(vis_type) => vis_type,
+            None => {
+                eprintln!("Unknown visualization: {}", name);
+                return Ok(());
+            }
+        },
+        None => VisualizationType::Starfield,
+    };
+
+    let buffers = export::render_frames(vis_type, frames, seed, 80, 24, colors)?;
+    if path.ends_with(".cast") {
+        std::fs::write(path, export::asciicast(&buffers))?;
+    } else {
+        export::write_ansi_frames(std::path::Path::new(path), &buffers)?;
+    }
+    println!(
+        "Exported {} frame(s) of {} to {}",
+        buffers.len(),
+        vis_type,
+        path
+    );
+    Ok(())
+}
+
+// Confirm an import and ask what to do with stations whose URL is already
+// saved. Returns None if the user cancels.
+fn ask_import_conflict(
+    preview: db::ImportPreview,
+) -> Result<Option<db::ImportConflict>, Box<dyn Error>> {
+    if preview.existing == 0 {
+        print!("Import {} new station(s)? [Y/n] ", preview.new);
+    } else {
+        print!(
+            "{} imported station(s) are already saved. [S]kip, [o]verwrite details, [k]eep both, or [c]ancel? ",
+            preview.existing
+        );
+    }
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(match answer.trim() {
+        "n" | "N" | "no" if preview.existing == 0 => None,
+        "c" | "C" | "cancel" => None,
+        "o" | "O" | "overwrite" => Some(db::ImportConflict::Overwrite),
+        "k" | "K" | "keep" => Some(db::ImportConflict::Keep),
+        _ => Some(db::ImportConflict::Skip),
+    })
+}
+
+// Replace the active database with a backup file after confirmation
+fn import_database(src: &str) -> Result<(), Box<dyn Error>> {
+    let src_path = std::path::Path::new(src);
+    if let Err(e) = db::validate_database_file(src_path) {
+        eprintln!("Cannot import {}: {}", src, e);
+        std::process::exit(1);
+    }
+
+    let db_path = app::get_database_path()?;
+    print!(
+        "This will replace {} with {}. Continue? [y/N] ",
+        db_path.display(),
+        src
+    );
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        println!("Import cancelled.");
+        return Ok(());
+    }
+
+    // Make sure nothing is left in the old WAL before overwriting
+    let conn = app::open_database()?;
+    db::checkpoint(&conn)?;
+    drop(conn);
+
+    std::fs::copy(src_path, &db_path)?;
+
+    // Bring the imported schema up to date
+    let conn = app::open_database()?;
+    let count = db::load_stations(&conn)?.len();
+    println!("Imported {} stations from {}", count, src);
+    Ok(())
+}
+
+// Remove (or with dry_run, just list) duplicate stations
+fn dedupe_stations(dry_run: bool, by: db::DedupeBy) -> Result<(), Box<dyn Error>> {
+    let conn = app::open_database()?;
+    if by != db::DedupeBy::Name {
+        dedupe_urls(&conn, dry_run)?;
+    }
+    if by != db::DedupeBy::Url {
+        dedupe_names(&conn, dry_run)?;
+    }
+    Ok(())
+}
+
+// Remove stations whose URL an older station already uses
+fn dedupe_urls(conn: &rusqlite::Connection, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let duplicates = db::remove_duplicate_urls_dry_run(conn)?;
+
+    if duplicates.is_empty() {
+        println!("No duplicate stations found.");
+        return Ok(());
+    }
+
+    let names: std::collections::HashMap<i32, String> = db::load_stations(conn)?
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect();
+
+    for (url, ids) in &duplicates {
+        println!("{}", url);
+        for id in ids {
+            let name = names.get(id).map(String::as_str).unwrap_or("?");
+            println!(
+                "  {} #{} {}",
+                if dry_run { "would remove" } else { "removing" },
+                id,
+                name
+            );
+        }
+    }
+
+    if dry_run {
+        println!("Dry run: nothing was deleted.");
+    } else {
+        let removed = db::remove_duplicate_urls(conn)?;
+        println!("Removed {} duplicate station(s).", removed);
+    }
+    Ok(())
+}
+
+// Offer to remove stations named like an older one, one group at a time,
+// since the same name can mean different streams (say, two bitrates)
+fn dedupe_names(conn: &rusqlite::Connection, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let groups = db::find_duplicate_names(conn)?;
+    if groups.is_empty() {
+        println!("No stations share a name.");
+        return Ok(());
+    }
+
+    let mut removed = 0;
+    for group in &groups {
+        println!("{}", group.keep.name);
+        println!("  keeping #{} {}", group.keep.id, group.keep.url);
+        for station in &group.extras {
+            println!(
+                "  {} #{} {}",
+                if dry_run { "would remove" } else { "duplicate" },
+                station.id,
+                station.url
+            );
+        }
+        if dry_run {
+            continue;
+        }
+
+        print!("Remove the {} duplicate(s)? [y/N] ", group.extras.len());
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if matches!(answer.trim(), "y" | "Y" | "yes") {
+            for station in &group.extras {
+                db::delete_station(conn, station.id)?;
+                db::forget_station_secret(station.id);
+                removed += 1;
+            }
+        }
+    }
+
+    if dry_run {
+        println!("Dry run: nothing was deleted.");
+    } else {
+        println!("Removed {} station(s) with a duplicate name.", removed);
+    }
+    Ok(())
+}
+
+// Show which station URLs would change, then rewrite them once confirmed
+fn rewrite_station_urls(from: &str, to: &str, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let conn = app::open_database()?;
+    let rewrites = db::preview_url_rewrite(&conn, from, to)?;
+    if rewrites.is_empty() {
+        println!("No station URLs contain {:?}.", from);
+        return Ok(());
+    }
+
+    for rewrite in &rewrites {
+        println!("#{} {}", rewrite.station_id, rewrite.name);
+        for (old, new) in rewrite.changes() {
+            println!("  {}", old);
+            println!("  → {}", new);
+        }
+    }
+    if dry_run {
+        println!("Dry run: {} station(s) would change.", rewrites.len());
+        return Ok(());
+    }
+
+    print!("Rewrite the URLs of {} station(s)? [y/N] ", rewrites.len());
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        println!("Nothing was changed.");
+        return Ok(());
+    }
+    let count = db::rewrite_urls(&conn, from, to)?;
+    println!("Rewrote the URLs of {} station(s).", count);
+    Ok(())
+}
+
+// Version plus how and where this binary was built, for bug reports
+fn print_build_info() {
+    let mut features = Vec::new();
+    if cfg!(feature = "skip_mpv") {
+        features.push("skip_mpv");
+    }
+    if cfg!(feature = "scrobble") {
+        features.push("scrobble");
+    }
+    if cfg!(feature = "keyring") {
+        features.push("keyring");
+    }
+    if cfg!(feature = "cast") {
+        features.push("cast");
+    }
+    if cfg!(feature = "bundled_stations") {
+        features.push("bundled_stations");
+    }
+    let mpv = if cfg!(feature = "skip_mpv") {
+        "not used (skip_mpv build)".to_string()
+    } else {
+        find_on_path("mpv").map_or("not found on PATH".to_string(), |path| {
+            path.display().to_string()
+        })
+    };
+
+    println!("RadioCLI v{}", VERSION);
+    println!("commit:   {}", env!("RADIO_CLI_GIT_COMMIT"));
+    println!("built:    {}", env!("RADIO_CLI_BUILD_DATE"));
+    println!("target:   {}", env!("RADIO_CLI_TARGET"));
+    println!(
+        "features: {}",
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    );
+    println!("mpv:      {}", mpv);
+}
+
+// The first executable with this name in a PATH directory
+fn find_on_path(program: &str) -> Option<std::path::PathBuf> {
+    let program = format!("{}{}", program, env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&program))
+        .find(|path| path.is_file())
+}
+
+// Check the database for problems and clean up duplicate stations
+fn run_doctor(dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let db_path = app::get_database_path()?;
+    println!("Checking {}", db_path.display());
+
+    if db_path.exists() {
+        if let Err(e) = db::validate_database_file(&db_path) {
+            eprintln!("Problem found: {}", e);
+            std::process::exit(1);
+        }
+        println!("Integrity check passed.");
+    }
+
+    dedupe_stations(dry_run, db::DedupeBy::Url)
 }
 
 // Function to test the duplicate URL removal functionality
@@ -120,7 +1014,8 @@ fn test_duplicate_url_removal() -> Result<(), Box<dyn Error>> {
 
     // Now remove duplicates
     println!("\nRemoving duplicates...");
-    db::remove_duplicate_urls(&conn)?;
+    let removed = db::remove_duplicate_urls(&conn)?;
+    println!("Removed {} duplicate entries", removed);
 
     // Count stations after deduplication
     let count_after: i32 = conn.query_row("SELECT COUNT(*) FROM stations", [], |row| row.get(0))?;
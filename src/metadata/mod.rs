@@ -0,0 +1,69 @@
+// Settings key for the user's StreamTitle format, e.g. "{title} - {artist}"
+pub const METADATA_FORMAT_SETTING: &str = "metadata_format";
+
+// Separators tried (in order) when no format is configured; all assume "Artist - Title"
+const AUTO_SEPARATORS: [&str; 5] = [" - ", " – ", " — ", ": ", " / "];
+
+const ARTIST_PLACEHOLDER: &str = "{artist}";
+const TITLE_PLACEHOLDER: &str = "{title}";
+
+// Split an ICY StreamTitle into (artist, title).
+// `format` is a pattern containing {artist} and {title} separated by literal text;
+// without one, common "Artist - Title" separators are auto-detected.
+pub fn parse_song(stream_title: &str, format: Option<&str>) -> Option<(String, String)> {
+    let text = stream_title.trim();
+
+    match format.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(format) => parse_with_format(text, format),
+        None => AUTO_SEPARATORS
+            .iter()
+            .find_map(|separator| split_pair(text, separator, true)),
+    }
+}
+
+fn parse_with_format(text: &str, format: &str) -> Option<(String, String)> {
+    let artist_pos = format.find(ARTIST_PLACEHOLDER)?;
+    let title_pos = format.find(TITLE_PLACEHOLDER)?;
+
+    // Work out which placeholder comes first and the literal text around them
+    let (first_pos, first_len, second_pos, second_len) = if artist_pos < title_pos {
+        (
+            artist_pos,
+            ARTIST_PLACEHOLDER.len(),
+            title_pos,
+            TITLE_PLACEHOLDER.len(),
+        )
+    } else {
+        (
+            title_pos,
+            TITLE_PLACEHOLDER.len(),
+            artist_pos,
+            ARTIST_PLACEHOLDER.len(),
+        )
+    };
+    let prefix = &format[..first_pos];
+    let separator = format.get(first_pos + first_len..second_pos)?;
+    let suffix = &format[second_pos + second_len..];
+
+    if separator.is_empty() {
+        return None;
+    }
+
+    let text = text.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    split_pair(text, separator, artist_pos < title_pos)
+}
+
+// Split on the first separator, returning (artist, title)
+fn split_pair(text: &str, separator: &str, artist_first: bool) -> Option<(String, String)> {
+    let (first, second) = text.split_once(separator)?;
+    let (first, second) = (first.trim(), second.trim());
+    if first.is_empty() || second.is_empty() {
+        return None;
+    }
+
+    if artist_first {
+        Some((first.to_string(), second.to_string()))
+    } else {
+        Some((second.to_string(), first.to_string()))
+    }
+}
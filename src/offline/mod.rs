@@ -0,0 +1,28 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Setting that starts every session offline, like --offline does for one
+pub const OFFLINE_SETTING: &str = "offline";
+
+// Shown wherever a network feature is skipped
+pub const OFFLINE_MESSAGE: &str = "Offline mode — network disabled";
+
+// Process-wide, so background threads and free functions see it too
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+// Whether a station plays from disk rather than over the network:
+// a file:// URL or a plain path
+pub fn is_local_url(url: &str) -> bool {
+    let url = url.trim();
+    match url.split_once("://") {
+        Some((scheme, _)) => scheme.eq_ignore_ascii_case("file"),
+        None => !url.is_empty(),
+    }
+}
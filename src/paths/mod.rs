@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+// Environment variable that moves everything the app writes under one folder
+pub const DATA_DIR_ENV: &str = "RADIO_CLI_DATA_DIR";
+
+// The override from RADIO_CLI_DATA_DIR, if set
+fn data_dir_override() -> Option<PathBuf> {
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+// Root for the database and other persistent files, created if missing.
+// RADIO_CLI_DATA_DIR wins, then XDG_DATA_HOME, then the platform default.
+pub fn data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let data_dir = match (data_dir_override(), std::env::var_os("XDG_DATA_HOME")) {
+        (Some(dir), _) => dir,
+        (None, Some(dir)) => PathBuf::from(dir).join("radio_cli"),
+        (None, None) => platform_data_dir()?,
+    };
+
+    fs::create_dir_all(&data_dir)?;
+    Ok(data_dir)
+}
+
+// Root for logs, the PID file and other disposable files. With
+// RADIO_CLI_DATA_DIR set this is its "cache" subfolder.
+pub fn cache_dir() -> Option<PathBuf> {
+    match data_dir_override() {
+        Some(dir) => Some(dir.join("cache")),
+        None => dirs_next::cache_dir().map(|dir| dir.join("radio_cli")),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let home = dirs_next::home_dir().ok_or("Could not find home directory")?;
+    Ok(home
+        .join("Library")
+        .join("Application Support")
+        .join("radio_cli"))
+}
+
+#[cfg(target_os = "linux")]
+fn platform_data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let home = dirs_next::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".local").join("share").join("radio_cli"))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let dir = dirs_next::data_dir().ok_or("Could not find data directory")?;
+    Ok(dir.join("radio_cli"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn platform_data_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let home = dirs_next::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".radio_cli"))
+}
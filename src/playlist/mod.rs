@@ -0,0 +1,43 @@
+use crate::db::Station;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// Build an extended M3U playlist
+pub fn to_m3u(stations: &[Station]) -> String {
+    let mut playlist = String::from("#EXTM3U\n");
+    for station in stations {
+        playlist.push_str(&format!("#EXTINF:-1,{}\n{}\n", station.name, station.url));
+    }
+    playlist
+}
+
+// Build a PLS playlist
+pub fn to_pls(stations: &[Station]) -> String {
+    let mut playlist = String::from("[playlist]\n");
+    for (i, station) in stations.iter().enumerate() {
+        let n = i + 1;
+        playlist.push_str(&format!(
+            "File{n}={}\nTitle{n}={}\nLength{n}=-1\n",
+            station.url, station.name
+        ));
+    }
+    playlist.push_str(&format!("NumberOfEntries={}\nVersion=2\n", stations.len()));
+    playlist
+}
+
+// Write stations to a playlist file; the format follows the extension (.pls, otherwise M3U)
+pub fn write_playlist(path: &Path, stations: &[Station]) -> Result<(), Box<dyn Error>> {
+    let is_pls = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("pls"))
+        .unwrap_or(false);
+
+    let contents = if is_pls {
+        to_pls(stations)
+    } else {
+        to_m3u(stations)
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
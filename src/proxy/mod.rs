@@ -0,0 +1,82 @@
+use std::env;
+
+// Settings key for the app-specific proxy override
+pub const PROXY_SETTING: &str = "http_proxy";
+
+// Environment variables checked (in order) when no override is configured
+const PROXY_ENV_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"];
+
+// Hosts listed here are always reached directly, whichever proxy is in use
+const NO_PROXY_ENV_VARS: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+// Work out which proxy to use: the app setting wins over the environment.
+// A setting of "none" or "off" explicitly disables the proxy.
+pub fn resolve_proxy(setting: Option<&str>) -> Option<String> {
+    if let Some(value) = setting.map(str::trim) {
+        return match value {
+            "" | "none" | "off" => None,
+            proxy => Some(proxy.to_string()),
+        };
+    }
+
+    PROXY_ENV_VARS
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+// Whether a comma-separated NO_PROXY list covers the host of `url`. An
+// entry matches the host itself and its subdomains ("example.com" and
+// ".example.com" both cover radio.example.com); "*" matches everything.
+pub fn no_proxy_matches(no_proxy: &str, url: &str) -> bool {
+    let host = match reqwest::Url::parse(url) {
+        Ok(url) => match url.host_str() {
+            Some(host) => host
+                .trim_matches(|c| c == '[' || c == ']')
+                .to_ascii_lowercase(),
+            None => return false,
+        },
+        Err(_) => return false,
+    };
+    no_proxy
+        .split(',')
+        .map(|entry| entry.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            entry == "*"
+                || host == entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+}
+
+// The proxy to use for `url`: none when NO_PROXY in the environment lists its host
+pub fn proxy_for<'a>(proxy: Option<&'a str>, url: &str) -> Option<&'a str> {
+    let bypassed = NO_PROXY_ENV_VARS
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .any(|list| no_proxy_matches(&list, url));
+    proxy.filter(|_| !bypassed)
+}
+
+// Build the mpv command-line flag for a proxy
+pub fn mpv_proxy_arg(proxy: &str) -> String {
+    format!("--http-proxy={}", proxy)
+}
+
+// Configure a reqwest client builder to use the given proxy, if any,
+// except for the hosts NO_PROXY lists
+pub fn apply_to_client(
+    builder: reqwest::ClientBuilder,
+    proxy: Option<&str>,
+) -> Result<reqwest::ClientBuilder, reqwest::Error> {
+    match proxy {
+        Some(proxy) => {
+            let proxy = reqwest::Proxy::all(proxy)?.no_proxy(reqwest::NoProxy::from_env());
+            Ok(builder.proxy(proxy))
+        }
+        None => Ok(builder.no_proxy()),
+    }
+}
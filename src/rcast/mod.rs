@@ -28,15 +28,22 @@ pub struct RcastStation {
     pub listeners: Option<u32>,
 }
 
-// Function to fetch stations from rcast.net
-pub async fn fetch_stations() -> Result<Vec<RcastStation>, RcastError> {
+// Function to fetch stations from rcast.net, optionally through a proxy
+pub async fn fetch_stations(proxy: Option<&str>) -> Result<Vec<RcastStation>, RcastError> {
     // URL for the Icecast stations from rcast.net
     let url = "https://www.rcast.net/dir?action=search&search=icecast&sortby=1";
 
+    if crate::offline::is_offline() {
+        return Err(RcastError::NetworkError(
+            crate::offline::OFFLINE_MESSAGE.to_string(),
+        ));
+    }
+
     // Use reqwest to send the HTTP request
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-        .build()
+    let builder = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36");
+    let client = crate::proxy::apply_to_client(builder, proxy)
+        .and_then(|builder| builder.build())
         .map_err(|e| RcastError::NetworkError(format!("Failed to build client: {}", e)))?;
 
     // Send request
@@ -96,7 +103,7 @@ fn parse_stations_from_html(html: &str) -> Result<Vec<RcastStation>, RcastError>
 
                     if let Some(id_pos) = html.find(&id_marker) {
                         // Search backwards from this position for h4 tags which often contain station names
-                        let name_start = if id_pos > 2000 { id_pos - 2000 } else { 0 };
+                        let name_start = id_pos.saturating_sub(2000);
                         let name_context = &html[name_start..id_pos];
 
                         // Look for h4 tags that might contain station name
@@ -187,7 +194,6 @@ fn clean_html(text: &str) -> String {
     normalized.trim().to_string()
 }
 
-#[allow(dead_code)]
 // Convert a RcastStation to a database Station
 pub fn rcast_to_db_station(rcast_station: &RcastStation) -> crate::db::Station {
     crate::db::Station {
@@ -196,5 +202,58 @@ pub fn rcast_to_db_station(rcast_station: &RcastStation) -> crate::db::Station {
         url: rcast_station.url.clone(),
         favorite: false,
         description: rcast_station.description.clone(),
+        mpv_args: None,
+        pinned: false,
+        last_check_status: None,
+        last_check_time: None,
+    }
+}
+
+// Orders the directory list can be sorted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RcastSort {
+    Name,
+    Listeners,
+    Bitrate,
+}
+
+impl RcastSort {
+    // The sort after this one when cycling
+    pub fn next(self) -> Self {
+        match self {
+            RcastSort::Name => RcastSort::Listeners,
+            RcastSort::Listeners => RcastSort::Bitrate,
+            RcastSort::Bitrate => RcastSort::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RcastSort::Name => "name",
+            RcastSort::Listeners => "listeners",
+            RcastSort::Bitrate => "bitrate",
+        }
+    }
+
+    // Sort stations in place: names A-Z, listeners and bitrate highest
+    // first. Stations missing the value go last.
+    pub fn apply(self, stations: &mut [RcastStation]) {
+        match self {
+            RcastSort::Name => stations.sort_by_key(|s| s.name.to_lowercase()),
+            RcastSort::Listeners => stations
+                .sort_by_key(|s| std::cmp::Reverse(s.listeners.map(|n| n as i64).unwrap_or(-1))),
+            RcastSort::Bitrate => stations.sort_by_key(|s| {
+                std::cmp::Reverse(s.bitrate_kbps().map(|n| n as i64).unwrap_or(-1))
+            }),
+        }
+    }
+}
+
+impl RcastStation {
+    // The numeric part of the bitrate, e.g. 128 for "128 kbps"
+    pub fn bitrate_kbps(&self) -> Option<u32> {
+        let bitrate = self.bitrate.as_deref()?.trim();
+        let digits: String = bitrate.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
     }
 }
@@ -0,0 +1,165 @@
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// Last.fm API endpoint
+const LASTFM_API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+// Settings keys holding the Last.fm credentials
+pub const API_KEY_SETTING: &str = "lastfm_api_key";
+pub const API_SECRET_SETTING: &str = "lastfm_api_secret";
+pub const SESSION_KEY_SETTING: &str = "lastfm_session_key";
+
+#[derive(Clone)]
+pub struct LastFmConfig {
+    pub api_key: String,
+    pub api_secret: String,
+    pub session_key: String,
+}
+
+impl LastFmConfig {
+    // Load credentials from settings; scrobbling stays off unless all three are set
+    pub fn load(conn: &Connection) -> Option<Self> {
+        let get = |key| {
+            crate::db::get_setting(conn, key)
+                .ok()
+                .flatten()
+                .filter(|value| !value.trim().is_empty())
+        };
+
+        Some(LastFmConfig {
+            api_key: get(API_KEY_SETTING)?,
+            api_secret: get(API_SECRET_SETTING)?,
+            session_key: get(SESSION_KEY_SETTING)?,
+        })
+    }
+}
+
+// Tracks the current song and submits it once it has played long enough
+pub struct Scrobbler {
+    config: LastFmConfig,
+    log_path: PathBuf,
+    metadata_format: Option<String>,
+    current_song: Option<String>,
+    song_started: Instant,
+    song_timestamp: i64,
+    submitted: bool,
+    min_play: Duration, // How long a song must play before it counts as a scrobble
+}
+
+impl Scrobbler {
+    pub fn new(
+        config: LastFmConfig,
+        log_path: PathBuf,
+        metadata_format: Option<String>,
+        min_play: Duration,
+    ) -> Self {
+        Scrobbler {
+            config,
+            log_path,
+            metadata_format,
+            current_song: None,
+            song_started: Instant::now(),
+            song_timestamp: 0,
+            submitted: false,
+            min_play,
+        }
+    }
+
+    // Called periodically with the song currently reported by the stream
+    pub fn update(&mut self, song: Option<&str>) {
+        if song != self.current_song.as_deref() {
+            self.current_song = song.map(str::to_string);
+            self.song_started = Instant::now();
+            self.song_timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            self.submitted = false;
+            return;
+        }
+
+        if self.submitted || self.song_started.elapsed() < self.min_play {
+            return;
+        }
+        self.submitted = true;
+
+        let parsed = self
+            .current_song
+            .as_deref()
+            .and_then(|song| crate::metadata::parse_song(song, self.metadata_format.as_deref()));
+        if let Some((artist, track)) = parsed {
+            self.submit(artist, track);
+        }
+    }
+
+    // Send the scrobble in the background; failures only go to the log file
+    fn submit(&self, artist: String, track: String) {
+        let config = self.config.clone();
+        let log_path = self.log_path.clone();
+        let timestamp = self.song_timestamp;
+
+        thread::spawn(move || {
+            let result = tokio::runtime::Runtime::new()
+                .map_err(|e| e.to_string())
+                .and_then(|rt| rt.block_on(send_scrobble(&config, &artist, &track, timestamp)));
+
+            if let Err(e) = result {
+                log::warn!("Scrobble failed for {} - {}: {}", artist, track, e);
+                if let Ok(mut log) = OpenOptions::new().create(true).append(true).open(&log_path) {
+                    let _ = writeln!(log, "Scrobble failed for {} - {}: {}", artist, track, e);
+                }
+            }
+        });
+    }
+}
+
+// Sign the request parameters as described in the Last.fm API docs
+fn api_signature(params: &BTreeMap<&str, String>, secret: &str) -> String {
+    let mut raw = String::new();
+    for (key, value) in params {
+        raw.push_str(key);
+        raw.push_str(value);
+    }
+    raw.push_str(secret);
+    format!("{:x}", md5::compute(raw))
+}
+
+async fn send_scrobble(
+    config: &LastFmConfig,
+    artist: &str,
+    track: &str,
+    timestamp: i64,
+) -> Result<(), String> {
+    if crate::offline::is_offline() {
+        return Err(crate::offline::OFFLINE_MESSAGE.to_string());
+    }
+
+    let mut params = BTreeMap::new();
+    params.insert("method", "track.scrobble".to_string());
+    params.insert("artist", artist.to_string());
+    params.insert("track", track.to_string());
+    params.insert("timestamp", timestamp.to_string());
+    params.insert("api_key", config.api_key.clone());
+    params.insert("sk", config.session_key.clone());
+
+    let signature = api_signature(&params, &config.api_secret);
+    params.insert("api_sig", signature);
+    params.insert("format", "json".to_string());
+
+    let response = reqwest::Client::new()
+        .post(LASTFM_API_URL)
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::error::Error;
+
+// Service name the passwords are filed under in the OS keyring
+const SERVICE: &str = "radio_cli";
+
+// Keyring entry for a station's stream password
+fn entry(station_id: i32) -> Result<keyring::Entry, keyring::Error> {
+    keyring::Entry::new(SERVICE, &format!("station-{}", station_id))
+}
+
+// The password stored for a station, if any
+pub fn get(station_id: i32) -> Result<Option<String>, Box<dyn Error>> {
+    match entry(station_id)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn set(station_id: i32, password: &str) -> Result<(), Box<dyn Error>> {
+    entry(station_id)?.set_password(password)?;
+    Ok(())
+}
+
+// Remove a station's password; removing one that isn't there is fine
+pub fn delete(station_id: i32) -> Result<(), Box<dyn Error>> {
+    match entry(station_id)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
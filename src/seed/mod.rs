@@ -0,0 +1,137 @@
+use crate::db::SeedStation;
+use serde_json::Value;
+use std::time::Duration;
+
+// Environment variable naming a JSON starter pack to seed a new library from.
+// Unset, no request is made and the built-in stations are used.
+pub const SEED_URL_ENV: &str = "RADIO_CLI_SEED_URL";
+
+// A first run shouldn't hang on an unreachable server
+const SEED_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Starter pack compiled in by the bundled_stations feature. Packagers can
+// replace the file with their own list, in the same format.
+#[cfg(feature = "bundled_stations")]
+const BUNDLED_STATIONS: &str = include_str!("../../assets/bundled_stations.json");
+
+// The starter pack URL from RADIO_CLI_SEED_URL, if set
+pub fn seed_url() -> Option<String> {
+    std::env::var(SEED_URL_ENV)
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+// Parse a starter pack: either {"stations": [...]}, as written by
+// --export-json, or a bare array of {"name", "url", "description"} objects.
+// Entries without a name or a stream URL are skipped.
+pub fn parse_starter_pack(text: &str) -> Result<Vec<SeedStation>, String> {
+    let doc: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let entries = match doc.get("stations").unwrap_or(&doc) {
+        Value::Array(entries) => entries,
+        _ => return Err("Expected a list of stations".to_string()),
+    };
+
+    let stations: Vec<SeedStation> = entries
+        .iter()
+        .filter_map(|entry| {
+            let name = entry.get("name")?.as_str()?.trim();
+            let url = entry.get("url")?.as_str()?.trim();
+            if name.is_empty() || !crate::stream_info::is_valid_stream_url(url) {
+                return None;
+            }
+            Some(SeedStation {
+                name: name.to_string(),
+                url: url.to_string(),
+                description: entry
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+
+    if stations.is_empty() {
+        return Err("No usable stations in the starter pack".to_string());
+    }
+    Ok(stations)
+}
+
+// A station given on the command line as "name|url|description"; the
+// description is optional and may itself contain '|'
+pub fn parse_station_spec(spec: &str) -> Result<SeedStation, String> {
+    let mut fields = spec.splitn(3, '|').map(str::trim);
+    let name = fields.next().unwrap_or_default();
+    let Some(url) = fields.next() else {
+        return Err("Expected \"name|url|description\"".to_string());
+    };
+    if name.is_empty() {
+        return Err("The station needs a name".to_string());
+    }
+    if !crate::stream_info::is_valid_stream_url(url) {
+        return Err(format!("Not a stream URL: {}", url));
+    }
+    Ok(SeedStation {
+        name: name.to_string(),
+        url: url.to_string(),
+        description: fields
+            .next()
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+    })
+}
+
+pub async fn fetch_starter_pack(
+    url: &str,
+    proxy: Option<&str>,
+) -> Result<Vec<SeedStation>, String> {
+    let client = crate::stream_info::build_client(proxy, SEED_TIMEOUT)?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch starter pack: {}", e))?;
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read starter pack: {}", e))?;
+    parse_starter_pack(&text)
+}
+
+// The stations embedded in this build; empty without the bundled_stations feature
+pub fn bundled_stations() -> Vec<SeedStation> {
+    #[cfg(feature = "bundled_stations")]
+    match parse_starter_pack(BUNDLED_STATIONS) {
+        Ok(stations) => stations,
+        Err(e) => {
+            log::warn!("Ignoring the bundled stations: {}", e);
+            Vec::new()
+        }
+    }
+    #[cfg(not(feature = "bundled_stations"))]
+    Vec::new()
+}
+
+// The starter pack for a new library: the one at RADIO_CLI_SEED_URL, else the
+// bundled one. Empty when there's neither, in which case the built-in
+// stations are used instead.
+pub fn fetch_configured_starter_pack() -> Vec<SeedStation> {
+    let Some(url) = seed_url().filter(|_| !crate::offline::is_offline()) else {
+        return bundled_stations();
+    };
+    let proxy = crate::proxy::resolve_proxy(None);
+    let result = tokio::runtime::Runtime::new()
+        .map_err(|e| e.to_string())
+        .and_then(|rt| rt.block_on(fetch_starter_pack(&url, proxy.as_deref())));
+    match result {
+        Ok(stations) => {
+            log::info!("Seeded {} station(s) from {}", stations.len(), url);
+            stations
+        }
+        Err(e) => {
+            log::warn!("Not using the starter pack: {}", e);
+            bundled_stations()
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use lazy_static::lazy_static;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Give up on slow servers quickly; this runs while the user is waiting
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The pre-flight check before playing should answer almost instantly
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(3);
+
+// However many mirrors a station has, they're all checked within this long
+const MIRROR_CHECK_LIMIT: Duration = Duration::from_secs(5);
+
+// Content types servers use for .pls/.m3u playlists
+const PLAYLIST_CONTENT_TYPES: [&str; 4] = [
+    "audio/x-scpls",
+    "audio/scpls",
+    "audio/x-mpegurl",
+    "audio/mpegurl",
+];
+
+lazy_static! {
+    // Playlist URL -> the stream URL it pointed to, so each is fetched once
+    static ref RESOLVED_PLAYLISTS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+// Details a stream advertises about itself
+#[derive(Debug, Default, Clone)]
+pub struct StreamHeaders {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub genre: Option<String>,
+    pub bitrate: Option<String>,
+    pub web_page: bool,                  // The URL served HTML rather than audio
+    pub page_stream_url: Option<String>, // A stream the web page links to, if one was found
+}
+
+// Whether `url` looks like something mpv can stream: http(s) with a host
+pub fn is_valid_stream_url(url: &str) -> bool {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(parsed) => matches!(parsed.scheme(), "http" | "https") && parsed.host_str().is_some(),
+        Err(_) => false,
+    }
+}
+
+// Split pasted text into one URL per line, dropping blank lines. Returns
+// the valid stream URLs and the lines that weren't.
+pub fn split_url_list(text: &str) -> (Vec<String>, Vec<String>) {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .partition(|line| is_valid_stream_url(line))
+}
+
+// Request a stream URL and read its ICY headers (icy-name, icy-genre, icy-br).
+// If the URL points at a web page instead, its <title> is used as the name.
+// Playlist URLs (.pls/.m3u) are resolved to the stream they point at first.
+pub async fn probe_stream(url: &str, proxy: Option<&str>) -> Result<StreamHeaders, String> {
+    let client = build_client(proxy, PROBE_TIMEOUT)?;
+    let mut url = resolve_stream_url(&client, url).await?;
+
+    // Only the headers are read, so the audio body is never downloaded
+    let mut response = get_stream(&client, &url).await?;
+
+    // Playlists without a telling extension are only recognisable by type
+    if let Some(content_type) = playlist_content_type(response.headers()).map(str::to_string) {
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        let stream_url = parse_playlist(&body)
+            .ok_or_else(|| format!("No stream URL in {} playlist", content_type))?;
+        remember_resolved(&url, &stream_url);
+        url = stream_url;
+        response = get_stream(&client, &url).await?;
+    }
+
+    let mut headers = headers_from_map(response.headers());
+
+    let is_html = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.contains("text/html"))
+        .unwrap_or(false);
+
+    // Someone pasted the station's website: name it after the page and
+    // look for the stream it plays
+    if is_html {
+        let html = response.text().await.map_err(|e| e.to_string())?;
+        headers.web_page = true;
+        if headers.name.is_none() {
+            headers.name = extract_html_title(&html);
+        }
+        headers.page_stream_url = find_stream_link(&html, &url);
+    }
+
+    Ok(headers)
+}
+
+// Extract the first stream URL from a PLS (File1=...) or M3U playlist
+pub fn parse_playlist(body: &str) -> Option<String> {
+    let is_stream = |value: &str| value.starts_with("http://") || value.starts_with("https://");
+
+    // PLS: the lowest-numbered FileN= entry; M3U: the first non-comment line
+    let pls_entry = body
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            let number = key.trim().to_ascii_lowercase();
+            let number: u32 = number.strip_prefix("file")?.parse().ok()?;
+            Some((number, value.trim()))
+        })
+        .filter(|(_, value)| is_stream(value))
+        .min_by_key(|(number, _)| *number)
+        .map(|(_, value)| value.to_string());
+
+    pls_entry.or_else(|| {
+        body.lines()
+            .map(str::trim)
+            .find(|line| !line.starts_with('#') && is_stream(line))
+            .map(str::to_string)
+    })
+}
+
+// Whether a URL names a .pls/.m3u playlist rather than a stream.
+// HLS (.m3u8) is left alone; mpv plays it directly and it has no ICY headers.
+pub fn is_playlist_url(url: &str) -> bool {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    path.ends_with(".pls") || path.ends_with(".m3u")
+}
+
+// Quick check that a station answers with audio, so a dead URL is
+// reported right away instead of mpv hanging on it
+pub async fn check_stream(
+    url: &str,
+    proxy: Option<&str>,
+    credentials: Option<&crate::db::Credentials>,
+) -> Result<(), String> {
+    let client = build_client(proxy, PREFLIGHT_TIMEOUT)?;
+    let url = resolve_stream_url(&client, url).await?;
+
+    let mut request = client.get(&url).header("Icy-MetaData", "1");
+    if let Some(login) = credentials {
+        request = request.basic_auth(&login.username, Some(&login.password));
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    match content_type {
+        _ if is_playable_content_type(content_type) => Ok(()),
+        Some(value) if value.contains("text/html") => {
+            Err("it's a web page, not an audio stream".to_string())
+        }
+        _ => Err(format!("not audio ({})", content_type.unwrap_or_default())),
+    }
+}
+
+// The first of a station's stream URLs, in order, that answers with audio.
+// They're all checked at once so dead mirrors don't add up. URLs that can't
+// be checked (files, rtsp:// and the like) go straight to mpv, so they count
+// as answering.
+pub async fn first_reachable(
+    candidates: &[String],
+    proxy: Option<&str>,
+    credentials: Option<&crate::db::Credentials>,
+) -> Result<String, String> {
+    let checks: Vec<_> = candidates
+        .iter()
+        .map(|url| {
+            let url = url.clone();
+            let proxy = proxy.map(str::to_string);
+            let credentials = credentials.cloned();
+            tokio::spawn(async move {
+                if !is_valid_stream_url(&url) {
+                    return Ok(());
+                }
+                check_stream(&url, proxy.as_deref(), credentials.as_ref()).await
+            })
+        })
+        .collect();
+
+    let in_order = async {
+        let mut failure = "no stream URL".to_string();
+        for (url, check) in candidates.iter().zip(checks) {
+            match check.await.map_err(|e| e.to_string()).and_then(|r| r) {
+                Ok(()) => return Ok(url.clone()),
+                Err(e) => {
+                    log::warn!("Pre-flight check failed for {}: {}", url, e);
+                    failure = e;
+                }
+            }
+        }
+        Err(failure)
+    };
+    tokio::time::timeout(MIRROR_CHECK_LIMIT, in_order)
+        .await
+        .unwrap_or_else(|_| Err("no answer in time".to_string()))
+}
+
+// Whether a response's content type could be something mpv plays. Servers
+// are sloppy about this, so only clearly non-audio types are rejected.
+pub fn is_playable_content_type(content_type: Option<&str>) -> bool {
+    let content_type = match content_type {
+        Some(value) => value.trim().to_ascii_lowercase(),
+        None => return true,
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    essence.is_empty()
+        || essence.starts_with("audio/")
+        || essence.starts_with("video/")
+        || matches!(
+            essence,
+            "application/ogg"
+                | "application/octet-stream"
+                | "application/vnd.apple.mpegurl"
+                | "application/x-mpegurl"
+                | "application/dash+xml"
+        )
+}
+
+pub fn build_client(proxy: Option<&str>, timeout: Duration) -> Result<reqwest::Client, String> {
+    if crate::offline::is_offline() {
+        return Err(crate::offline::OFFLINE_MESSAGE.to_string());
+    }
+    let builder = reqwest::Client::builder()
+        .user_agent(concat!("radio_cli/", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout);
+    crate::proxy::apply_to_client(builder, proxy)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Failed to build client: {}", e))
+}
+
+async fn get_stream(client: &reqwest::Client, url: &str) -> Result<reqwest::Response, String> {
+    let response = client
+        .get(url)
+        .header("Icy-MetaData", "1")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(response)
+}
+
+// The stream URL to probe: a cached resolution, the first entry of a
+// playlist URL, or the URL itself
+async fn resolve_stream_url(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    if let Some(resolved) = RESOLVED_PLAYLISTS
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(url).cloned())
+    {
+        return Ok(resolved);
+    }
+    if !is_playlist_url(url) {
+        return Ok(url.to_string());
+    }
+
+    let body = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let stream_url = parse_playlist(&body).ok_or("No stream URL in playlist")?;
+    remember_resolved(url, &stream_url);
+    Ok(stream_url)
+}
+
+fn remember_resolved(playlist_url: &str, stream_url: &str) {
+    if let Ok(mut cache) = RESOLVED_PLAYLISTS.lock() {
+        cache.insert(playlist_url.to_string(), stream_url.to_string());
+    }
+}
+
+fn playlist_content_type(map: &HeaderMap) -> Option<&str> {
+    let content_type = map.get(CONTENT_TYPE)?.to_str().ok()?;
+    PLAYLIST_CONTENT_TYPES
+        .iter()
+        .any(|playlist| content_type.starts_with(playlist))
+        .then_some(content_type)
+}
+
+// Pull the icy-* fields out of a response's headers
+pub fn headers_from_map(map: &HeaderMap) -> StreamHeaders {
+    let get = |key: &str| {
+        map.get(key)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    StreamHeaders {
+        name: get("icy-name"),
+        description: get("icy-description"),
+        genre: get("icy-genre"),
+        bitrate: get("icy-br").map(|br| format!("{} kbps", br)),
+        ..Default::default()
+    }
+}
+
+// File extensions of streams and playlists a page might link to
+const STREAM_LINK_EXTENSIONS: [&str; 9] = [
+    ".mp3", ".aac", ".aacp", ".ogg", ".opus", ".flac", ".m3u", ".m3u8", ".pls",
+];
+
+// Find the first stream a web page links to: an <audio>/<source> src or a
+// link to an audio file, playlist or Shoutcast-style `/;` mount, resolved
+// against the page URL
+pub fn find_stream_link(html: &str, page_url: &str) -> Option<String> {
+    let base = reqwest::Url::parse(page_url).ok()?;
+    attribute_values(html, "src")
+        .chain(attribute_values(html, "href"))
+        .filter_map(|value| base.join(value.trim()).ok())
+        .find(|link| {
+            let path = link.path().to_ascii_lowercase();
+            matches!(link.scheme(), "http" | "https")
+                && (STREAM_LINK_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+                    || path.ends_with("/;")
+                    || path.ends_with("/stream"))
+        })
+        .map(|link| link.to_string())
+}
+
+// The quoted values of every `name="..."` attribute in an HTML document
+fn attribute_values<'a>(html: &'a str, name: &str) -> impl Iterator<Item = &'a str> {
+    let lower = html.to_ascii_lowercase();
+    let pattern = format!("{}=", name);
+    let mut values = Vec::new();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(&pattern) {
+        let start = from + found + pattern.len();
+        from = start;
+        let quote = match html[start..].chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => continue,
+        };
+        if let Some(len) = html[start + 1..].find(quote) {
+            values.push(&html[start + 1..start + 1 + len]);
+            from = start + 1 + len;
+        }
+    }
+    values.into_iter()
+}
+
+// Find the contents of the first <title> tag
+fn extract_html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let open_end = start + lower[start..].find('>')? + 1;
+    let close = open_end + lower[open_end..].find("</title>")?;
+
+    let title = html.get(open_end..close)?.trim();
+    if title.is_empty() {
+        None
+    } else {
+        Some(title.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
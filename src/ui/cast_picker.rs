@@ -0,0 +1,69 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+// What the cast device popup shows
+pub struct CastPicker {
+    pub devices: Vec<String>, // Device names, the one being cast to marked
+    pub selected: usize,
+    pub searching: bool, // A search of the network is still running
+}
+
+pub fn render_cast_picker(
+    f: &mut Frame,
+    picker: &CastPicker,
+    spinner: super::Spinner,
+    list_style: &super::ListStyle,
+    area: Rect,
+) {
+    // Create a centered popup
+    let popup_width = 50.min(area.width);
+    let popup_height = 12.min(area.height);
+    let popup_rect = Rect::new(
+        area.x + (area.width - popup_width) / 2,
+        area.y + (area.height - popup_height) / 2,
+        popup_width,
+        popup_height,
+    );
+    f.render_widget(Clear, popup_rect);
+
+    let title = if picker.searching {
+        format!("Cast to Device {}", spinner.label("searching…"))
+    } else {
+        "Cast to Device".to_string()
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    if picker.devices.is_empty() {
+        let message = if picker.searching {
+            spinner.label("Looking for devices on the network…")
+        } else {
+            "No devices found. Press r to search again.".to_string()
+        };
+        f.render_widget(
+            Paragraph::new(message)
+                .block(block)
+                .style(Style::default().fg(Color::White)),
+            popup_rect,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .devices
+        .iter()
+        .map(|name| ListItem::new(name.as_str()).style(Style::default().fg(Color::White)))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    f.render_stateful_widget(
+        list_style.apply(List::new(items).block(block)),
+        popup_rect,
+        &mut state,
+    );
+}
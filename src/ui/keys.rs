@@ -0,0 +1,266 @@
+use crate::app::AppMode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+// Key bindings for each mode, most important first so the footer can drop
+// the tail when the terminal is too narrow to show everything
+pub fn bindings(mode: &AppMode) -> &'static [(&'static str, &'static str)] {
+    match mode {
+        AppMode::Normal => &[
+            ("⏎", "Play"),
+            ("↑/↓", "Navigate"),
+            ("s", "Stop"),
+            ("`", "Last Station"),
+            ("/", "Search"),
+            ("m", "Mute/Unmute"),
+            ("+/-", "Volume"),
+            ("f", "Favorite"),
+            ("P", "Pin to Top"),
+            ("a", "Add"),
+            ("p", "Add From Clipboard"),
+            ("e", "Edit"),
+            ("d", "Delete"),
+            ("Tab", "RCast"),
+            (":", "Commands"),
+            ("q", "Quit"),
+            ("Space", "Mark"),
+            ("x", "Export Playlist"),
+            ("F", "Copy Favorites (M3U)"),
+            ("c", "Copy mpv Command"),
+            ("o", "Open Externally"),
+            ("C", "Cast to Device"),
+            ("R", "Refresh Now Playing"),
+            ("u/Ctrl+R", "Undo/Redo"),
+            ("0-9", "Set Volume"),
+            (",/.", "Seek"),
+            ("t", "Toggle Top Stations"),
+            ("T", "Top: All Time/Week/Month"),
+            ("h", "History"),
+            ("i", "Station Details"),
+            ("!", "Failing Stations Only"),
+            ("v", "Vis Menu"),
+            ("g", "Browse Tags"),
+            ("V", "Vis/Stats/Split"),
+            ("Z", "Freeze Vis"),
+            ("[/]", "Fewer/More Stars"),
+            ("H", "Hide Footer"),
+            ("Alt+/", "Last Search"),
+            ("Alt+A-Z", "Stations by Letter"),
+        ],
+        AppMode::AddingStation => &[
+            ("Enter", "Confirm (URL only: fill in details)"),
+            ("Tab", "Next Field"),
+            ("Esc", "Cancel"),
+            ("↑/↓ Tab", "Pick/Complete Tag"),
+        ],
+        AppMode::EditingStation => &[
+            ("Enter", "Save"),
+            (
+                "Tab",
+                "Next Field (name, URL, description, tags, mpv args, login, mirrors)",
+            ),
+            ("Esc", "Cancel"),
+            ("↑/↓ Tab", "Pick/Complete Tag"),
+        ],
+        AppMode::DeletingStation => &[("y", "Confirm Delete"), ("n/Esc", "Cancel")],
+        AppMode::VisualizationMenu => {
+            &[("↑/↓", "Navigate"), ("Enter", "Select"), ("Esc", "Cancel")]
+        }
+        AppMode::RcastStations => &[
+            ("⏎", "Play"),
+            ("↑/↓", "Navigate"),
+            ("Tab", "Main View"),
+            ("a", "Add to Stations"),
+            ("o", "Sort: Name/Listeners/Bitrate"),
+            ("PgUp/PgDn", "Scroll Info"),
+            ("x", "Hide"),
+            ("m", "Mute/Unmute"),
+            ("+/-", "Volume"),
+            ("/", "Search"),
+            ("q", "Quit"),
+            ("r", "Refresh"),
+            ("t", "Toggle Top Stations"),
+            ("T", "Top: All Time/Week/Month"),
+            ("V", "Vis/Stats/Split"),
+            ("H", "Hide Footer"),
+        ],
+        AppMode::Searching => &[
+            ("↑/↓", "Navigate"),
+            ("⏎", "Play Selected"),
+            ("↑ (empty)", "Past Searches"),
+            ("Esc", "Cancel"),
+            ("Ctrl+F", "Favorites Only"),
+            ("Alt+F", "Favorite Selected"),
+            ("Ctrl+U", "Clear"),
+            ("Type", "Search..."),
+        ],
+        AppMode::CommandPalette => &[
+            ("⏎", "Run"),
+            ("↑/↓", "Navigate"),
+            ("Esc", "Cancel"),
+            ("Type", "Filter..."),
+        ],
+        AppMode::ConfirmingImport => &[("y/Enter", "Import New Stations"), ("n/Esc", "Cancel")],
+        AppMode::RewritingUrls => &[
+            ("Tab", "Next Field"),
+            ("Enter", "Preview Changes"),
+            ("Esc", "Cancel"),
+        ],
+        AppMode::ConfirmingRewrite => &[("y/Enter", "Rewrite URLs"), ("n/Esc", "Cancel")],
+        AppMode::TagBrowser => &[
+            ("↑/↓", "Navigate"),
+            ("⏎/→", "Open Tag / Play"),
+            ("Esc/←", "Back"),
+        ],
+        AppMode::ExportingStations => &[("Enter", "Export (.pls or .m3u)"), ("Esc", "Cancel")],
+        AppMode::StationDetail => &[("⏎", "Play"), ("r", "Check Again"), ("Esc/i", "Back")],
+        #[cfg(feature = "cast")]
+        AppMode::CastPicker => &[
+            ("↑/↓", "Navigate"),
+            ("Enter", "Cast"),
+            ("r", "Search Again"),
+            ("Esc", "Cancel"),
+        ],
+    }
+}
+
+// Modes where `?` opens the full key list (the others are typing text)
+pub fn has_key_help(mode: &AppMode) -> bool {
+    matches!(mode, AppMode::Normal | AppMode::RcastStations)
+}
+
+// Build a single-line footer that fits in `width` columns, eliding the less
+// important bindings with "…" when they don't fit
+pub fn footer_text(mode: &AppMode, width: usize) -> String {
+    let entries: Vec<String> = bindings(mode)
+        .iter()
+        .map(|(key, action)| format!("{}: {}", key, action))
+        .collect();
+    let more = if has_key_help(mode) {
+        "…  ?: All Keys"
+    } else {
+        "…"
+    };
+
+    let full = entries.join("  ");
+    if full.chars().count() <= width {
+        return full;
+    }
+
+    // Keep as many bindings as fit alongside the elision marker
+    let budget = width.saturating_sub(more.chars().count() + 2);
+    let mut text = String::new();
+    for entry in &entries {
+        let needed = if text.is_empty() {
+            entry.chars().count()
+        } else {
+            text.chars().count() + 2 + entry.chars().count()
+        };
+        if needed > budget {
+            break;
+        }
+        if !text.is_empty() {
+            text.push_str("  ");
+        }
+        text.push_str(entry);
+    }
+
+    if text.is_empty() {
+        more.to_string()
+    } else {
+        format!("{}  {}", text, more)
+    }
+}
+
+// The basics, shown once on first run
+const WELCOME_KEYS: [(&str, &str); 6] = [
+    ("↑/↓", "Choose a station"),
+    ("⏎", "Play it (s stops)"),
+    ("a", "Add a station by its stream URL"),
+    ("Tab", "Browse stations online"),
+    ("/", "Search"),
+    ("q", "Quit"),
+];
+
+pub fn render_welcome_popup(f: &mut Frame) {
+    let size = f.size();
+    let mut lines = vec!["Welcome to radio_cli!".to_string(), String::new()];
+    lines.extend(
+        WELCOME_KEYS
+            .iter()
+            .map(|(key, action)| format!("{:>5}  {}", key, action)),
+    );
+    lines.push(String::new());
+    lines.push("Press ? at any time for every key, or : for the command palette.".to_string());
+
+    let content_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let popup_width = (content_width as u16 + 4).min(size.width.saturating_sub(4));
+    let popup_height = (lines.len() as u16 + 2).min(size.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (size.width - popup_width) / 2,
+        y: (size.height - popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    f.render_widget(Clear, popup_area);
+
+    let popup = Paragraph::new(lines.join("\n"))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title("Welcome (any key to continue)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green))
+                .style(Style::default().bg(Color::Black)),
+        );
+
+    f.render_widget(popup, popup_area);
+}
+
+// Function to render the full key list for the current mode
+pub fn render_key_help_popup(f: &mut Frame, mode: &AppMode) {
+    let size = f.size();
+    let entries = bindings(mode);
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|(key, action)| format!("{:>width$}  {}", key, action, width = key_width))
+        .collect();
+
+    // Size the popup to its content, clamped to the terminal
+    let content_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let popup_width = (content_width as u16 + 4).min(size.width.saturating_sub(4));
+    let popup_height = (lines.len() as u16 + 2).min(size.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (size.width - popup_width) / 2,
+        y: (size.height - popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    f.render_widget(Clear, popup_area);
+
+    let popup = Paragraph::new(lines.join("\n"))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .title("Keys (any key to close)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .style(Style::default().bg(Color::Black)),
+        );
+
+    f.render_widget(popup, popup_area);
+}
@@ -0,0 +1,73 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::List,
+};
+use std::str::FromStr;
+
+// Settings keys for the selection marker and the selected row's style
+pub const HIGHLIGHT_SYMBOL_SETTING: &str = "highlight_symbol";
+pub const HIGHLIGHT_STYLE_SETTING: &str = "highlight_style";
+
+// How the selected entry of every list is drawn
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListStyle {
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Default for ListStyle {
+    fn default() -> Self {
+        ListStyle {
+            symbol: ">> ".to_string(),
+            style: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+impl ListStyle {
+    // Build from the saved settings; anything unset or unreadable keeps the default
+    pub fn from_settings(symbol: Option<&str>, style: Option<&str>) -> Self {
+        let default = ListStyle::default();
+        ListStyle {
+            symbol: symbol.map(str::to_string).unwrap_or(default.symbol),
+            style: style
+                .and_then(|spec| {
+                    let parsed = parse_style(spec);
+                    if parsed.is_none() {
+                        log::warn!("Ignoring unreadable highlight style: {}", spec);
+                    }
+                    parsed
+                })
+                .unwrap_or(default.style),
+        }
+    }
+
+    // Give a list this selection marker and style
+    pub fn apply<'a>(&'a self, list: List<'a>) -> List<'a> {
+        list.highlight_style(self.style)
+            .highlight_symbol(&self.symbol)
+    }
+}
+
+// Parse a style like "black on yellow bold", "reversed" or "#ffaf00 underlined":
+// a foreground color, "on" and a background color, and modifiers, in any order
+fn parse_style(spec: &str) -> Option<Style> {
+    let mut style = Style::default();
+    let mut words = spec.split_whitespace();
+    while let Some(word) = words.next() {
+        let word = word.to_ascii_lowercase();
+        style = match word.as_str() {
+            "on" => style.bg(Color::from_str(words.next()?).ok()?),
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underline" | "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reverse" | "reversed" => style.add_modifier(Modifier::REVERSED),
+            color => style.fg(Color::from_str(color).ok()?),
+        };
+    }
+    (style != Style::default()).then_some(style)
+}
@@ -1,53 +1,225 @@
 use crate::app::AppMode;
-use crate::audio::AudioVisualizer;
-use crate::db::{format_play_time, get_station_stats, get_top_stations, Station};
+use crate::audio::{AudioVisualizer, Connectivity};
+use crate::db::{
+    format_play_time, format_relative_time, Station, StatsSnapshot, TopStationsPeriod,
+};
 use crate::visualizations::VisualizationManager;
-use rusqlite::{params, Connection};
+use std::collections::HashSet;
+#[cfg(feature = "cast")]
+mod cast_picker;
+mod keys;
+mod list_style;
+mod palette;
 mod popup;
+mod progress;
 mod rcast_stations;
+mod spinner;
+mod station_detail;
+mod tag_browser;
+mod theme;
 mod vis_menu;
+mod volume;
 
+#[cfg(feature = "cast")]
+pub use cast_picker::CastPicker;
+pub use list_style::{ListStyle, HIGHLIGHT_STYLE_SETTING, HIGHLIGHT_SYMBOL_SETTING};
+pub use progress::format_clock;
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Span,
-    widgets::{canvas::Canvas, Block, Borders, List, ListItem, ListState, Paragraph},
+    text::{Line, Span},
+    widgets::{
+        canvas::Canvas, Block, Borders, List, ListItem, ListState, Paragraph, Sparkline, Wrap,
+    },
     Frame,
 };
 pub use rcast_stations::render_rcast_stations;
+pub use spinner::Spinner;
+pub use station_detail::{Reachability, StationDetail};
+pub use theme::{Theme, THEME_SETTING};
+
+// What fills the top of the right pane in the station view
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RightPane {
+    Visualization,
+    #[default]
+    Stats,
+    Split, // A small visualization above the stats
+}
+
+impl RightPane {
+    // The layout after this one when cycling with V
+    pub fn next(self) -> Self {
+        match self {
+            RightPane::Visualization => RightPane::Stats,
+            RightPane::Stats => RightPane::Split,
+            RightPane::Split => RightPane::Visualization,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RightPane::Visualization => "visualization",
+            RightPane::Stats => "stats",
+            RightPane::Split => "split",
+        }
+    }
+
+    // Parse the saved setting, which stores the label
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "visualization" | "vis" => Some(RightPane::Visualization),
+            "stats" => Some(RightPane::Stats),
+            "split" | "both" => Some(RightPane::Split),
+            _ => None,
+        }
+    }
+}
+
+// Everything the UI needs to render a single frame
+pub struct UiContext<'a> {
+    pub stations: &'a [Station],
+    pub list_state: &'a mut ListState,
+    pub visualizer: &'a AudioVisualizer,
+    pub mode: &'a AppMode,
+    pub add_station_name: &'a str,
+    pub add_station_url: &'a str,
+    pub add_station_desc: &'a str,
+    pub add_station_tags: &'a str,
+    pub input_field: usize,
+    pub input_cursor: usize,
+    pub tag_suggestions: &'a [String],
+    pub tag_suggestion: usize,
+    pub tag_counts: &'a [(String, usize)],
+    pub tag_list_state: &'a mut ListState,
+    pub tag_stations: &'a [Station],
+    pub tag_station_state: &'a mut ListState,
+    pub tag_stations_focused: bool,
+    pub import_preview: crate::db::ImportPreview,
+    pub vis_manager: &'a VisualizationManager,
+    pub vis_menu_state: &'a mut ListState,
+    pub rcast_stations: &'a [crate::rcast::RcastStation],
+    pub rcast_list_state: &'a mut ListState,
+    pub rcast_loading: bool,
+    pub spinner: Spinner, // Animates everything that's waiting on background work
+    pub import_progress: Option<(usize, usize)>, // Stations done and total of a running import
+    pub autofilling: bool, // The Add form is reading details from its URL
+    pub checking_station: Option<&'a str>, // Station whose stream is checked before it plays
+    pub rcast_sort: Option<crate::rcast::RcastSort>,
+    pub rcast_info_scroll: &'a mut u16,
+    pub db_warning: Option<&'a str>,
+    pub color_mode: crate::visualizations::ColorMode,
+    pub list_style: &'a ListStyle,
+    pub show_top_stations: bool,
+    pub top_stations_period: TopStationsPeriod,
+    pub show_history: bool,
+    pub stats: &'a StatsSnapshot,
+    pub current_station_id: Option<i32>,
+    pub search_query: &'a str,
+    pub search_results: &'a [Station],
+    pub search_list_state: &'a mut ListState,
+    pub search_favorites_only: bool,
+    pub right_pane: RightPane,
+    pub show_footer: bool,
+    pub failing_only: bool,
+    pub letter_filter: Option<char>,
+    pub status_message: Option<&'a str>,
+    pub metadata_format: Option<&'a str>,
+    pub selected_station_ids: &'a HashSet<i32>,
+    pub export_path: &'a str,
+    pub rewrite_fields: [&'a str; 2], // Find and replace text of the URL rewrite prompt
+    pub rewrite_preview: &'a [crate::db::UrlRewrite],
+    pub show_key_help: bool,
+    pub show_welcome: bool,
+    pub palette_query: &'a str,
+    pub palette_matches: &'a [crate::app::Action],
+    pub palette_state: &'a mut ListState,
+    pub station_detail: Option<&'a StationDetail>,
+    #[cfg(feature = "cast")]
+    pub cast_picker: Option<CastPicker>,
+}
+
+pub fn ui(f: &mut Frame, ctx: &mut UiContext) {
+    let input_field = ctx.input_field;
+    let input_cursor = ctx.input_cursor;
+    let tag_suggestions = popup::TagSuggestions {
+        items: ctx.tag_suggestions,
+        selected: ctx.tag_suggestion,
+    };
+    let rcast_loading = ctx.rcast_loading.then_some(ctx.spinner);
+    let autofilling = ctx.autofilling.then_some(ctx.spinner);
+    let spinner = ctx.spinner;
+    let rcast_sort = ctx.rcast_sort;
+    let show_top_stations = ctx.show_top_stations;
+    let top_stations_period = ctx.top_stations_period;
+    let show_history = ctx.show_history;
+    let current_station_id = ctx.current_station_id;
+    let right_pane = ctx.right_pane;
+    let show_footer = ctx.show_footer;
+    // A running import shows its progress whenever there's no other message
+    let import_status = ctx
+        .import_progress
+        .map(|(done, total)| ctx.spinner.label(&format!("Importing {}/{}", done, total)));
+    // The check answers a key just pressed, so it beats older messages
+    let check_status = ctx
+        .checking_station
+        .map(|name| ctx.spinner.label(&format!("Checking {}…", name)));
+    let status_message = check_status
+        .as_deref()
+        .or(ctx.status_message)
+        .or(import_status.as_deref());
+    let db_warning = ctx.db_warning;
+    let tag_stations_focused = ctx.tag_stations_focused;
+    let import_preview = ctx.import_preview;
+    let color_mode = ctx.color_mode;
+    let list_style = ctx.list_style;
+    let search_favorites_only = ctx.search_favorites_only;
+    let metadata_format = ctx.metadata_format;
+    let selected_station_ids = ctx.selected_station_ids;
+    let rewrite_fields = ctx.rewrite_fields;
+    let rewrite_preview = ctx.rewrite_preview;
+    let UiContext {
+        stations,
+        list_state,
+        visualizer,
+        mode,
+        add_station_name,
+        add_station_url,
+        add_station_desc,
+        add_station_tags,
+        vis_manager,
+        vis_menu_state,
+        rcast_stations,
+        rcast_list_state,
+        rcast_info_scroll,
+        stats: stats_cache,
+        search_query,
+        search_results,
+        search_list_state,
+        export_path,
+        palette_query,
+        palette_matches,
+        palette_state,
+        tag_counts,
+        tag_list_state,
+        tag_stations,
+        tag_station_state,
+        #[cfg(feature = "cast")]
+        cast_picker,
+        ..
+    } = ctx;
 
-#[allow(clippy::too_many_arguments)]
-pub fn ui(
-    f: &mut Frame,
-    stations: &[Station],
-    list_state: &mut ListState,
-    visualizer: &AudioVisualizer,
-    mode: &AppMode,
-    add_station_name: &str,
-    add_station_url: &str,
-    add_station_desc: &str,
-    input_field: usize,
-    input_cursor: usize,
-    vis_manager: &VisualizationManager,
-    vis_menu_state: &mut ListState,
-    rcast_stations: &[crate::rcast::RcastStation],
-    rcast_list_state: &mut ListState,
-    rcast_loading: bool,
-    show_top_stations: bool,
-    conn: &Connection,
-    current_station_id: Option<i32>,
-    search_query: &str,
-    search_results: &[Station],
-    search_list_state: &mut ListState,
-    show_visualizations: bool,
-) {
     let size = f.size();
 
     // First split into main area and help area
     let main_help_chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
-        .constraints([Constraint::Percentage(85), Constraint::Percentage(15)].as_ref())
+        .constraints(if show_footer {
+            [Constraint::Percentage(85), Constraint::Percentage(15)]
+        } else {
+            [Constraint::Percentage(100), Constraint::Length(0)]
+        })
         .split(size);
 
     // Split main area into stations list (35%) and right panel (65%)
@@ -56,25 +228,64 @@ pub fn ui(
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
         .split(main_help_chunks[0]);
 
-    // Render help area
-    let help_text = match mode {
-        AppMode::Normal => "↑/↓: Navigate  ⏎: Play  s: Stop  m: Mute/Unmute  +/-: Volume  f: Favorite  a: Add  e: Edit  d: Delete  t: Toggle Top Stations  v: Vis Menu  V: Toggle Visualizations  /: Search  Tab: RCast  q: Quit",
-        AppMode::AddingStation => "Tab: Next Field  Enter: Confirm  Esc: Cancel",
-        AppMode::EditingStation => "Tab: Next Field  Enter: Save  Esc: Cancel",
-        AppMode::DeletingStation => "y: Confirm Delete  n/Esc: Cancel",
-        AppMode::VisualizationMenu => "↑/↓: Navigate  Enter: Select  Esc: Cancel",
-        AppMode::RcastStations => "↑/↓: Navigate  ⏎: Play  m: Mute/Unmute  +/-: Volume  r: Refresh  t: Toggle Top Stations  V: Toggle Visualizations  /: Search  Tab: Main View  q: Quit",
-        AppMode::Searching => "↑/↓: Navigate  ⏎: Play Selected  Esc: Cancel  Type to search...",
+    // Render help area, fitted to the footer width
+    let help_text = keys::footer_text(mode, main_help_chunks[1].width.saturating_sub(2) as usize);
+
+    // Surface any transient status message in the help title
+    let help_title = match status_message {
+        Some(message) => format!("Help - {}", message),
+        None => "Help".to_string(),
     };
 
-    let help =
-        Paragraph::new(help_text).block(Block::default().borders(Borders::ALL).title("Help"));
-    f.render_widget(help, main_help_chunks[1]);
+    if show_footer {
+        let help = Paragraph::new(help_text)
+            .block(Block::default().borders(Borders::ALL).title(help_title));
+        f.render_widget(help, main_help_chunks[1]);
+    } else if let Some(message) = status_message {
+        // No footer to title, so use the bottom margin
+        let status_area = Rect {
+            x: size.x + 1,
+            y: size.bottom().saturating_sub(1),
+            width: size.width.saturating_sub(2),
+            height: 1.min(size.height),
+        };
+        f.render_widget(
+            Paragraph::new(message).style(Style::default().fg(Color::Yellow)),
+            status_area,
+        );
+    }
+
+    // Keep a database write failure visible in the top margin for the rest
+    // of the session
+    if let Some(warning) = db_warning {
+        let banner_area = Rect {
+            x: size.x + 1,
+            y: size.y,
+            width: size.width.saturating_sub(2),
+            height: 1.min(size.height),
+        };
+        let banner = Paragraph::new(format!("⚠ {}", warning)).style(
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        );
+        f.render_widget(banner, banner_area);
+    }
 
     // The main UI always shows, regardless of the mode
     // We'll change what appears in the right pane based on the mode
 
     // Render stations list (always visible in left pane)
+    let stations_title = if !selected_station_ids.is_empty() {
+        format!("Stations ({} selected)", selected_station_ids.len())
+    } else if let Some(letter) = ctx.letter_filter {
+        format!("Stations: {} ({}, Esc for all)", letter, stations.len())
+    } else if ctx.failing_only {
+        "Stations (failing checks)".to_string()
+    } else {
+        "Stations".to_string()
+    };
     let items: Vec<ListItem> = stations
         .iter()
         .map(|s| {
@@ -84,10 +295,27 @@ pub fn ui(
             if s.favorite {
                 content = format!("★ {}", content);
             }
+            if s.pinned {
+                content = format!("▲ {}", content);
+            }
+            // Flag stations found dead by their last check
+            if s.check_failed() {
+                content = format!("⚠ {}", content);
+            }
+
+            // Show checkboxes while any station is marked for a bulk action
+            if !selected_station_ids.is_empty() {
+                let marker = if selected_station_ids.contains(&s.id) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                content = format!("{} {}", marker, content);
+            }
 
-            // If visualizations are disabled, add stats to the list item
-            if !show_visualizations {
-                if let Ok(Some(stats)) = get_station_stats(conn, s.id) {
+            // Alongside the stats pane, add stats to the list item
+            if right_pane != RightPane::Visualization {
+                if let Some(stats) = stats_cache.stats_for(s.id) {
                     let play_time = format_play_time(stats.total_play_time);
 
                     // Add formatted play time
@@ -109,15 +337,9 @@ pub fn ui(
         })
         .collect();
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Stations"))
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
+    let list = list_style.apply(
+        List::new(items).block(Block::default().borders(Borders::ALL).title(stations_title)),
+    );
     f.render_stateful_widget(list, main_chunks[0], list_state);
 
     // Render right pane content based on mode
@@ -145,32 +367,67 @@ pub fn ui(
                 .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
                 .split(main_chunks[1]);
 
-            // Determine what to show in the top area based on visualization setting
-            if show_visualizations {
+            // Fill the top area with the visualization, the stats or both
+            let (vis_area, details_area) = match right_pane {
+                RightPane::Visualization => (Some(vis_chunks[0]), None),
+                RightPane::Stats => (None, Some(vis_chunks[0])),
+                RightPane::Split => {
+                    let split = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                        .split(vis_chunks[0]);
+                    (Some(split[0]), Some(split[1]))
+                }
+            };
+
+            if let Some(area) = vis_area {
                 // Make the mute status more prominent by adding a symbol
-                let status_with_symbol = if state.is_muted {
+                let mut status_with_symbol = if state.is_muted {
                     format!("Visualization - {} 🔇", status_text)
                 } else {
                     format!("Visualization - {} 🔊", status_text)
                 };
+                if state.vis_frozen {
+                    status_with_symbol.push_str(" ❄ frozen");
+                }
 
                 let vis_block = Block::default()
                     .borders(Borders::ALL)
                     .title(status_with_symbol);
 
                 // Create a canvas with the active visualization
+                let aspect = crate::visualizations::canvas_aspect_ratio(area);
                 let canvas = Canvas::default()
                     .block(vis_block)
                     .x_bounds([0.0, 100.0])
                     .y_bounds([0.0, 100.0])
                     .paint(|ctx| {
                         // Use the current visualization from the manager
-                        vis_manager.render(ctx, &state);
+                        vis_manager.render(ctx, &state, aspect, color_mode);
                     });
 
-                f.render_widget(canvas, vis_chunks[0]);
-            } else {
-                // Instead of visualization, show detailed station info
+                f.render_widget(canvas, area);
+            }
+            if let Some(area) = details_area {
+                // Listening per day below the details, once there's enough history
+                let area = if stats_cache.has_listening_trend() {
+                    let parts = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(3), Constraint::Length(5)])
+                        .split(area);
+                    let title =
+                        format!("Listening - Last {} Days", crate::db::LISTENING_TREND_DAYS);
+                    let sparkline = Sparkline::default()
+                        .block(Block::default().borders(Borders::ALL).title(title))
+                        .data(&stats_cache.daily_listening)
+                        .style(Style::default().fg(Color::Green));
+                    f.render_widget(sparkline, parts[1]);
+                    parts[0]
+                } else {
+                    area
+                };
+
+                // Show detailed station info
                 let title = format!("Station Details - {}", status_text);
                 let details_block = Block::default().borders(Borders::ALL).title(title);
 
@@ -186,19 +443,17 @@ pub fn ui(
                         }
 
                         // Add detailed station stats
-                        if let Ok(Some(stats)) = get_station_stats(conn, station.id) {
+                        if let Some(stats) = stats_cache.stats_for(station.id) {
                             text.push_str(&format!(
                                 "\n\nTotal Play Time: {}",
                                 format_play_time(stats.total_play_time)
                             ));
 
                             if let Some(last_played) = stats.last_played {
-                                let datetime = chrono::DateTime::from_timestamp(last_played, 0)
-                                    .unwrap_or_else(|| {
-                                        chrono::DateTime::from_timestamp(0, 0).unwrap()
-                                    });
-                                let local_time = datetime.format("%Y-%m-%d %H:%M:%S");
-                                text.push_str(&format!("\nLast Played: {}", local_time));
+                                text.push_str(&format!(
+                                    "\nLast Played: {}",
+                                    format_relative_time(last_played)
+                                ));
                             }
                         }
 
@@ -212,59 +467,79 @@ pub fn ui(
 
                 let details_widget = Paragraph::new(details_text).block(details_block);
 
-                f.render_widget(details_widget, vis_chunks[0]);
+                f.render_widget(details_widget, area);
             }
 
             // Display stream metadata or top stations
             let metadata_text = if show_top_stations {
                 // Show top 5 stations by play time
-                match get_top_stations(conn, 5) {
-                    Ok(top_stations) => {
-                        if top_stations.is_empty() {
-                            "No station play history yet.\nListen to some stations to build your stats!".to_string()
-                        } else {
-                            let mut text = "Top 5 Stations by Play Time:\n\n".to_string();
-                            for (i, (station, play_time)) in top_stations.iter().enumerate() {
-                                text.push_str(&format!(
-                                    "{}. {} - {}\n",
-                                    i + 1,
-                                    station.name,
-                                    format_play_time(*play_time)
-                                ));
-                            }
-                            text
+                top_stations_text(stats_cache.top_stations_for(top_stations_period))
+            } else if show_history {
+                // Show the most recent plays, with the song that was on if known
+                let history = &stats_cache.recent_history;
+                if history.is_empty() {
+                    "Nothing played yet.".to_string()
+                } else {
+                    let mut text = String::new();
+                    for entry in history {
+                        let started = chrono::DateTime::from_timestamp(entry.started_at, 0)
+                            .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap())
+                            .format("%m-%d %H:%M");
+                        match &entry.song {
+                            Some(song) => text.push_str(&format!(
+                                "{} {} — {}\n",
+                                started, entry.station_name, song
+                            )),
+                            None => text.push_str(&format!("{} {}\n", started, entry.station_name)),
                         }
                     }
-                    Err(_) => "Error loading top stations stats.".to_string(),
+                    text
                 }
             } else if let Some(info) = &state.stream_info {
                 let unknown = "Unknown".to_string();
                 let song = info.current_song.as_ref().unwrap_or(&unknown);
 
+                // Show artist and title separately when the StreamTitle can be split
+                let song_text = match info
+                    .current_song
+                    .as_deref()
+                    .and_then(|song| crate::metadata::parse_song(song, metadata_format))
+                {
+                    Some((artist, title)) => format!("Artist: {}\nTitle: {}", artist, title),
+                    None => format!("Current Song: {}", song),
+                };
+
                 // Start with basic stream info
                 let mut text = format!(
-                    "Station: {}\nFormat: {}\nBitrate: {}\nCurrent Song: {}\nMuted: {}",
+                    "Station: {}\nFormat: {}\nBitrate: {}\n{}\nMuted: {}",
                     info.station_name,
                     info.format,
                     info.bitrate,
-                    song,
+                    song_text,
                     if state.is_muted { "Yes" } else { "No" }
                 );
+                if let Some(genre) = &info.genre {
+                    text.push_str(&format!("\nGenre: {}", genre));
+                }
+                // Live streams have no duration, so only the elapsed time fits;
+                // on-demand content gets the progress bar below instead
+                if let (Some(position), None) = (info.position, info.duration) {
+                    text.push_str(&format!("\nElapsed: {}", format_clock(position)));
+                }
 
                 // If we have a current station ID, add the stats
                 if let Some(station_id) = current_station_id {
-                    if let Ok(Some(stats)) = get_station_stats(conn, station_id) {
+                    if let Some(stats) = stats_cache.stats_for(station_id) {
                         text.push_str(&format!(
                             "\n\nTotal Play Time: {}",
                             format_play_time(stats.total_play_time)
                         ));
 
                         if let Some(last_played) = stats.last_played {
-                            // Convert timestamp to local date/time
-                            let datetime = chrono::DateTime::from_timestamp(last_played, 0)
-                                .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
-                            let local_time = datetime.format("%Y-%m-%d %H:%M:%S");
-                            text.push_str(&format!("\nLast Played: {}", local_time));
+                            text.push_str(&format!(
+                                "\nLast Played: {}",
+                                format_relative_time(last_played)
+                            ));
                         }
                     }
                 }
@@ -276,17 +551,17 @@ pub fn ui(
                     let mut text = format!("Selected: {}", stations[selected].name);
 
                     // Add station stats if available
-                    if let Ok(Some(stats)) = get_station_stats(conn, stations[selected].id) {
+                    if let Some(stats) = stats_cache.stats_for(stations[selected].id) {
                         text.push_str(&format!(
                             "\nTotal Play Time: {}",
                             format_play_time(stats.total_play_time)
                         ));
 
                         if let Some(last_played) = stats.last_played {
-                            let datetime = chrono::DateTime::from_timestamp(last_played, 0)
-                                .unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
-                            let local_time = datetime.format("%Y-%m-%d %H:%M:%S");
-                            text.push_str(&format!("\nLast Played: {}", local_time));
+                            text.push_str(&format!(
+                                "\nLast Played: {}",
+                                format_relative_time(last_played)
+                            ));
                         }
                     }
 
@@ -303,16 +578,66 @@ pub fn ui(
                 "No stream playing".to_string()
             };
 
-            let block_title = if show_top_stations {
-                "Top Stations"
+            let mut block_title = vec![Span::raw(if show_top_stations {
+                format!("Top Stations ({})", top_stations_period.label())
+            } else if show_history {
+                "Recently Played".to_string()
             } else {
-                "Stream Info"
-            };
+                "Stream Info".to_string()
+            })];
+
+            // Signal indicator from the last connectivity check
+            let connectivity = state
+                .stream_info
+                .as_ref()
+                .and_then(|info| info.connectivity);
+            if !show_top_stations && !show_history {
+                if let Some(connectivity) = connectivity {
+                    let color = match connectivity {
+                        Connectivity::Streaming => Color::Green,
+                        Connectivity::Buffering => Color::Yellow,
+                        Connectivity::Stalled => Color::Red,
+                    };
+                    block_title.push(Span::styled(
+                        format!(" ● {}", connectivity.label()),
+                        Style::default().fg(color),
+                    ));
+                }
+            }
 
-            let metadata = Paragraph::new(metadata_text)
-                .block(Block::default().borders(Borders::ALL).title(block_title));
+            let metadata = Paragraph::new(metadata_text).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Line::from(block_title)),
+            );
+
+            // Position and length of on-demand content, when known
+            let progress = state
+                .stream_info
+                .as_ref()
+                .and_then(|info| Some((info.position?, info.duration?)))
+                .filter(|_| !show_top_stations && !show_history);
+
+            // Reserve small strips below the stream info for the progress bar
+            // (on-demand content only) and the volume gauge
+            let progress_height = if progress.is_some() { 3 } else { 0 };
+            let info_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Min(3),
+                        Constraint::Length(progress_height),
+                        Constraint::Length(3),
+                    ]
+                    .as_ref(),
+                )
+                .split(vis_chunks[1]);
 
-            f.render_widget(metadata, vis_chunks[1]);
+            f.render_widget(metadata, info_chunks[0]);
+            if let Some((position, duration)) = progress {
+                progress::render_progress_gauge(f, position, duration, info_chunks[1]);
+            }
+            volume::render_volume_gauge(f, &state, info_chunks[2]);
         }
         AppMode::RcastStations => {
             // Split the right pane for stations list and either stats or loading indicator
@@ -328,38 +653,24 @@ pub fn ui(
                 rcast_list_state,
                 rcast_chunks[0],
                 rcast_loading,
+                rcast_sort,
+                list_style,
             );
 
             // Show either stats or loading indicator in the bottom part
             if show_top_stations {
                 // Show top 5 stations by play time
-                let metadata_text = match get_top_stations(conn, 5) {
-                    Ok(top_stations) => {
-                        if top_stations.is_empty() {
-                            "No station play history yet.\nListen to some stations to build your stats!".to_string()
-                        } else {
-                            let mut text = "Top 5 Stations by Play Time:\n\n".to_string();
-                            for (i, (station, play_time)) in top_stations.iter().enumerate() {
-                                text.push_str(&format!(
-                                    "{}. {} - {}\n",
-                                    i + 1,
-                                    station.name,
-                                    format_play_time(*play_time)
-                                ));
-                            }
-                            text
-                        }
-                    }
-                    Err(_) => "Error loading top stations stats.".to_string(),
-                };
+                let metadata_text =
+                    top_stations_text(stats_cache.top_stations_for(top_stations_period));
+                let title = format!("Top Stations ({})", top_stations_period.label());
 
                 let metadata = Paragraph::new(metadata_text)
-                    .block(Block::default().borders(Borders::ALL).title("Top Stations"));
+                    .block(Block::default().borders(Borders::ALL).title(title));
 
                 f.render_widget(metadata, rcast_chunks[1]);
-            } else if rcast_loading {
+            } else if let Some(spinner) = rcast_loading {
                 // Show loading indicator
-                let loading = Paragraph::new("Loading stations from RCast.net...")
+                let loading = Paragraph::new(spinner.label("Loading stations from RCast.net…"))
                     .block(Block::default().borders(Borders::ALL).title("Loading"));
 
                 f.render_widget(loading, rcast_chunks[1]);
@@ -388,46 +699,55 @@ pub fn ui(
                     }
 
                     // Try to find station stats in our database (by URL)
-                    if let Ok(mut stmt) = conn.prepare("SELECT id FROM stations WHERE url = ?1") {
-                        if let Ok(id_result) =
-                            stmt.query_map(params![&station.url], |row| row.get::<_, i32>(0))
-                        {
-                            if let Some(id) = id_result.flatten().next() {
-                                if let Ok(Some(stats)) = get_station_stats(conn, id) {
-                                    text.push_str(&format!(
-                                        "\n\nTotal Play Time: {}",
-                                        format_play_time(stats.total_play_time)
-                                    ));
-
-                                    if let Some(last_played) = stats.last_played {
-                                        let datetime =
-                                            chrono::DateTime::from_timestamp(last_played, 0)
-                                                .unwrap_or_else(|| {
-                                                    chrono::DateTime::from_timestamp(0, 0).unwrap()
-                                                });
-                                        let local_time = datetime.format("%Y-%m-%d %H:%M:%S");
-                                        text.push_str(&format!("\nLast Played: {}", local_time));
-                                    }
-                                }
-                            }
+                    if let Some(stats) = stats_cache.stats_for_url(&station.url) {
+                        text.push_str(&format!(
+                            "\n\nTotal Play Time: {}",
+                            format_play_time(stats.total_play_time)
+                        ));
+
+                        if let Some(last_played) = stats.last_played {
+                            text.push_str(&format!(
+                                "\nLast Played: {}",
+                                format_relative_time(last_played)
+                            ));
                         }
                     }
 
+                    // Wrap long directory descriptions and let PgUp/PgDn
+                    // scroll through them, clamped to the wrapped text
+                    let area = rcast_chunks[1];
+                    let inner_width = area.width.saturating_sub(2);
+                    let visible = area.height.saturating_sub(2);
+                    let max_scroll = wrapped_line_count(&text, inner_width).saturating_sub(visible);
+                    **rcast_info_scroll = (**rcast_info_scroll).min(max_scroll);
+
+                    let title = if max_scroll > 0 {
+                        "Station Info (PgUp/PgDn to scroll)"
+                    } else {
+                        "Station Info"
+                    };
                     let metadata = Paragraph::new(text)
-                        .block(Block::default().borders(Borders::ALL).title("Station Info"));
+                        .wrap(Wrap { trim: false })
+                        .scroll((**rcast_info_scroll, 0))
+                        .block(Block::default().borders(Borders::ALL).title(title));
 
-                    f.render_widget(metadata, rcast_chunks[1]);
+                    f.render_widget(metadata, area);
                 }
             }
         }
         AppMode::AddingStation => {
             popup::render_add_station_popup(
                 f,
-                add_station_name,
-                add_station_url,
-                add_station_desc,
+                [
+                    add_station_name,
+                    add_station_url,
+                    add_station_desc,
+                    add_station_tags,
+                ],
                 input_field,
                 input_cursor,
+                &tag_suggestions,
+                autofilling,
             );
         }
         AppMode::EditingStation => {
@@ -435,24 +755,79 @@ pub fn ui(
                 if let Some(app) = app_guard.as_ref() {
                     popup::render_edit_station_popup(
                         f,
-                        &app.edit_station_name,
-                        &app.edit_station_url,
-                        &app.edit_station_desc,
+                        app,
                         input_field,
                         input_cursor,
+                        &tag_suggestions,
                     );
                 }
             }
         }
         AppMode::DeletingStation => {
-            if let Some(selected) = list_state.selected() {
+            if !selected_station_ids.is_empty() {
+                popup::render_delete_station_popup(
+                    f,
+                    &format!("{} selected stations", selected_station_ids.len()),
+                );
+            } else if let Some(selected) = list_state.selected() {
                 if selected < stations.len() {
-                    popup::render_delete_station_popup(f, &stations[selected].name);
+                    popup::render_delete_station_popup(
+                        f,
+                        &format!("\"{}\"", stations[selected].name),
+                    );
                 }
             }
         }
+        AppMode::ExportingStations => {
+            let count = selected_station_ids.len().max(1);
+            popup::render_export_popup(f, count, export_path, input_cursor);
+        }
         AppMode::VisualizationMenu => {
-            vis_menu::render_visualization_menu(f, vis_manager, vis_menu_state, size);
+            vis_menu::render_visualization_menu(f, vis_manager, vis_menu_state, list_style, size);
+        }
+        AppMode::ConfirmingImport => {
+            popup::render_import_preview_popup(f, &import_preview.summary());
+        }
+        AppMode::RewritingUrls => {
+            popup::render_rewrite_urls_popup(f, rewrite_fields, input_field, input_cursor);
+        }
+        AppMode::ConfirmingRewrite => {
+            popup::render_rewrite_preview_popup(f, rewrite_preview);
+        }
+        #[cfg(feature = "cast")]
+        AppMode::CastPicker => {
+            if let Some(picker) = cast_picker {
+                cast_picker::render_cast_picker(f, picker, spinner, list_style, size);
+            }
+        }
+        AppMode::StationDetail => {
+            if let Some(detail) = ctx.station_detail {
+                station_detail::render_station_detail(f, detail, spinner, main_help_chunks[0]);
+            }
+        }
+        AppMode::TagBrowser => {
+            tag_browser::render_tag_browser(
+                f,
+                tag_browser::TagBrowser {
+                    tag_counts,
+                    tag_list_state,
+                    stations: tag_stations,
+                    station_state: tag_station_state,
+                    stations_focused: tag_stations_focused,
+                },
+                list_style,
+                main_help_chunks[0],
+            );
+        }
+        AppMode::CommandPalette => {
+            palette::render_command_palette(
+                f,
+                palette_query,
+                palette_matches,
+                palette_state,
+                list_style,
+                size,
+            );
         }
         AppMode::Searching => {
             // Split the main area into search input and search results
@@ -462,8 +837,13 @@ pub fn ui(
                 .split(main_chunks[0]);
 
             // Render search input
+            let search_title = if search_favorites_only {
+                "Search (favorites only)"
+            } else {
+                "Search"
+            };
             let search_input = Paragraph::new(search_query.to_string())
-                .block(Block::default().borders(Borders::ALL).title("Search"))
+                .block(Block::default().borders(Borders::ALL).title(search_title))
                 .style(Style::default().fg(Color::Yellow));
 
             f.render_widget(search_input, search_chunks[0]);
@@ -476,19 +856,23 @@ pub fn ui(
                     if s.favorite {
                         content = format!("★ {}", content);
                     }
-                    ListItem::new(Span::styled(content, Style::default().fg(Color::Cyan)))
+                    // Saved stations have an id; directory results are id 0
+                    // and only play as a preview until they're saved
+                    let badge = if s.id > 0 {
+                        Span::styled("[local] ", Style::default().fg(Color::Green))
+                    } else {
+                        Span::styled("[web]   ", Style::default().fg(Color::Magenta))
+                    };
+                    ListItem::new(Line::from(vec![
+                        badge,
+                        Span::styled(content, Style::default().fg(Color::Cyan)),
+                    ]))
                 })
                 .collect();
 
-            let results_list = List::new(items)
-                .block(Block::default().borders(Borders::ALL).title("Results"))
-                .highlight_style(
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                )
-                .highlight_symbol(">> ");
+            let results_list = list_style.apply(
+                List::new(items).block(Block::default().borders(Borders::ALL).title("Results")),
+            );
 
             f.render_stateful_widget(results_list, search_chunks[1], search_list_state);
 
@@ -501,6 +885,11 @@ pub fn ui(
 
                     details.push_str(&format!("Name: {}\n", station.name));
                     details.push_str(&format!("URL: {}\n", station.url));
+                    details.push_str(if station.id > 0 {
+                        "Source: saved station\n"
+                    } else {
+                        "Source: RCast directory (not saved yet)\n"
+                    });
 
                     if let Some(desc) = &station.description {
                         details.push_str(&format!("\nDescription: {}\n", desc));
@@ -508,19 +897,17 @@ pub fn ui(
 
                     // Try to get station stats if available
                     if station.id > 0 {
-                        if let Ok(Some(stats)) = get_station_stats(conn, station.id) {
+                        if let Some(stats) = stats_cache.stats_for(station.id) {
                             details.push_str(&format!(
                                 "\nTotal Play Time: {}",
                                 format_play_time(stats.total_play_time)
                             ));
 
                             if let Some(last_played) = stats.last_played {
-                                let datetime = chrono::DateTime::from_timestamp(last_played, 0)
-                                    .unwrap_or_else(|| {
-                                        chrono::DateTime::from_timestamp(0, 0).unwrap()
-                                    });
-                                let local_time = datetime.format("%Y-%m-%d %H:%M:%S");
-                                details.push_str(&format!("\nLast Played: {}", local_time));
+                                details.push_str(&format!(
+                                    "\nLast Played: {}",
+                                    format_relative_time(last_played)
+                                ));
                             }
                         }
                     }
@@ -543,4 +930,59 @@ pub fn ui(
             }
         }
     }
+
+    // The full key list sits on top of everything else
+    if ctx.show_key_help {
+        keys::render_key_help_popup(f, mode);
+    } else if ctx.show_welcome {
+        keys::render_welcome_popup(f);
+    }
+}
+
+// How many rows `text` takes when word-wrapped to `width` columns
+fn wrapped_line_count(text: &str, width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    let mut rows = 0usize;
+    for line in text.lines() {
+        let mut row_len = 0;
+        rows += 1;
+        for word in line.split(' ') {
+            let len = word.chars().count();
+            let needed = if row_len == 0 { len } else { row_len + 1 + len };
+            if needed <= width {
+                row_len = needed;
+            } else {
+                // Start a new row; words longer than a row are broken up
+                rows += 1;
+                row_len = len;
+                while row_len > width {
+                    rows += 1;
+                    row_len -= width;
+                }
+                if row_len == 0 {
+                    rows -= 1;
+                }
+            }
+        }
+    }
+    rows.min(u16::MAX as usize) as u16
+}
+
+// Text for the "Top Stations" panes
+fn top_stations_text(top_stations: &[(Station, i64)]) -> String {
+    if top_stations.is_empty() {
+        return "No station play history yet.\nListen to some stations to build your stats!"
+            .to_string();
+    }
+
+    let mut text = "Top 5 Stations by Play Time:\n\n".to_string();
+    for (i, (station, play_time)) in top_stations.iter().enumerate() {
+        text.push_str(&format!(
+            "{}. {} - {}\n",
+            i + 1,
+            station.name,
+            format_play_time(*play_time)
+        ));
+    }
+    text
 }
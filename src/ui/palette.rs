@@ -0,0 +1,75 @@
+use crate::app::Action;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+pub fn render_command_palette(
+    f: &mut Frame,
+    query: &str,
+    matches: &[Action],
+    palette_state: &mut ListState,
+    list_style: &super::ListStyle,
+    area: Rect,
+) {
+    // Create a centered popup, like the visualization menu
+    let popup_width = 50.min(area.width);
+    let popup_height = 20.min(area.height);
+    let popup_rect = Rect::new(
+        area.x + (area.width - popup_width) / 2,
+        area.y + (area.height - popup_height) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    f.render_widget(Clear, popup_rect);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+        .split(popup_rect);
+
+    // Query input, styled like the search box
+    let input = Paragraph::new(format!(":{}", query))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Command Palette"),
+        )
+        .style(Style::default().fg(Color::Yellow).bg(Color::Black));
+    f.render_widget(input, chunks[0]);
+
+    // Matching actions with their key, if they have one
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|action| {
+            let mut spans = vec![Span::styled(
+                action.label(),
+                Style::default().fg(Color::Cyan),
+            )];
+            if !action.key_hint().is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", action.key_hint()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = if matches.is_empty() {
+        "No matching actions"
+    } else {
+        "Actions"
+    };
+    let list = list_style.apply(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(Style::default().bg(Color::Black)),
+    );
+
+    f.render_stateful_widget(list, chunks[1], palette_state);
+}
@@ -1,53 +1,197 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line as TextLine, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
+// Existing tags offered under the focused Tags field
+pub struct TagSuggestions<'a> {
+    pub items: &'a [String],
+    pub selected: usize,
+}
+
 // Function to render the add station popup
+// `fields` holds the name, URL, description and tags being typed;
+// `autofilling` animates the title while the URL is being probed
 pub fn render_add_station_popup(
     f: &mut Frame,
-    name: &str,
-    url: &str,
-    description: &str,
+    fields: [&str; 4],
     input_field: usize,
     input_cursor: usize,
+    suggestions: &TagSuggestions,
+    autofilling: Option<super::Spinner>,
 ) {
+    let [name, url, description, tags] = fields;
+    let title = match autofilling {
+        Some(spinner) => format!(
+            "Add New Station {}",
+            spinner.label("reading stream details…")
+        ),
+        None => "Add New Station".to_string(),
+    };
     render_station_form(
         f,
-        "Add New Station",
-        name,
-        url,
-        description,
+        &title,
+        &[
+            ("Name:", name),
+            ("URL:", url),
+            ("Description:", description),
+            ("Tags:", tags),
+        ],
         input_field,
         input_cursor,
+        suggestions,
+    );
+}
+
+// Function to render the playlist export filename prompt
+pub fn render_export_popup(f: &mut Frame, count: usize, path: &str, input_cursor: usize) {
+    render_station_form(
+        f,
+        &format!("Export {} Station(s)", count),
+        &[("File:", path)],
+        0,
+        input_cursor,
+        &TagSuggestions {
+            items: &[],
+            selected: 0,
+        },
     );
 }
 
 // Function to render the edit station popup
 pub fn render_edit_station_popup(
     f: &mut Frame,
-    name: &str,
-    url: &str,
-    description: &str,
+    form: &crate::app::AppState,
     input_field: usize,
     input_cursor: usize,
+    suggestions: &TagSuggestions,
 ) {
+    // Never show the password, just how long it is
+    let masked = "*".repeat(form.edit_station_password.chars().count());
     render_station_form(
         f,
         "Edit Station",
-        name,
-        url,
-        description,
+        &[
+            ("Name:", &form.edit_station_name),
+            ("URL:", &form.edit_station_url),
+            ("Description:", &form.edit_station_desc),
+            ("Tags:", &form.edit_station_tags),
+            ("mpv args:", &form.edit_station_args),
+            ("Username:", &form.edit_station_username),
+            ("Password:", &masked),
+            ("Mirrors:", &form.edit_station_mirrors),
+        ],
         input_field,
         input_cursor,
+        suggestions,
     );
 }
 
 // Function to render the delete station confirmation popup
-pub fn render_delete_station_popup(f: &mut Frame, station_name: &str) {
+// `target` describes what is being deleted, e.g. a quoted station name
+pub fn render_delete_station_popup(f: &mut Frame, target: &str) {
+    render_confirm_popup(
+        f,
+        "Delete Station",
+        &format!("Are you sure you want to delete {}? (y/n)", target),
+        Color::Red,
+    );
+}
+
+// Function to render the import preview shown before anything is written
+pub fn render_import_preview_popup(f: &mut Frame, summary: &str) {
+    render_confirm_popup(
+        f,
+        "Import Stations",
+        &format!("{}. Import the new ones? (y/n)", summary),
+        Color::Yellow,
+    );
+}
+
+// Function to render the find/replace prompt for station URLs
+pub fn render_rewrite_urls_popup(
+    f: &mut Frame,
+    fields: [&str; 2],
+    input_field: usize,
+    input_cursor: usize,
+) {
+    let [from, to] = fields;
+    render_station_form(
+        f,
+        "Rewrite Station URLs",
+        &[("Find:", from), ("Replace with:", to)],
+        input_field,
+        input_cursor,
+        &TagSuggestions {
+            items: &[],
+            selected: 0,
+        },
+    );
+}
+
+// Every URL a rewrite would change, under its station's name, as many as fit
+pub fn render_rewrite_preview_popup(f: &mut Frame, rewrites: &[crate::db::UrlRewrite]) {
+    let size = f.size();
+    let popup_width = 80.min(size.width - 4);
+    let popup_height = (size.height * 3 / 4).max(5).min(size.height - 4);
+    let popup_area = Rect {
+        x: (size.width - popup_width) / 2,
+        y: (size.height - popup_height) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(format!(
+            "Rewrite the URLs of {} station(s)? (y/n)",
+            rewrites.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let room = popup_height.saturating_sub(2) as usize;
+    let mut lines = Vec::new();
+    for (i, rewrite) in rewrites.iter().enumerate() {
+        let changes = rewrite.changes();
+        // Keep the last line free to say how many stations didn't fit
+        if lines.len() + 1 + changes.len() * 2 > room.saturating_sub(1) && i > 0 {
+            lines.push(TextLine::from(Span::styled(
+                format!("…and {} more station(s)", rewrites.len() - i),
+                Style::default().fg(Color::DarkGray),
+            )));
+            break;
+        }
+        lines.push(TextLine::from(Span::styled(
+            rewrite.name.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        for (old, new) in changes {
+            lines.push(TextLine::from(Span::styled(
+                format!("  {}", old),
+                Style::default().fg(Color::Red),
+            )));
+            lines.push(TextLine::from(Span::styled(
+                format!("  → {}", new),
+                Style::default().fg(Color::Green),
+            )));
+        }
+    }
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .style(Style::default().fg(Color::White))
+            .block(block),
+        popup_area,
+    );
+}
+
+// A small centered yes/no question
+fn render_confirm_popup(f: &mut Frame, title: &str, message: &str, border: Color) {
     let size = f.size();
 
     // Create a centered popup area
@@ -66,9 +210,9 @@ pub fn render_delete_station_popup(f: &mut Frame, station_name: &str) {
 
     // Draw the popup frame
     let popup_block = Block::default()
-        .title("Delete Station")
+        .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
+        .border_style(Style::default().fg(border))
         .style(Style::default().bg(Color::Black));
 
     f.render_widget(popup_block, popup_area);
@@ -81,13 +225,9 @@ pub fn render_delete_station_popup(f: &mut Frame, station_name: &str) {
         height: popup_area.height - 2,
     };
 
-    // Format the confirmation message
-    let message = format!(
-        "Are you sure you want to delete \"{}\"? (y/n)",
-        station_name
-    );
-
-    let text = Paragraph::new(message).style(Style::default().fg(Color::White));
+    let text = Paragraph::new(message)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: true });
 
     f.render_widget(text, inner_area);
 }
@@ -96,17 +236,16 @@ pub fn render_delete_station_popup(f: &mut Frame, station_name: &str) {
 fn render_station_form(
     f: &mut Frame,
     title: &str,
-    name: &str,
-    url: &str,
-    description: &str,
+    fields: &[(&str, &str)], // (label, value) pairs in display order
     input_field: usize,
     input_cursor: usize,
+    suggestions: &TagSuggestions,
 ) {
     let size = f.size();
 
-    // Create a centered popup area
+    // Create a centered popup area, two rows per field plus borders
     let popup_width = 60.min(size.width - 4);
-    let popup_height = 10.min(size.height - 4);
+    let popup_height = (fields.len() as u16 * 2 + 4).min(size.height - 4);
 
     let popup_area = Rect {
         x: (size.width - popup_width) / 2,
@@ -135,42 +274,89 @@ fn render_station_form(
         height: popup_area.height - 2,
     };
 
+    let constraints: Vec<Constraint> = fields.iter().map(|_| Constraint::Length(2)).collect();
     let input_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Length(2), // Name
-                Constraint::Length(2), // URL
-                Constraint::Length(2), // Description
-            ]
-            .as_ref(),
-        )
+        .constraints(constraints)
         .split(inner_area);
 
     // Render each field
-    render_input_field(
-        f,
-        input_chunks[0],
-        "Name:",
-        name,
-        input_field == 0,
-        input_cursor,
-    );
-    render_input_field(
-        f,
-        input_chunks[1],
-        "URL:",
-        url,
-        input_field == 1,
-        input_cursor,
-    );
-    render_input_field(
-        f,
-        input_chunks[2],
-        "Description:",
-        description,
-        input_field == 2,
-        input_cursor,
+    for (i, (label, value)) in fields.iter().enumerate() {
+        render_input_field(
+            f,
+            input_chunks[i],
+            label,
+            value,
+            input_field == i,
+            input_cursor,
+        );
+    }
+
+    // Drop the suggestions down over the fields below the focused one
+    if let (Some(field_area), Some((label, _))) =
+        (input_chunks.get(input_field), fields.get(input_field))
+    {
+        if !suggestions.items.is_empty() {
+            render_suggestions(f, *field_area, label.len() as u16 + 1, suggestions);
+        }
+    }
+}
+
+// Draw the suggestion dropdown just below a field's input
+fn render_suggestions(
+    f: &mut Frame,
+    field_area: Rect,
+    label_width: u16,
+    suggestions: &TagSuggestions,
+) {
+    let size = f.size();
+    let x = field_area.x + label_width;
+    let y = field_area.y + 1;
+    let width = suggestions
+        .items
+        .iter()
+        .map(|tag| tag.chars().count() as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .max(16)
+        .min(size.width.saturating_sub(x));
+    let height = (suggestions.items.len() as u16 + 2).min(size.height.saturating_sub(y));
+    if width < 3 || height < 3 {
+        return;
+    }
+    let area = Rect {
+        x,
+        y,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = suggestions
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let style = if i == suggestions.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(tag.as_str()).style(style)
+        })
+        .collect();
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .style(Style::default().bg(Color::Black)),
+        ),
+        area,
     );
 }
 
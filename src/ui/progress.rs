@@ -0,0 +1,38 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge},
+    Frame,
+};
+
+// Format seconds as m:ss, or h:mm:ss from an hour up
+pub fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+// Function to render the playback position of on-demand content
+pub fn render_progress_gauge(f: &mut Frame, position: f64, duration: f64, area: Rect) {
+    let ratio = if duration > 0.0 {
+        (position / duration).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title("Progress"))
+        .gauge_style(Style::default().fg(Color::Cyan).bg(Color::Black))
+        .ratio(ratio)
+        .label(format!(
+            "{} / {}",
+            format_clock(position),
+            format_clock(duration)
+        ));
+
+    f.render_widget(gauge, area);
+}
@@ -1,12 +1,12 @@
 use ratatui::{
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Color, Style},
     text::Span,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
-use crate::rcast::RcastStation;
+use crate::rcast::{RcastSort, RcastStation};
 
 // Function to render the RCast stations pane
 pub fn render_rcast_stations(
@@ -14,16 +14,20 @@ pub fn render_rcast_stations(
     stations: &[RcastStation],
     list_state: &mut ListState,
     area: Rect,
-    loading: bool,
+    loading: Option<super::Spinner>, // Some while the directory is being fetched
+    sort: Option<RcastSort>,
+    list_style: &super::ListStyle,
 ) {
-    // Create a block for the stations list
-    let rcast_block = Block::default()
-        .borders(Borders::ALL)
-        .title("RCast Radio Stations");
+    // Create a block for the stations list, naming the active sort
+    let title = match sort {
+        Some(sort) => format!("RCast Radio Stations (by {})", sort.label()),
+        None => "RCast Radio Stations".to_string(),
+    };
+    let rcast_block = Block::default().borders(Borders::ALL).title(title);
 
-    if loading {
+    if let Some(spinner) = loading {
         // Show loading message if we're waiting for stations to load
-        let loading_text = Paragraph::new("Loading stations from RCast.net...")
+        let loading_text = Paragraph::new(spinner.label("Loading stations from RCast.net…"))
             .style(Style::default().fg(Color::Yellow))
             .block(rcast_block);
         f.render_widget(loading_text, area);
@@ -47,15 +51,7 @@ pub fn render_rcast_stations(
             .collect();
 
         // Create the list widget
-        let list = List::new(items)
-            .block(rcast_block)
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
+        let list = list_style.apply(List::new(items).block(rcast_block));
 
         // Render the list
         f.render_stateful_widget(list, area, list_state);
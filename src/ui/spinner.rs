@@ -0,0 +1,27 @@
+// Braille dots chasing each other around a cell
+const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+// The draw loop runs at about 60 ticks a second; turn at about 10 frames a second
+const TICKS_PER_FRAME: u64 = 6;
+
+// Animated marker for work running in the background, shared by every view
+// that waits on something, so they all look alive in the same way
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Spinner {
+    tick: u64, // Draw loop iterations so far
+}
+
+impl Spinner {
+    pub fn new(tick: u64) -> Self {
+        Spinner { tick }
+    }
+
+    pub fn frame(self) -> &'static str {
+        FRAMES[(self.tick / TICKS_PER_FRAME) as usize % FRAMES.len()]
+    }
+
+    // The spinner in front of a message, e.g. "⠹ Loading stations…"
+    pub fn label(self, text: &str) -> String {
+        format!("{} {}", self.frame(), text)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::db::{format_play_time, format_relative_time, HistoryEntry, Station, StationStats};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+// Whether the station answered when the detail page was opened
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reachability {
+    Checking,
+    Reachable,
+    Unreachable(String),
+    Unknown(String), // Not checked, e.g. while offline
+}
+
+// Everything known about one station, loaded when its page is opened
+pub struct StationDetail {
+    pub station: Station,
+    pub tags: Vec<String>,
+    pub stats: Option<StationStats>,
+    pub play_count: usize,
+    pub history: Vec<HistoryEntry>, // Latest plays, newest first
+    pub reachability: Reachability,
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn field(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<14}", label), Style::default().fg(Color::Gray)),
+        Span::raw(value),
+    ])
+}
+
+// A full-page view of one station: what it is, how it's been listened to
+// and whether it's reachable
+pub fn render_station_detail(
+    f: &mut Frame,
+    detail: &StationDetail,
+    spinner: super::Spinner,
+    area: Rect,
+) {
+    let station = &detail.station;
+    let mut lines = vec![
+        heading("Station"),
+        field("Name", station.name.clone()),
+        field("URL", station.url.clone()),
+        field(
+            "Description",
+            station
+                .description
+                .clone()
+                .filter(|text| !text.trim().is_empty())
+                .unwrap_or_else(|| "—".to_string()),
+        ),
+        field(
+            "Tags",
+            if detail.tags.is_empty() {
+                "—".to_string()
+            } else {
+                detail.tags.join(", ")
+            },
+        ),
+        field(
+            "Favorite",
+            if station.favorite { "yes" } else { "no" }.to_string(),
+        ),
+        field(
+            "Pinned",
+            if station.pinned { "yes" } else { "no" }.to_string(),
+        ),
+    ];
+    if let Some(args) = station.mpv_args.as_deref().filter(|args| !args.is_empty()) {
+        lines.push(field("mpv args", args.to_string()));
+    }
+
+    let (status, color) = match &detail.reachability {
+        Reachability::Checking => (spinner.label("Checking…"), Color::Gray),
+        Reachability::Reachable => ("Reachable".to_string(), Color::Green),
+        Reachability::Unreachable(e) => (format!("Unreachable: {}", e), Color::Red),
+        Reachability::Unknown(reason) => (reason.clone(), Color::Gray),
+    };
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:<14}", "Status"),
+            Style::default().fg(Color::Gray),
+        ),
+        Span::styled(status, Style::default().fg(color)),
+    ]));
+    if let Some(checked_at) = station.last_check_time {
+        let result = match station.last_check_status.as_deref() {
+            Some(status) if station.check_failed() => format!("⚠ failed: {}", status),
+            _ => "passed".to_string(),
+        };
+        lines.push(field(
+            "Last check",
+            format!("{} ({})", result, format_relative_time(checked_at)),
+        ));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Listening"));
+    match &detail.stats {
+        Some(stats) => {
+            lines.push(field("Play time", format_play_time(stats.total_play_time)));
+            lines.push(field("Plays", detail.play_count.to_string()));
+            lines.push(field(
+                "Last played",
+                stats
+                    .last_played
+                    .map(format_relative_time)
+                    .unwrap_or_else(|| "never".to_string()),
+            ));
+        }
+        None => lines.push(Line::from("Never played")),
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Recent Songs"));
+    let songs: Vec<&HistoryEntry> = detail
+        .history
+        .iter()
+        .filter(|entry| entry.song.is_some())
+        .collect();
+    if songs.is_empty() {
+        lines.push(Line::from("None recorded yet"));
+    }
+    for entry in songs {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<14}", format_relative_time(entry.started_at)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::raw(entry.song.clone().unwrap_or_default()),
+        ]));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(format!("Station Details - {}", station.name)),
+            )
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
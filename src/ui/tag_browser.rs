@@ -0,0 +1,94 @@
+use crate::db::Station;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+// What the tag browser shows: the tags and the highlighted tag's stations
+pub struct TagBrowser<'a> {
+    pub tag_counts: &'a [(String, usize)],
+    pub tag_list_state: &'a mut ListState,
+    pub stations: &'a [Station],
+    pub station_state: &'a mut ListState,
+    pub stations_focused: bool,
+}
+
+// Tags with their station counts on the left, the highlighted tag's
+// stations on the right. The focused list gets the yellow border.
+pub fn render_tag_browser(
+    f: &mut Frame,
+    browser: TagBrowser,
+    list_style: &super::ListStyle,
+    area: Rect,
+) {
+    let TagBrowser {
+        tag_counts,
+        tag_list_state,
+        stations,
+        station_state,
+        stations_focused,
+    } = browser;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+        .split(area);
+    let border = |focused: bool| {
+        Style::default().fg(if focused {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        })
+    };
+    let tags: Vec<ListItem> = tag_counts
+        .iter()
+        .map(|(tag, count)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(tag.as_str(), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(" ({})", count),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+    let tag_list = list_style.apply(
+        List::new(tags).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border(!stations_focused))
+                .title(format!("Tags ({})", tag_counts.len())),
+        ),
+    );
+    f.render_widget(Clear, chunks[0]);
+    f.render_stateful_widget(tag_list, chunks[0], tag_list_state);
+
+    let selected_tag = tag_list_state
+        .selected()
+        .and_then(|i| tag_counts.get(i))
+        .map(|(tag, _)| tag.as_str())
+        .unwrap_or_default();
+    let items: Vec<ListItem> = stations
+        .iter()
+        .map(|s| {
+            let name = if s.favorite {
+                format!("★ {}", s.name)
+            } else {
+                s.name.clone()
+            };
+            ListItem::new(name).style(Style::default().fg(Color::Cyan))
+        })
+        .collect();
+    let station_list = list_style.apply(
+        List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border(stations_focused))
+                .title(format!("Stations tagged \"{}\"", selected_tag)),
+        ),
+    );
+    f.render_widget(Clear, chunks[1]);
+    f.render_stateful_widget(station_list, chunks[1], station_state);
+}
@@ -0,0 +1,93 @@
+use crate::visualizations::ColorMode;
+use ratatui::{buffer::Buffer, style::Color};
+
+// Settings key for the color theme ("default", "terminal" or "base16")
+pub const THEME_SETTING: &str = "theme";
+
+// Set by base16-shell and tinted-shell once they've loaded a scheme
+pub const BASE16_ENV: &str = "BASE16_THEME";
+
+// base16 shells put the extra shades of a scheme in palette slots 18-21
+const BASE16_LIGHTER_BACKGROUND: Color = Color::Indexed(18); // base01
+const BASE16_SELECTION_BACKGROUND: Color = Color::Indexed(19); // base02
+const BASE16_DARK_FOREGROUND: Color = Color::Indexed(20); // base04
+
+// How the UI's colors fit in with the terminal's own color scheme
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Theme {
+    // The classic look: black popups and white text
+    #[default]
+    Default,
+    // The terminal's own background and text colors instead of black and white
+    Terminal,
+    // Like Terminal, with popups shaded from the base16 scheme's extra colors
+    Base16,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" | "classic" => Some(Theme::Default),
+            "terminal" | "ansi" => Some(Theme::Terminal),
+            "base16" => Some(Theme::Base16),
+            _ => None,
+        }
+    }
+
+    // The chosen theme; without a usable choice, base16 shells get Base16
+    pub fn detect(choice: Option<&str>) -> Self {
+        let chosen = choice.and_then(|name| {
+            let theme = Theme::from_name(name);
+            if theme.is_none() {
+                log::warn!("Ignoring unknown theme: {}", name);
+            }
+            theme
+        });
+        chosen.unwrap_or_else(|| Self::from_base16_env(std::env::var(BASE16_ENV).ok().as_deref()))
+    }
+
+    pub fn from_base16_env(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(scheme) if !scheme.is_empty() => Theme::Base16,
+            _ => Theme::Default,
+        }
+    }
+
+    // Themed modes draw visualizations from the terminal's 16 colors too
+    pub fn color_mode(self, detected: ColorMode) -> ColorMode {
+        match self {
+            Theme::Default => detected,
+            Theme::Terminal | Theme::Base16 => ColorMode::Ansi16,
+        }
+    }
+
+    // The colors a cell drawn as `fg` on `bg` gets in this theme
+    pub fn cell_colors(self, fg: Color, bg: Color) -> (Color, Color) {
+        let bg = match (self, bg) {
+            (Theme::Terminal, Color::Black | Color::DarkGray) => Color::Reset,
+            (Theme::Base16, Color::Black) => BASE16_LIGHTER_BACKGROUND,
+            (Theme::Base16, Color::DarkGray) => BASE16_SELECTION_BACKGROUND,
+            _ => bg,
+        };
+        let fg = match (self, fg) {
+            (Theme::Default, _) => fg,
+            (_, Color::White) => Color::Reset,
+            (Theme::Base16, Color::DarkGray) => BASE16_DARK_FOREGROUND,
+            _ => fg,
+        };
+        (fg, bg)
+    }
+
+    // Recolor a drawn frame. Done once over the whole screen so every
+    // widget follows the theme without knowing about it.
+    pub fn apply(self, buffer: &mut Buffer) {
+        if self == Theme::Default {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            let (fg, bg) = self.cell_colors(cell.fg, cell.bg);
+            cell.fg = fg;
+            cell.bg = bg;
+        }
+    }
+}
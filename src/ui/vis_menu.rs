@@ -1,7 +1,7 @@
 use crate::visualizations::VisualizationManager;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Color, Style},
     text::Span,
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
@@ -11,6 +11,7 @@ pub fn render_visualization_menu(
     f: &mut Frame,
     vis_manager: &VisualizationManager,
     vis_menu_state: &mut ListState,
+    list_style: &super::ListStyle,
     area: Rect,
 ) {
     // Create a centered popup
@@ -62,19 +63,13 @@ pub fn render_visualization_menu(
         .collect();
 
     // Create list widget with highlighting
-    let list = List::new(items)
-        .block(
+    let list = list_style.apply(
+        List::new(items).block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Available Visualizations"),
-        )
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
+        ),
+    );
 
     // Render the list with the state
     f.render_stateful_widget(list, chunks[0], vis_menu_state);
@@ -0,0 +1,36 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Gauge},
+    Frame,
+};
+
+use crate::audio::AudioState;
+
+// Function to render the volume gauge
+pub fn render_volume_gauge(f: &mut Frame, state: &AudioState, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title("Volume");
+
+    let gauge = if state.is_muted {
+        // Show a distinct, empty gauge while muted
+        Gauge::default()
+            .block(block)
+            .gauge_style(
+                Style::default()
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .ratio(0.0)
+            .label("MUTED")
+    } else {
+        let volume = state.volume.min(100);
+        Gauge::default()
+            .block(block)
+            .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+            .percent(volume as u16)
+            .label(format!("{}%", volume))
+    };
+
+    f.render_widget(gauge, area);
+}
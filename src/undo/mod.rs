@@ -0,0 +1,211 @@
+use crate::db::{self, Credentials, Station, StationStats};
+use rusqlite::Connection;
+use std::collections::VecDeque;
+use std::error::Error;
+
+// How many operations can be undone before the oldest is forgotten
+pub const UNDO_CAPACITY: usize = 20;
+
+// A library change, holding enough state to reverse and replay it
+pub enum StationCommand {
+    Add(Box<StationSnapshot>),
+    Edit {
+        before: Box<StationSnapshot>,
+        after: Box<StationSnapshot>,
+    },
+    Delete(Vec<StationSnapshot>),
+}
+
+// A station with everything needed to bring it back or put it back as it was
+pub struct StationSnapshot {
+    pub station: Station,
+    pub stats: Option<StationStats>,
+    pub tags: Vec<String>,
+    pub mirrors: Vec<String>,
+    pub credentials: Option<Credentials>,
+}
+
+impl StationSnapshot {
+    // Capture a station along with its stats, tags, mirrors and login
+    pub fn take(conn: &Connection, station: Station) -> Result<Self, Box<dyn Error>> {
+        Ok(StationSnapshot {
+            stats: db::get_station_stats(conn, station.id)?,
+            tags: db::get_station_tags(conn, station.id)?,
+            mirrors: db::get_station_mirrors(conn, station.id)?,
+            credentials: db::get_station_credentials(conn, station.id)?,
+            station,
+        })
+    }
+
+    // Re-insert the station under its original id
+    fn restore(&self, conn: &Connection) -> Result<(), Box<dyn Error>> {
+        db::restore_station(conn, &self.station)?;
+        if let Some(stats) = &self.stats {
+            db::restore_station_stats(conn, stats)?;
+        }
+        self.write_details(conn)
+    }
+
+    // Put the fields the edit form changes back the way they were
+    fn write(&self, conn: &Connection) -> Result<(), Box<dyn Error>> {
+        let station = &self.station;
+        db::update_station(
+            conn,
+            station.id,
+            &station.name,
+            &station.url,
+            station.description.as_deref(),
+            station.mpv_args.as_deref(),
+        )?;
+        self.write_details(conn)
+    }
+
+    fn write_details(&self, conn: &Connection) -> Result<(), Box<dyn Error>> {
+        let id = self.station.id;
+        db::set_station_tags(conn, id, &self.tags)?;
+        db::set_station_mirrors(conn, id, &self.mirrors)?;
+        db::set_station_credentials(conn, id, self.credentials.as_ref())
+    }
+}
+
+impl StationCommand {
+    // Snapshot stations (and their stats, tags, mirrors and login) before deleting them
+    pub fn delete(conn: &Connection, stations: Vec<Station>) -> Result<Self, Box<dyn Error>> {
+        let mut entries = Vec::with_capacity(stations.len());
+        for station in stations {
+            entries.push(StationSnapshot::take(conn, station)?);
+        }
+        Ok(StationCommand::Delete(entries))
+    }
+
+    // Reverse the change in the database
+    fn revert(&self, conn: &Connection) -> Result<(), Box<dyn Error>> {
+        match self {
+            StationCommand::Add(added) => db::delete_station(conn, added.station.id),
+            StationCommand::Edit { before, .. } => before.write(conn),
+            StationCommand::Delete(entries) => {
+                for entry in entries {
+                    entry.restore(conn)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Perform the change again after it was undone
+    fn apply(&self, conn: &Connection) -> Result<(), Box<dyn Error>> {
+        match self {
+            StationCommand::Add(added) => added.restore(conn),
+            StationCommand::Edit { after, .. } => after.write(conn),
+            StationCommand::Delete(entries) => {
+                for entry in entries {
+                    db::delete_station(conn, entry.station.id)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Short description for the status bar
+    pub fn describe(&self) -> String {
+        match self {
+            StationCommand::Add(added) => format!("add of \"{}\"", added.station.name),
+            StationCommand::Edit { after, .. } => format!("edit of \"{}\"", after.station.name),
+            StationCommand::Delete(entries) => match entries.as_slice() {
+                [entry] => format!("delete of \"{}\"", entry.station.name),
+                _ => format!("delete of {} stations", entries.len()),
+            },
+        }
+    }
+}
+
+// Stations stay deleted for good once their command leaves the history, so
+// their keyring passwords can go too. An applied command has deleted the
+// stations it removed; an undone one has deleted the station it added.
+fn forget_secrets(command: &StationCommand, applied: bool) {
+    match (command, applied) {
+        (StationCommand::Delete(entries), true) => {
+            for entry in entries {
+                db::forget_station_secret(entry.station.id);
+            }
+        }
+        (StationCommand::Add(added), false) => db::forget_station_secret(added.station.id),
+        _ => {}
+    }
+}
+
+// In-memory undo/redo history of library changes
+pub struct UndoStack {
+    undo: VecDeque<StationCommand>,
+    redo: Vec<StationCommand>,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        UndoStack {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    // Remember a change that has just been made; this invalidates the redo history
+    pub fn record(&mut self, command: StationCommand) {
+        self.undo.push_back(command);
+        if self.undo.len() > UNDO_CAPACITY {
+            if let Some(oldest) = self.undo.pop_front() {
+                forget_secrets(&oldest, true);
+            }
+        }
+        for undone in self.redo.drain(..) {
+            forget_secrets(&undone, false);
+        }
+    }
+
+    // Drop the whole history at the end of a session
+    pub fn clear(&mut self) {
+        for applied in self.undo.drain(..) {
+            forget_secrets(&applied, true);
+        }
+        for undone in self.redo.drain(..) {
+            forget_secrets(&undone, false);
+        }
+    }
+
+    // Undo the most recent change, returning its description
+    pub fn undo(&mut self, conn: &Connection) -> Result<Option<String>, Box<dyn Error>> {
+        let Some(command) = self.undo.pop_back() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = command.revert(conn) {
+            self.undo.push_back(command);
+            return Err(e);
+        }
+
+        let description = command.describe();
+        self.redo.push(command);
+        Ok(Some(description))
+    }
+
+    // Redo the most recently undone change, returning its description
+    pub fn redo(&mut self, conn: &Connection) -> Result<Option<String>, Box<dyn Error>> {
+        let Some(command) = self.redo.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = command.apply(conn) {
+            self.redo.push(command);
+            return Err(e);
+        }
+
+        let description = command.describe();
+        self.undo.push_back(command);
+        Ok(Some(description))
+    }
+}
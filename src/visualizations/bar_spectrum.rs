@@ -1,9 +1,19 @@
-use super::Visualization;
+use super::{ColorMode, Visualization};
 use crate::audio::AudioState;
 use ratatui::style::Color;
-use ratatui::widgets::canvas::{Context, Rectangle};
+use ratatui::widgets::canvas::{Context, Line, Rectangle};
+use std::cell::{Cell, RefCell};
 
-pub struct BarSpectrumVisualization;
+const NUM_BARS: usize = 30;
+
+// How far a peak marker falls per frame once the bar drops below it
+const PEAK_DECAY_PER_FRAME: f64 = 0.4;
+
+pub struct BarSpectrumVisualization {
+    // Peak-hold heights per bar; render() only gets &self, hence the cells
+    peaks: RefCell<Vec<f64>>,
+    last_frame: Cell<u64>,
+}
 
 impl Default for BarSpectrumVisualization {
     fn default() -> Self {
@@ -13,7 +23,10 @@ impl Default for BarSpectrumVisualization {
 
 impl BarSpectrumVisualization {
     pub fn new() -> Self {
-        BarSpectrumVisualization
+        BarSpectrumVisualization {
+            peaks: RefCell::new(vec![0.0; NUM_BARS]),
+            last_frame: Cell::new(0),
+        }
     }
 }
 
@@ -23,21 +36,26 @@ impl Visualization for BarSpectrumVisualization {
     }
 
     fn description(&self) -> &str {
-        "Audio spectrum visualization with vertical bars"
+        "Audio spectrum visualization with vertical bars and peak hold"
     }
 
-    fn render(&self, ctx: &mut Context, state: &AudioState) {
+    fn render(&self, ctx: &mut Context, state: &AudioState, _aspect: f64, colors: ColorMode) {
         // Background - dark background
         ctx.draw(&Rectangle {
             x: 0.0,
             y: 0.0,
             width: 100.0,
             height: 100.0,
-            color: Color::Rgb(10, 10, 20),
+            color: colors.adapt(Color::Rgb(10, 10, 20)),
         });
 
+        // Let peaks fall by however many frames passed since the last draw
+        let elapsed = state.frame_count.saturating_sub(self.last_frame.get()) as f64;
+        self.last_frame.set(state.frame_count);
+        let mut peaks = self.peaks.borrow_mut();
+
         if state.is_playing {
-            let num_bars = 30;
+            let num_bars = NUM_BARS;
             let bar_width = 100.0 / num_bars as f64;
             let spacing = 1.0;
             let effective_width = bar_width - spacing;
@@ -72,12 +90,24 @@ impl Visualization for BarSpectrumVisualization {
                 );
 
                 // Draw the bar
+                let bar_x = i as f64 * bar_width + spacing / 2.0;
                 ctx.draw(&Rectangle {
-                    x: i as f64 * bar_width + spacing / 2.0,
+                    x: bar_x,
                     y: 100.0 - height,
                     width: effective_width,
                     height,
-                    color,
+                    color: colors.adapt(color),
+                });
+
+                // Peak hold: jump up with the bar, then sink slowly
+                let peak = &mut peaks[i];
+                *peak = height.max(*peak - PEAK_DECAY_PER_FRAME * elapsed);
+                ctx.draw(&Line {
+                    x1: bar_x,
+                    y1: 100.0 - *peak,
+                    x2: bar_x + effective_width,
+                    y2: 100.0 - *peak,
+                    color: colors.adapt(Color::Rgb(230, 230, 255)),
                 });
             }
 
@@ -93,7 +123,7 @@ impl Visualization for BarSpectrumVisualization {
                 y,
                 width: indicator_width,
                 height: indicator_height,
-                color: Color::Rgb(30, 30, 50),
+                color: colors.adapt(Color::Rgb(30, 30, 50)),
             });
 
             // Filled portion based on bass impact
@@ -102,13 +132,15 @@ impl Visualization for BarSpectrumVisualization {
                 y,
                 width: indicator_width * state.bass_impact,
                 height: indicator_height,
-                color: Color::Rgb(
+                color: colors.adapt(Color::Rgb(
                     100 + (155.0 * state.bass_impact) as u8,
                     50 + (100.0 * (1.0 - state.bass_impact)) as u8,
                     200,
-                ),
+                )),
             });
         } else {
+            peaks.iter_mut().for_each(|peak| *peak = 0.0);
+
             // Draw a static pattern when not playing
             for i in 0..15 {
                 let height = 5.0 + (i as f64 % 5.0) * 3.0;
@@ -119,7 +151,7 @@ impl Visualization for BarSpectrumVisualization {
                     y: 50.0 - height / 2.0,
                     width: 3.0,
                     height,
-                    color: Color::Rgb(50, 50, 100),
+                    color: colors.adapt(Color::Rgb(50, 50, 100)),
                 });
             }
         }
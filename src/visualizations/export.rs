@@ -0,0 +1,142 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::Color,
+    widgets::{canvas::Canvas, Block, Borders},
+    Terminal,
+};
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::{canvas_aspect_ratio, ColorMode, VisualizationManager, VisualizationType};
+use crate::audio::AudioState;
+
+// Playback rate written into asciicast recordings
+const ASCIICAST_FPS: f64 = 30.0;
+
+// Render `count` frames of a visualization off-screen. The animation is
+// driven by an RNG seeded with `seed`, so the same seed gives the same frames.
+pub fn render_frames(
+    vis_type: VisualizationType,
+    count: usize,
+    seed: u64,
+    width: u16,
+    height: u16,
+    colors: ColorMode,
+) -> Result<Vec<Buffer>, Box<dyn Error>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut state = AudioState::with_rng(&mut rng);
+    state.is_playing = true;
+
+    let mut manager = VisualizationManager::new();
+    manager.set_visualization_type(vis_type);
+
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    let mut frames = Vec::with_capacity(count);
+    for _ in 0..count {
+        state.update_visualization_with(&mut rng);
+        terminal.draw(|f| {
+            let area = f.size();
+            let aspect = canvas_aspect_ratio(area);
+            let canvas = Canvas::default()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(vis_type.to_string()),
+                )
+                .x_bounds([0.0, 100.0])
+                .y_bounds([0.0, 100.0])
+                .paint(|ctx| manager.render(ctx, &state, aspect, colors));
+            f.render_widget(canvas, area);
+        })?;
+        frames.push(terminal.backend().buffer().clone());
+    }
+    Ok(frames)
+}
+
+// A frame as text with ANSI color codes, rows separated by "\r\n"
+pub fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut rows = Vec::with_capacity(area.height as usize);
+    for y in area.top()..area.bottom() {
+        let mut row = String::new();
+        let mut colors = None;
+        for x in area.left()..area.right() {
+            let cell = buffer.get(x, y);
+            if colors != Some((cell.fg, cell.bg)) {
+                row.push_str(&format!(
+                    "\x1b[0;{};{}m",
+                    sgr_color(cell.fg, false),
+                    sgr_color(cell.bg, true)
+                ));
+                colors = Some((cell.fg, cell.bg));
+            }
+            row.push_str(&cell.symbol);
+        }
+        row.push_str("\x1b[0m");
+        rows.push(row);
+    }
+    rows.join("\r\n")
+}
+
+// SGR parameters selecting a foreground or background color
+fn sgr_color(color: Color, background: bool) -> String {
+    let base = if background { 40 } else { 30 };
+    let basic = |offset: u8| (base + offset).to_string();
+    let bright = |offset: u8| (base + 60 + offset).to_string();
+    let extended = if background { 48 } else { 38 };
+    match color {
+        Color::Reset => (base + 9).to_string(),
+        Color::Black => basic(0),
+        Color::Red => basic(1),
+        Color::Green => basic(2),
+        Color::Yellow => basic(3),
+        Color::Blue => basic(4),
+        Color::Magenta => basic(5),
+        Color::Cyan => basic(6),
+        Color::Gray => basic(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", extended, r, g, b),
+        Color::Indexed(i) => format!("{};5;{}", extended, i),
+    }
+}
+
+// Write each frame to its own frame_0001.ans, frame_0002.ans, ... in `dir`
+pub fn write_ansi_frames(dir: &Path, frames: &[Buffer]) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    for (i, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("frame_{:04}.ans", i + 1));
+        fs::write(path, format!("{}\r\n", buffer_to_ansi(frame)))?;
+    }
+    Ok(())
+}
+
+// Write the frames as an asciicast v2 recording, playable with asciinema
+pub fn asciicast(frames: &[Buffer]) -> String {
+    let (width, height) = frames
+        .first()
+        .map(|frame| (frame.area.width, frame.area.height))
+        .unwrap_or((0, 0));
+
+    let mut cast = json!({ "version": 2, "width": width, "height": height }).to_string();
+    cast.push('\n');
+    for (i, frame) in frames.iter().enumerate() {
+        let time = i as f64 / ASCIICAST_FPS;
+        // Home the cursor and redraw the whole screen each frame
+        let data = format!("\x1b[H{}", buffer_to_ansi(frame));
+        cast.push_str(&json!([time, "o", data]).to_string());
+        cast.push('\n');
+    }
+    cast
+}
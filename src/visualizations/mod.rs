@@ -1,11 +1,92 @@
+use ratatui::layout::Rect;
+use ratatui::style::Color;
 use ratatui::widgets::canvas::Context;
 use std::fmt;
 
 use crate::audio::AudioState;
 
+// Terminal cells are roughly twice as tall as they are wide
+const CELL_WIDTH_TO_HEIGHT: f64 = 0.5;
+
+// Physical width/height of a bordered canvas area. Visualizations draw on a
+// 0-100 square coordinate space, so they need this to keep circles round.
+pub fn canvas_aspect_ratio(area: Rect) -> f64 {
+    let width = area.width.saturating_sub(2).max(1) as f64;
+    let height = area.height.saturating_sub(2).max(1) as f64;
+    width * CELL_WIDTH_TO_HEIGHT / height
+}
+
+// How many colors the terminal can show. Visualizations compute RGB colors
+// and pass them through adapt() so basic terminals get the closest ANSI color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    #[default]
+    TrueColor,
+    Ansi16,
+}
+
+// xterm's default RGB values for the 16 ANSI colors
+const ANSI16_PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+impl ColorMode {
+    // Truecolor terminals announce themselves through COLORTERM
+    pub fn detect() -> Self {
+        Self::from_colorterm(std::env::var("COLORTERM").ok().as_deref())
+    }
+
+    pub fn from_colorterm(value: Option<&str>) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("truecolor" | "24bit") => ColorMode::TrueColor,
+            _ => ColorMode::Ansi16,
+        }
+    }
+
+    // Map a color to one the terminal can show
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorMode::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16(r, g, b),
+            _ => color,
+        }
+    }
+}
+
+// The ANSI color closest to an RGB value
+pub fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    let distance = |(pr, pg, pb): (u8, u8, u8)| {
+        let dr = r as i32 - pr as i32;
+        let dg = g as i32 - pg as i32;
+        let db = b as i32 - pb as i32;
+        dr * dr + dg * dg + db * db
+    };
+    ANSI16_PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
 // Trait for visualizations to implement
 pub trait Visualization {
-    fn render(&self, ctx: &mut Context, state: &AudioState);
+    // `aspect` is the canvas width/height ratio from canvas_aspect_ratio and
+    // every color drawn goes through `colors`
+    fn render(&self, ctx: &mut Context, state: &AudioState, aspect: f64, colors: ColorMode);
     fn name(&self) -> &str;
     fn description(&self) -> &str;
 }
@@ -16,6 +97,9 @@ pub enum VisualizationType {
     Starfield,
     BarSpectrum,
     WaveForms,
+    RadialSpectrum,
+    Plasma,
+    TextScroller,
 }
 
 impl fmt::Display for VisualizationType {
@@ -24,18 +108,58 @@ impl fmt::Display for VisualizationType {
             VisualizationType::Starfield => write!(f, "Starfield"),
             VisualizationType::BarSpectrum => write!(f, "Bar Spectrum"),
             VisualizationType::WaveForms => write!(f, "Wave Forms"),
+            VisualizationType::RadialSpectrum => write!(f, "Radial Spectrum"),
+            VisualizationType::Plasma => write!(f, "Plasma"),
+            VisualizationType::TextScroller => write!(f, "Text Scroller"),
         }
     }
 }
 
+impl VisualizationType {
+    pub const ALL: [VisualizationType; 6] = [
+        VisualizationType::Starfield,
+        VisualizationType::BarSpectrum,
+        VisualizationType::WaveForms,
+        VisualizationType::RadialSpectrum,
+        VisualizationType::Plasma,
+        VisualizationType::TextScroller,
+    ];
+
+    // Look a visualization up by name, ignoring case, spaces and dashes
+    // ("starfield", "bar-spectrum", "WaveForms", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted: String = name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        Self::ALL.into_iter().find(|vis_type| {
+            vis_type
+                .to_string()
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+                == wanted
+        })
+    }
+}
+
 // Module imports
 mod bar_spectrum;
+pub mod export;
+mod plasma;
+mod radial_spectrum;
 mod starfield;
+mod text_scroller;
 mod waveforms;
 
 // Re-exports
 pub use bar_spectrum::BarSpectrumVisualization;
+pub use plasma::PlasmaVisualization;
+pub use radial_spectrum::RadialSpectrumVisualization;
 pub use starfield::StarfieldVisualization;
+pub use text_scroller::TextScrollerVisualization;
 pub use waveforms::WaveFormsVisualization;
 
 // Manager for handling visualizations
@@ -44,6 +168,9 @@ pub struct VisualizationManager {
     starfield: StarfieldVisualization,
     bar_spectrum: BarSpectrumVisualization,
     waveforms: WaveFormsVisualization,
+    radial_spectrum: RadialSpectrumVisualization,
+    plasma: PlasmaVisualization,
+    text_scroller: TextScrollerVisualization,
 }
 
 impl Default for VisualizationManager {
@@ -59,6 +186,9 @@ impl VisualizationManager {
             starfield: StarfieldVisualization::new(),
             bar_spectrum: BarSpectrumVisualization::new(),
             waveforms: WaveFormsVisualization::new(),
+            radial_spectrum: RadialSpectrumVisualization::new(),
+            plasma: PlasmaVisualization::new(),
+            text_scroller: TextScrollerVisualization::new(),
         }
     }
 
@@ -67,6 +197,9 @@ impl VisualizationManager {
             VisualizationType::Starfield => &self.starfield,
             VisualizationType::BarSpectrum => &self.bar_spectrum,
             VisualizationType::WaveForms => &self.waveforms,
+            VisualizationType::RadialSpectrum => &self.radial_spectrum,
+            VisualizationType::Plasma => &self.plasma,
+            VisualizationType::TextScroller => &self.text_scroller,
         }
     }
 
@@ -95,6 +228,21 @@ impl VisualizationManager {
                 self.waveforms.name(),
                 self.waveforms.description(),
             ),
+            (
+                VisualizationType::RadialSpectrum,
+                self.radial_spectrum.name(),
+                self.radial_spectrum.description(),
+            ),
+            (
+                VisualizationType::Plasma,
+                self.plasma.name(),
+                self.plasma.description(),
+            ),
+            (
+                VisualizationType::TextScroller,
+                self.text_scroller.name(),
+                self.text_scroller.description(),
+            ),
         ]
     }
 
@@ -118,7 +266,8 @@ impl VisualizationManager {
     }
     */
 
-    pub fn render(&self, ctx: &mut Context, state: &AudioState) {
-        self.current_visualization().render(ctx, state);
+    pub fn render(&self, ctx: &mut Context, state: &AudioState, aspect: f64, colors: ColorMode) {
+        self.current_visualization()
+            .render(ctx, state, aspect, colors);
     }
 }
@@ -0,0 +1,75 @@
+use super::{ColorMode, Visualization};
+use crate::audio::AudioState;
+use ratatui::style::Color;
+use ratatui::widgets::canvas::{Context, Line};
+
+// Rows and segments per row; each segment is one flat-colored line
+const ROWS: usize = 100;
+const COLUMNS: usize = 40;
+
+pub struct PlasmaVisualization;
+
+impl Default for PlasmaVisualization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlasmaVisualization {
+    pub fn new() -> Self {
+        PlasmaVisualization
+    }
+}
+
+// Classic plasma: a sum of sine waves over position and time, in -1.0..1.0
+fn plasma_value(x: f64, y: f64, t: f64) -> f64 {
+    let v1 = (x * 0.08 + t).sin();
+    let v2 = (y * 0.1 - t * 0.7).sin();
+    let v3 = ((x + y) * 0.05 + t * 0.5).sin();
+    let (cx, cy) = (x - 50.0 + (t * 0.3).sin() * 30.0, y - 50.0);
+    let v4 = ((cx * cx + cy * cy).sqrt() * 0.08 - t).sin();
+    (v1 + v2 + v3 + v4) / 4.0
+}
+
+impl Visualization for PlasmaVisualization {
+    fn name(&self) -> &str {
+        "Plasma"
+    }
+
+    fn description(&self) -> &str {
+        "Slowly shifting color field, brightened by the bass"
+    }
+
+    fn render(&self, ctx: &mut Context, state: &AudioState, _aspect: f64, colors: ColorMode) {
+        // Drift slowly, and dimly when nothing is playing
+        let t = state.frame_count as f64 * 0.015;
+        let brightness = if state.is_playing {
+            0.55 + state.bass_impact * 0.45
+        } else {
+            0.3
+        };
+
+        let width = 100.0 / COLUMNS as f64;
+        for row in 0..ROWS {
+            let y = row as f64;
+            for column in 0..COLUMNS {
+                let x = column as f64 * width;
+                let value = plasma_value(x + width / 2.0, y, t);
+
+                // Walk the value around the color wheel, phase-shifted per channel
+                let phase = value * std::f64::consts::PI + state.bass_impact;
+                let channel =
+                    |offset: f64| ((0.5 + 0.5 * (phase + offset).sin()) * brightness * 255.0) as u8;
+                let color = Color::Rgb(channel(0.0), channel(2.1), channel(4.2));
+
+                ctx.draw(&Line {
+                    x1: x,
+                    y1: y,
+                    x2: x + width,
+                    y2: y,
+                    color: colors.adapt(color),
+                });
+            }
+        }
+    }
+}
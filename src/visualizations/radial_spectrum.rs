@@ -0,0 +1,116 @@
+use super::{ColorMode, Visualization};
+use crate::audio::AudioState;
+use ratatui::style::Color;
+use ratatui::widgets::canvas::{Context, Line, Rectangle};
+use std::f64::consts::PI;
+
+const NUM_SPOKES: usize = 48;
+
+// Radii in canvas units along the shorter side of the canvas
+const INNER_RADIUS: f64 = 8.0;
+const MAX_LENGTH: f64 = 38.0;
+
+pub struct RadialSpectrumVisualization;
+
+impl Default for RadialSpectrumVisualization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RadialSpectrumVisualization {
+    pub fn new() -> Self {
+        RadialSpectrumVisualization
+    }
+}
+
+impl Visualization for RadialSpectrumVisualization {
+    fn name(&self) -> &str {
+        "Radial Spectrum"
+    }
+
+    fn description(&self) -> &str {
+        "Spectrum bars radiating from the center, pulsing with the bass"
+    }
+
+    fn render(&self, ctx: &mut Context, state: &AudioState, aspect: f64, colors: ColorMode) {
+        // Background - dark purple
+        ctx.draw(&Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+            color: colors.adapt(Color::Rgb(12, 5, 20)),
+        });
+
+        // Same physical scale on both axes so the pattern stays round
+        let (scale_x, scale_y) = if aspect >= 1.0 {
+            (1.0 / aspect, 1.0)
+        } else {
+            (1.0, aspect)
+        };
+        let point = |angle: f64, radius: f64| {
+            (
+                50.0 + angle.cos() * radius * scale_x,
+                50.0 + angle.sin() * radius * scale_y,
+            )
+        };
+
+        let t = state.frame_count as f64 * 0.02;
+        for i in 0..NUM_SPOKES {
+            let position = i as f64 / NUM_SPOKES as f64;
+            let angle = position * 2.0 * PI;
+
+            let (length, color) = if state.is_playing {
+                // Each angle gets its own phase so the bars ripple around the circle
+                let phase1 = t * 0.6 + angle * 3.0;
+                let phase2 = t * 0.9 - angle * 5.0;
+                let phase3 = t * 0.4 + angle * 2.0;
+                let base =
+                    ((phase1.sin() * 0.5 + phase2.sin() * 0.3 + phase3.sin() * 0.2) + 1.0) / 2.0;
+                let length = base * (0.3 + state.bass_impact * 0.7) * MAX_LENGTH;
+
+                // Hue turns around the circle; longer bars glow brighter
+                let intensity = (length / MAX_LENGTH).min(1.0);
+                let r = 0.5 + 0.5 * (angle + t * 0.3).sin();
+                let b = 0.5 + 0.5 * (angle + t * 0.3).cos();
+                let color = Color::Rgb(
+                    ((0.3 + 0.7 * r * intensity) * 255.0) as u8,
+                    ((0.2 + 0.5 * state.bass_impact) * 255.0) as u8,
+                    ((0.4 + 0.6 * b) * 255.0) as u8,
+                );
+                (length.max(1.0), color)
+            } else {
+                // A still, dim ring when not playing
+                (2.0 + (i % 4) as f64, Color::Rgb(50, 40, 90))
+            };
+
+            let (x1, y1) = point(angle, INNER_RADIUS);
+            let (x2, y2) = point(angle, INNER_RADIUS + length);
+            ctx.draw(&Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color: colors.adapt(color),
+            });
+        }
+
+        // Inner circle that swells with the bass
+        let core = INNER_RADIUS * (0.5 + state.bass_impact * 0.4);
+        let segments = 32;
+        for i in 0..segments {
+            let a1 = i as f64 / segments as f64 * 2.0 * PI;
+            let a2 = (i + 1) as f64 / segments as f64 * 2.0 * PI;
+            let (x1, y1) = point(a1, core);
+            let (x2, y2) = point(a2, core);
+            ctx.draw(&Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color: colors.adapt(Color::Rgb(150 + (100.0 * state.bass_impact) as u8, 80, 200)),
+            });
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::Visualization;
+use super::{ColorMode, Visualization};
 use crate::audio::AudioState;
 use ratatui::style::Color;
 use ratatui::widgets::canvas::{Context, Line, Rectangle};
@@ -26,18 +26,18 @@ impl Visualization for StarfieldVisualization {
         "3D starfield with warp effect"
     }
 
-    fn render(&self, ctx: &mut Context, state: &AudioState) {
+    fn render(&self, ctx: &mut Context, state: &AudioState, aspect: f64, colors: ColorMode) {
         // Background - dark space
         ctx.draw(&Rectangle {
             x: 0.0,
             y: 0.0,
             width: 100.0,
             height: 100.0,
-            color: Color::Rgb(0, 0, 20), // Very dark blue
+            color: colors.adapt(Color::Rgb(0, 0, 20)), // Very dark blue
         });
 
         // Star color palette
-        let colors = [
+        let star_colors = [
             Color::Rgb(255, 255, 255), // White
             Color::Rgb(200, 200, 255), // Light blue
             Color::Rgb(255, 230, 200), // Light yellow
@@ -45,6 +45,15 @@ impl Visualization for StarfieldVisualization {
             Color::Rgb(200, 255, 200), // Light green
         ];
 
+        // Project with the same physical scale on both axes so the field expands
+        // as a circle instead of an ellipse stretched to the canvas shape
+        let (scale_x, scale_y) = if aspect >= 1.0 {
+            (40.0 / aspect, 40.0)
+        } else {
+            (40.0, 40.0 * aspect)
+        };
+        let (size_x, size_y) = (scale_x / 40.0, scale_y / 40.0);
+
         // Draw each star in the starfield
         for star in &state.stars {
             // Calculate projected position based on perspective
@@ -58,8 +67,8 @@ impl Visualization for StarfieldVisualization {
             // Calculate projected position (perspective projection)
             // Higher z = closer to viewer = further from center
             let scale = 1.0 / (1.01 - star.z.min(0.99)); // Avoid division by zero
-            let projected_x = center_x + star.x * scale * 40.0; // Scale factor for width
-            let projected_y = center_y + star.y * scale * 40.0; // Scale factor for height
+            let projected_x = center_x + star.x * scale * scale_x; // Scale factor for width
+            let projected_y = center_y + star.y * scale * scale_y; // Scale factor for height
 
             // Calculate size based on z (closer = larger)
             // Use non-linear scaling for more dramatic effect
@@ -74,7 +83,7 @@ impl Visualization for StarfieldVisualization {
             }
 
             // Get color for this star
-            let base_color = colors[star.color as usize % colors.len()];
+            let base_color = star_colors[star.color as usize % star_colors.len()];
 
             // Adjust color based on brightness
             let color = match base_color {
@@ -86,13 +95,13 @@ impl Visualization for StarfieldVisualization {
                 _ => base_color,
             };
 
-            // Draw the star
+            // Draw the star (square on screen, not in canvas units)
             ctx.draw(&Rectangle {
-                x: projected_x - size / 2.0,
-                y: projected_y - size / 2.0,
-                width: size,
-                height: size,
-                color,
+                x: projected_x - size * size_x / 2.0,
+                y: projected_y - size * size_y / 2.0,
+                width: size * size_x,
+                height: size * size_y,
+                color: colors.adapt(color),
             });
 
             // For closer stars, add a trail/streak effect when at high warp speed
@@ -129,7 +138,7 @@ impl Visualization for StarfieldVisualization {
                         y1: trail_y,
                         x2: projected_x,
                         y2: projected_y,
-                        color: trail_color,
+                        color: colors.adapt(trail_color),
                     });
                 }
             }
@@ -149,7 +158,7 @@ impl Visualization for StarfieldVisualization {
                 y,
                 width: indicator_width,
                 height: indicator_height,
-                color: Color::Rgb(30, 30, 50),
+                color: colors.adapt(Color::Rgb(30, 30, 50)),
             });
 
             // Draw filled portion based on warp speed (1.0 to 3.0 mapped to 0-100%)
@@ -159,11 +168,11 @@ impl Visualization for StarfieldVisualization {
                 y,
                 width: fill_width.min(indicator_width),
                 height: indicator_height,
-                color: Color::Rgb(
+                color: colors.adapt(Color::Rgb(
                     (100.0 + (155.0 * (warp - 1.0) / 2.0)) as u8,
                     (200.0 - (150.0 * (warp - 1.0) / 2.0)) as u8,
                     255,
-                ),
+                )),
             });
         }
     }
@@ -0,0 +1,186 @@
+use super::{ColorMode, Visualization};
+use crate::audio::AudioState;
+use ratatui::style::Color;
+use ratatui::widgets::canvas::{Context, Rectangle};
+use std::cell::{Cell, RefCell};
+
+// Shown when there's nothing playing to announce
+const IDLE_TEXT: &str = "RadioCLI";
+
+// Height of one LED in canvas units; glyphs are 7 LEDs tall
+const LED_SIZE: f64 = 5.0;
+
+// Canvas units scrolled per frame at warp speed 1.0
+const SCROLL_SPEED: f64 = 0.6;
+
+// 5x7 glyphs, one row per byte with the leftmost LED in bit 4
+#[rustfmt::skip]
+const FONT: &[(char, [u8; 7])] = &[
+    (' ', [0, 0, 0, 0, 0, 0, 0]),
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('-', [0, 0, 0, 0b11111, 0, 0, 0]),
+    ('.', [0, 0, 0, 0, 0, 0b01100, 0b01100]),
+    (',', [0, 0, 0, 0, 0b01100, 0b00100, 0b01000]),
+    (':', [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0]),
+    ('!', [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0, 0b00100]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100]),
+    ('\'', [0b01100, 0b00100, 0b01000, 0, 0, 0, 0]),
+    ('&', [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101]),
+    ('/', [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+];
+
+// Characters the font lacks show as a small block
+const UNKNOWN_GLYPH: [u8; 7] = [0, 0, 0b01110, 0b01110, 0b01110, 0, 0];
+
+fn glyph(c: char) -> [u8; 7] {
+    let c = c.to_ascii_uppercase();
+    FONT.iter()
+        .find(|(known, _)| *known == c)
+        .map(|(_, rows)| *rows)
+        .unwrap_or(UNKNOWN_GLYPH)
+}
+
+// What the ticker announces: the station and, once known, its song
+fn ticker_text(state: &AudioState) -> String {
+    let info = state.stream_info.as_ref().filter(|_| state.is_playing);
+    let Some(info) = info.filter(|info| !info.station_name.trim().is_empty()) else {
+        return IDLE_TEXT.to_string();
+    };
+    match info.current_song.as_deref().map(str::trim) {
+        Some(song) if !song.is_empty() => format!("{} - {}", info.station_name.trim(), song),
+        _ => info.station_name.trim().to_string(),
+    }
+}
+
+pub struct TextScrollerVisualization {
+    // How far the text has moved in from the right edge, and what it said;
+    // render() only gets &self, hence the cells
+    offset: Cell<f64>,
+    last_frame: Cell<u64>,
+    text: RefCell<String>,
+}
+
+impl Default for TextScrollerVisualization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextScrollerVisualization {
+    pub fn new() -> Self {
+        TextScrollerVisualization {
+            offset: Cell::new(0.0),
+            last_frame: Cell::new(0),
+            text: RefCell::new(String::new()),
+        }
+    }
+}
+
+impl Visualization for TextScrollerVisualization {
+    fn name(&self) -> &str {
+        "Text Scroller"
+    }
+
+    fn description(&self) -> &str {
+        "LED ticker scrolling the station and current song"
+    }
+
+    fn render(&self, ctx: &mut Context, state: &AudioState, aspect: f64, colors: ColorMode) {
+        // Background - near black, like an unlit display
+        ctx.draw(&Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+            color: colors.adapt(Color::Rgb(15, 5, 5)),
+        });
+
+        // LEDs are square on screen, so they're narrower in canvas units on wide canvases
+        let led_width = LED_SIZE / aspect.max(0.1);
+        let text = ticker_text(state);
+        let text_width = text.chars().count() as f64 * 6.0 * led_width;
+
+        // Scroll by however many frames passed since the last draw, restarting
+        // from the right edge whenever the text changes
+        let elapsed = state.frame_count.saturating_sub(self.last_frame.get()) as f64;
+        self.last_frame.set(state.frame_count);
+        let mut last_text = self.text.borrow_mut();
+        let mut offset = self.offset.get() + elapsed * SCROLL_SPEED * state.warp_speed;
+        if *last_text != text {
+            *last_text = text.clone();
+            offset = 0.0;
+        }
+        offset %= 100.0 + text_width;
+        self.offset.set(offset);
+
+        let intensity = 0.6 + state.bass_impact * 0.4;
+        let lit = colors.adapt(Color::Rgb(
+            (255.0 * intensity) as u8,
+            (140.0 * intensity) as u8,
+            20,
+        ));
+        let top = 50.0 + 3.5 * LED_SIZE;
+        let start = 100.0 - offset;
+        for (i, c) in text.chars().enumerate() {
+            let char_x = start + i as f64 * 6.0 * led_width;
+            if char_x + 5.0 * led_width < 0.0 || char_x > 100.0 {
+                continue;
+            }
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0b10000 >> column) == 0 {
+                        continue;
+                    }
+                    let x = char_x + column as f64 * led_width;
+                    if !(0.0..100.0).contains(&x) {
+                        continue;
+                    }
+                    ctx.draw(&Rectangle {
+                        x,
+                        y: top - (row + 1) as f64 * LED_SIZE,
+                        width: led_width * 0.6,
+                        height: LED_SIZE * 0.6,
+                        color: lit,
+                    });
+                }
+            }
+        }
+    }
+}
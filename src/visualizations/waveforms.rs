@@ -1,4 +1,4 @@
-use super::Visualization;
+use super::{ColorMode, Visualization};
 use crate::audio::AudioState;
 use ratatui::style::Color;
 use ratatui::widgets::canvas::{Context, Line};
@@ -6,6 +6,84 @@ use std::f64::consts::PI;
 
 pub struct WaveFormsVisualization;
 
+// Colour families picked from the stream's codec
+#[derive(Clone, Copy)]
+enum Palette {
+    Default, // mp3 and anything unrecognised
+    Aac,
+    Xiph, // Vorbis and Opus
+    Lossless,
+}
+
+impl Palette {
+    fn for_format(format: &str) -> Self {
+        let format = format.to_ascii_lowercase();
+        if format.contains("flac") || format.contains("alac") || format.contains("pcm") {
+            Palette::Lossless
+        } else if format.contains("aac") {
+            Palette::Aac
+        } else if format.contains("vorbis") || format.contains("opus") {
+            Palette::Xiph
+        } else {
+            Palette::Default
+        }
+    }
+
+    // Colour of the main (second = false) or phase-shifted (second = true) wave
+    fn color(self, bass_impact: f64, second: bool) -> Color {
+        let intensity = 0.5 + bass_impact * 0.5;
+        let (r, g, b) = match (self, second) {
+            (Palette::Default, false) => (
+                0.2 + intensity * 0.8,
+                0.8 - intensity * 0.3,
+                0.7 + intensity * 0.3,
+            ),
+            (Palette::Default, true) => (0.7 - bass_impact * 0.2, 0.2 + bass_impact * 0.6, 0.8),
+            (Palette::Aac, false) => (0.2, 0.6 + intensity * 0.4, 0.3 + intensity * 0.2),
+            (Palette::Aac, true) => (0.6, 0.9, 0.2 + bass_impact * 0.4),
+            (Palette::Xiph, false) => (0.9 + intensity * 0.1, 0.4 + intensity * 0.3, 0.1),
+            (Palette::Xiph, true) => (0.8, 0.2 + bass_impact * 0.4, 0.3),
+            (Palette::Lossless, false) => (0.8 + intensity * 0.2, 0.8 + intensity * 0.2, 0.7),
+            (Palette::Lossless, true) => (0.9, 0.75, 0.3 + bass_impact * 0.3),
+        };
+        Color::Rgb(
+            (r.min(1.0) * 255.0) as u8,
+            (g.min(1.0) * 255.0) as u8,
+            (b.min(1.0) * 255.0) as u8,
+        )
+    }
+}
+
+// How the waveform looks for the current stream
+struct WaveStyle {
+    points: usize, // Segments across the width; more for higher bitrates
+    detail: f64,   // Scale for the high-frequency component; less for higher bitrates
+    palette: Palette,
+}
+
+impl WaveStyle {
+    fn from_state(state: &AudioState) -> Self {
+        let info = state.stream_info.as_ref();
+        let kbps = info.and_then(|info| parse_kbps(&info.bitrate));
+
+        WaveStyle {
+            points: kbps.map_or(100, |kbps| (kbps as usize).clamp(48, 200)),
+            detail: kbps.map_or(1.0, |kbps| (128.0 / kbps).clamp(0.3, 2.0)),
+            palette: info.map_or(Palette::Default, |info| Palette::for_format(&info.format)),
+        }
+    }
+}
+
+// Pull the number out of a bitrate like "128 kbps"
+fn parse_kbps(bitrate: &str) -> Option<f64> {
+    let digits: String = bitrate
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    digits.parse::<f64>().ok().filter(|kbps| *kbps > 0.0)
+}
+
 impl Default for WaveFormsVisualization {
     fn default() -> Self {
         Self::new()
@@ -24,10 +102,10 @@ impl Visualization for WaveFormsVisualization {
     }
 
     fn description(&self) -> &str {
-        "Oscilloscope-style wave form visualization"
+        "Oscilloscope-style wave form shaped by the stream's bitrate and codec"
     }
 
-    fn render(&self, ctx: &mut Context, state: &AudioState) {
+    fn render(&self, ctx: &mut Context, state: &AudioState, _aspect: f64, colors: ColorMode) {
         // Background - gradient from dark blue to black
         for y in 0..100 {
             let color_intensity = (100 - y) as f64 * 0.2;
@@ -38,7 +116,7 @@ impl Visualization for WaveFormsVisualization {
                 y1: y as f64,
                 x2: 100.0,
                 y2: y as f64,
-                color,
+                color: colors.adapt(color),
             });
         }
 
@@ -51,7 +129,7 @@ impl Visualization for WaveFormsVisualization {
                     y1: y,
                     x2: 100.0,
                     y2: y,
-                    color: Color::Rgb(30, 30, 50),
+                    color: colors.adapt(Color::Rgb(30, 30, 50)),
                 });
             }
 
@@ -63,13 +141,27 @@ impl Visualization for WaveFormsVisualization {
                     y1: 0.0,
                     x2: x,
                     y2: 100.0,
-                    color: Color::Rgb(30, 30, 50),
+                    color: colors.adapt(Color::Rgb(30, 30, 50)),
+                });
+            }
+
+            let style = WaveStyle::from_state(state);
+
+            // Muted streams flat-line
+            if state.is_muted {
+                ctx.draw(&Line {
+                    x1: 0.0,
+                    y1: 50.0,
+                    x2: 100.0,
+                    y2: 50.0,
+                    color: colors.adapt(style.palette.color(0.0, false)),
                 });
+                return;
             }
 
             // Generate sine wave visualization based on frame count and bass impact
             let t = state.frame_count as f64 * 0.02;
-            let num_points = 100;
+            let num_points = style.points;
             let mut prev_x = 0.0;
             let mut prev_y = 50.0;
 
@@ -84,7 +176,7 @@ impl Visualization for WaveFormsVisualization {
                 // Combine different frequencies with varying amplitudes
                 let amp1 = 15.0 + state.bass_impact * 10.0; // Main amplitude
                 let amp2 = 5.0 * state.bass_impact; // Second amplitude affected strongly by bass
-                let amp3 = 3.0; // Small high-frequency component
+                let amp3 = 3.0 * style.detail; // Small high-frequency component
 
                 // Calculate the waveform value
                 let phase = x / 100.0 * 2.0 * PI + t;
@@ -97,13 +189,8 @@ impl Visualization for WaveFormsVisualization {
 
                 // Only draw lines inside the canvas boundaries
                 if (0.0..=100.0).contains(&y) && (0.0..=100.0).contains(&prev_y) {
-                    // Determine color based on bass impact and position
-                    let intensity = 0.5 + state.bass_impact * 0.5;
-                    let color = Color::Rgb(
-                        ((0.2 + intensity * 0.8) * 255.0) as u8,
-                        ((0.8 - intensity * 0.3) * 255.0) as u8,
-                        ((0.7 + intensity * 0.3) * 255.0) as u8,
-                    );
+                    // Determine color based on codec and bass impact
+                    let color = style.palette.color(state.bass_impact, false);
 
                     // Draw line segment
                     ctx.draw(&Line {
@@ -111,7 +198,7 @@ impl Visualization for WaveFormsVisualization {
                         y1: prev_y,
                         x2: x,
                         y2: y,
-                        color,
+                        color: colors.adapt(color),
                     });
                 }
 
@@ -133,7 +220,7 @@ impl Visualization for WaveFormsVisualization {
 
                 let amp1 = 10.0;
                 let amp2 = 7.0 * state.bass_impact;
-                let amp3 = 2.0;
+                let amp3 = 2.0 * style.detail;
 
                 let phase = x / 100.0 * 2.0 * PI + t + PI / 2.0; // Phase shifted
                 let wave = amp1 * (phase * freq1).cos()
@@ -144,18 +231,14 @@ impl Visualization for WaveFormsVisualization {
 
                 if (0.0..=100.0).contains(&y) && (0.0..=100.0).contains(&prev_y) {
                     // Different color for second wave
-                    let color = Color::Rgb(
-                        ((0.7 - state.bass_impact * 0.2) * 255.0) as u8,
-                        ((0.2 + state.bass_impact * 0.6) * 255.0) as u8,
-                        ((0.8) * 255.0) as u8,
-                    );
+                    let color = style.palette.color(state.bass_impact, true);
 
                     ctx.draw(&Line {
                         x1: prev_x,
                         y1: prev_y,
                         x2: x,
                         y2: y,
-                        color,
+                        color: colors.adapt(color),
                     });
                 }
 
@@ -170,7 +253,7 @@ impl Visualization for WaveFormsVisualization {
                 y1: 50.0,
                 x2: 100.0,
                 y2: 50.0,
-                color: Color::Rgb(50, 50, 80),
+                color: colors.adapt(Color::Rgb(50, 50, 80)),
             });
 
             // Small pulses
@@ -182,7 +265,7 @@ impl Visualization for WaveFormsVisualization {
                     y1: 50.0,
                     x2: x,
                     y2: 45.0,
-                    color: Color::Rgb(60, 60, 100),
+                    color: colors.adapt(Color::Rgb(60, 60, 100)),
                 });
 
                 ctx.draw(&Line {
@@ -190,7 +273,7 @@ impl Visualization for WaveFormsVisualization {
                     y1: 45.0,
                     x2: x + 5.0,
                     y2: 50.0,
-                    color: Color::Rgb(60, 60, 100),
+                    color: colors.adapt(Color::Rgb(60, 60, 100)),
                 });
             }
         }
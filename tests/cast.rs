@@ -0,0 +1,214 @@
+#![cfg(feature = "cast")]
+
+use radio_cli::cast::chromecast::{
+    content_type, decode_message, encode_message, media_receiver_session, parse_mdns_response,
+};
+use radio_cli::cast::dlna::{parse_device_description, soap_envelope, ssdp_header};
+use radio_cli::cast::{CastDevice, CastProtocol};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <friendlyName>Living Room &amp; Kitchen</friendlyName>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/RenderingControl/ctrl</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>/AVTransport/ctrl</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+#[test]
+fn ssdp_headers_are_case_insensitive() {
+    let response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nLocation: http://192.168.1.20:49152/desc.xml\r\n\r\n";
+    assert_eq!(
+        ssdp_header(response, "location"),
+        Some("http://192.168.1.20:49152/desc.xml")
+    );
+    assert_eq!(ssdp_header(response, "st"), None);
+}
+
+#[test]
+fn device_description_gives_name_and_transport_url() {
+    let device =
+        parse_device_description(DESCRIPTION, "http://192.168.1.20:49152/desc.xml").unwrap();
+    assert_eq!(device.name, "Living Room & Kitchen");
+    assert_eq!(
+        device.protocol,
+        CastProtocol::Dlna {
+            control_url: "http://192.168.1.20:49152/AVTransport/ctrl".to_string()
+        }
+    );
+}
+
+#[test]
+fn devices_without_av_transport_are_skipped() {
+    let description = DESCRIPTION.replace("AVTransport:1", "ConnectionManager:1");
+    assert_eq!(
+        parse_device_description(&description, "http://192.168.1.20:49152/desc.xml"),
+        None
+    );
+}
+
+#[test]
+fn soap_arguments_are_escaped() {
+    let body = soap_envelope(
+        "SetAVTransportURI",
+        &[("CurrentURI", "http://a.example/?x=1&y=2")],
+    );
+    assert!(body
+        .contains("<u:SetAVTransportURI xmlns:u=\"urn:schemas-upnp-org:service:AVTransport:1\">"));
+    assert!(body.contains("<CurrentURI>http://a.example/?x=1&amp;y=2</CurrentURI>"));
+}
+
+// A DNS name in wire format
+fn dns_name(name: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for label in name.split('.') {
+        bytes.push(label.len() as u8);
+        bytes.extend_from_slice(label.as_bytes());
+    }
+    bytes.push(0);
+    bytes
+}
+
+fn dns_record(name: &[u8], kind: u16, data: &[u8]) -> Vec<u8> {
+    let mut bytes = name.to_vec();
+    bytes.extend_from_slice(&kind.to_be_bytes());
+    bytes.extend_from_slice(&[0, 1, 0, 0, 0x11, 0x94]); // class IN, TTL
+    bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+// A Chromecast's answer: PTR to its instance, then SRV, TXT and A records.
+// The SRV and TXT records point back at the instance name to test compression.
+fn mdns_answer(with_address: bool) -> Vec<u8> {
+    let instance = dns_name("Chromecast-1a2b._googlecast._tcp.local");
+    let mut packet = vec![
+        0,
+        0,
+        0x84,
+        0,
+        0,
+        0,
+        0,
+        1,
+        0,
+        0,
+        0,
+        if with_address { 3 } else { 2 },
+    ];
+    let instance_at = packet.len() + dns_name("_googlecast._tcp.local").len() + 10;
+    packet.extend(dns_record(
+        &dns_name("_googlecast._tcp.local"),
+        12,
+        &instance,
+    ));
+    let pointer = [0xc0, instance_at as u8];
+
+    let mut srv = vec![0, 0, 0, 0, 0x1f, 0x49]; // priority, weight, port 8009
+    srv.extend(dns_name("1a2b.local"));
+    packet.extend(dns_record(&pointer, 33, &srv));
+
+    let mut txt = vec![7];
+    txt.extend_from_slice(b"md=Nest");
+    txt.push(14);
+    txt.extend_from_slice(b"fn=Living Room");
+    packet.extend(dns_record(&pointer, 16, &txt));
+
+    if with_address {
+        packet.extend(dns_record(&dns_name("1a2b.local"), 1, &[192, 168, 1, 30]));
+    }
+    packet
+}
+
+#[test]
+fn mdns_answers_give_chromecast_name_and_address() {
+    let sender = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 99));
+    let devices = parse_mdns_response(&mdns_answer(true), sender);
+    assert_eq!(
+        devices,
+        vec![CastDevice {
+            name: "Living Room".to_string(),
+            protocol: CastProtocol::Chromecast {
+                address: SocketAddr::from(([192, 168, 1, 30], 8009))
+            },
+        }]
+    );
+    assert_eq!(devices[0].label(), "Living Room (Chromecast)");
+}
+
+#[test]
+fn mdns_answers_without_an_address_use_the_sender() {
+    let sender = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 99));
+    let devices = parse_mdns_response(&mdns_answer(false), sender);
+    assert_eq!(
+        devices[0].protocol,
+        CastProtocol::Chromecast {
+            address: SocketAddr::from(([192, 168, 1, 99], 8009))
+        }
+    );
+}
+
+#[test]
+fn truncated_mdns_answers_are_ignored() {
+    let answer = mdns_answer(true);
+    let sender = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 99));
+    assert!(parse_mdns_response(&answer[..20], sender).is_empty());
+}
+
+#[test]
+fn cast_messages_round_trip() {
+    let frame = encode_message(
+        "sender-0",
+        "receiver-0",
+        "urn:x-cast:com.google.cast.receiver",
+        r#"{"type":"GET_STATUS","requestId":1}"#,
+    );
+    let len = u32::from_be_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+    assert_eq!(len, frame.len() - 4);
+
+    let message = decode_message(&frame[4..]).unwrap();
+    assert_eq!(message.source, "sender-0");
+    assert_eq!(message.namespace, "urn:x-cast:com.google.cast.receiver");
+    assert_eq!(message.payload, r#"{"type":"GET_STATUS","requestId":1}"#);
+}
+
+#[test]
+fn running_media_receiver_is_found_in_status() {
+    let status = serde_json::json!({
+        "type": "RECEIVER_STATUS",
+        "status": { "applications": [
+            { "appId": "E8C28D3C", "sessionId": "backdrop", "transportId": "backdrop" },
+            { "appId": "CC1AD845", "sessionId": "abc", "transportId": "web-5" },
+        ]},
+    });
+    assert_eq!(
+        media_receiver_session(&status),
+        Some(("abc".to_string(), "web-5".to_string()))
+    );
+    assert_eq!(
+        media_receiver_session(&serde_json::json!({ "status": {} })),
+        None
+    );
+}
+
+#[test]
+fn content_type_follows_the_extension() {
+    assert_eq!(
+        content_type("http://a.example/live.aac?token=1"),
+        "audio/aac"
+    );
+    assert_eq!(
+        content_type("http://a.example/hls/master.m3u8"),
+        "application/x-mpegURL"
+    );
+    assert_eq!(content_type("http://a.example/stream"), "audio/mpeg");
+}
@@ -0,0 +1,32 @@
+use radio_cli::visualizations::{nearest_ansi16, ColorMode};
+use ratatui::style::Color;
+
+#[test]
+fn colorterm_decides_the_default() {
+    assert_eq!(
+        ColorMode::from_colorterm(Some("truecolor")),
+        ColorMode::TrueColor
+    );
+    assert_eq!(
+        ColorMode::from_colorterm(Some("24bit")),
+        ColorMode::TrueColor
+    );
+    assert_eq!(ColorMode::from_colorterm(Some("")), ColorMode::Ansi16);
+    assert_eq!(ColorMode::from_colorterm(None), ColorMode::Ansi16);
+}
+
+#[test]
+fn rgb_maps_to_the_nearest_ansi_color() {
+    assert_eq!(nearest_ansi16(0, 0, 20), Color::Black);
+    assert_eq!(nearest_ansi16(250, 250, 250), Color::White);
+    assert_eq!(nearest_ansi16(200, 10, 10), Color::Red);
+    assert_eq!(nearest_ansi16(100, 180, 255), Color::LightBlue);
+}
+
+#[test]
+fn only_ansi16_mode_changes_colors() {
+    let rgb = Color::Rgb(50, 240, 50);
+    assert_eq!(ColorMode::TrueColor.adapt(rgb), rgb);
+    assert_eq!(ColorMode::Ansi16.adapt(rgb), Color::LightGreen);
+    assert_eq!(ColorMode::Ansi16.adapt(Color::Cyan), Color::Cyan);
+}
@@ -0,0 +1,58 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use radio_cli::app::{palette_matches, Action};
+
+#[test]
+fn empty_query_lists_every_palette_action() {
+    assert_eq!(palette_matches(""), Action::PALETTE.to_vec());
+}
+
+#[test]
+fn fuzzy_query_matches_subsequences_case_insensitively() {
+    let matches = palette_matches("rmdup");
+    assert_eq!(matches.first(), Some(&Action::RemoveDuplicates));
+    assert!(palette_matches("EXPORT").contains(&Action::ExportPlaylist));
+    assert!(palette_matches("zzz").is_empty());
+}
+
+#[test]
+fn tighter_matches_rank_first() {
+    assert_eq!(palette_matches("stop").first(), Some(&Action::Stop));
+}
+
+#[test]
+fn keys_map_to_the_same_actions_as_the_palette() {
+    let key = |code, modifiers| KeyEvent::new(code, modifiers);
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char(':'), KeyModifiers::NONE)),
+        Some(Action::CommandPalette)
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('p'), KeyModifiers::CONTROL)),
+        Some(Action::CommandPalette)
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('/'), KeyModifiers::ALT)),
+        Some(Action::LastSearch)
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('7'), KeyModifiers::NONE)),
+        Some(Action::SetVolume(70))
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('f'), KeyModifiers::ALT)),
+        Some(Action::FilterByLetter('F'))
+    );
+    // Folded the same way as station names, so it finds "Été FM"
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('é'), KeyModifiers::ALT)),
+        Some(Action::FilterByLetter('É'))
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('Z'), KeyModifiers::NONE)),
+        Some(Action::ToggleVisFreeze)
+    );
+    assert_eq!(
+        Action::from_normal_key(key(KeyCode::Char('z'), KeyModifiers::NONE)),
+        None
+    );
+}
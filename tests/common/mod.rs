@@ -0,0 +1,9 @@
+use radio_cli::db;
+use rusqlite::Connection;
+
+// A fresh in-memory library, seeded like a first run
+pub fn test_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    db::init_db(&conn).unwrap();
+    conn
+}
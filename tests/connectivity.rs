@@ -0,0 +1,30 @@
+use radio_cli::audio::Connectivity;
+
+#[test]
+fn advancing_playback_is_streaming() {
+    assert_eq!(
+        Connectivity::from_mpv(Some(false), Some(false)),
+        Connectivity::Streaming
+    );
+    assert_eq!(
+        Connectivity::from_mpv(Some(false), None),
+        Connectivity::Streaming
+    );
+}
+
+#[test]
+fn cache_pause_is_buffering() {
+    assert_eq!(
+        Connectivity::from_mpv(Some(true), Some(true)),
+        Connectivity::Buffering
+    );
+}
+
+#[test]
+fn idle_core_or_no_answer_is_stalled() {
+    assert_eq!(
+        Connectivity::from_mpv(Some(true), Some(false)),
+        Connectivity::Stalled
+    );
+    assert_eq!(Connectivity::from_mpv(None, None), Connectivity::Stalled);
+}
@@ -0,0 +1,657 @@
+mod common;
+
+use common::test_db;
+use radio_cli::db;
+use rusqlite::Connection;
+
+#[test]
+fn station_lifecycle() {
+    let conn = test_db();
+    let seeded = db::load_stations(&conn).unwrap().len();
+
+    // Add
+    let id = db::add_station(&conn, "Test FM", "http://test.fm/live", Some("Testing")).unwrap();
+    let stations = db::load_stations(&conn).unwrap();
+    assert_eq!(stations.len(), seeded + 1);
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert_eq!(station.name, "Test FM");
+    assert_eq!(station.url, "http://test.fm/live");
+    assert_eq!(station.description.as_deref(), Some("Testing"));
+    assert!(!station.favorite);
+
+    // Update
+    db::update_station(&conn, id, "Test FM 2", "http://test.fm/hq", None, None).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert_eq!(station.name, "Test FM 2");
+    assert_eq!(station.url, "http://test.fm/hq");
+    assert_eq!(station.description, None);
+
+    // Favorite toggles on and off
+    db::toggle_favorite(&conn, id, true).unwrap();
+    assert!(db::get_station_by_id(&conn, id).unwrap().unwrap().favorite);
+    db::toggle_favorite(&conn, id, false).unwrap();
+    assert!(!db::get_station_by_id(&conn, id).unwrap().unwrap().favorite);
+
+    // Stats accumulate
+    assert!(db::get_station_stats(&conn, id).unwrap().is_none());
+    db::update_station_stats(&conn, id, 10).unwrap();
+    db::update_station_stats(&conn, id, 25).unwrap();
+    let stats = db::get_station_stats(&conn, id).unwrap().unwrap();
+    assert_eq!(stats.total_play_time, 35);
+    assert!(stats.last_played.unwrap_or(0) > 0);
+
+    // Delete removes both the station and its stats
+    db::delete_station(&conn, id).unwrap();
+    assert!(db::get_station_by_id(&conn, id).unwrap().is_none());
+    assert_eq!(db::load_stations(&conn).unwrap().len(), seeded);
+    assert!(db::get_station_stats(&conn, id).unwrap().is_none());
+}
+
+#[test]
+fn deleting_one_station_leaves_others_stats_alone() {
+    let conn = test_db();
+    let keep = db::add_station(&conn, "Keep", "http://keep.example", None).unwrap();
+    let drop = db::add_station(&conn, "Drop", "http://drop.example", None).unwrap();
+    db::update_station_stats(&conn, keep, 10).unwrap();
+    db::update_station_stats(&conn, drop, 10).unwrap();
+
+    db::delete_station(&conn, drop).unwrap();
+
+    assert_eq!(
+        db::get_station_stats(&conn, keep)
+            .unwrap()
+            .unwrap()
+            .total_play_time,
+        10
+    );
+}
+
+#[test]
+fn loading_keeps_intentional_duplicates() {
+    let conn = test_db();
+    let first = db::add_station(&conn, "News", "http://same.example", None).unwrap();
+    let second = db::add_station(&conn, "News (kitchen)", "http://same.example", None).unwrap();
+    // Pinning lists it first, but it's still the newer one
+    db::set_pinned(&conn, second, true).unwrap();
+
+    assert!(db::get_station_by_id(&conn, first).unwrap().is_some());
+    assert!(db::get_station_by_id(&conn, second).unwrap().is_some());
+
+    let duplicates = db::remove_duplicate_urls_dry_run(&conn).unwrap();
+    assert_eq!(
+        duplicates,
+        vec![("http://same.example".to_string(), vec![second])]
+    );
+    assert_eq!(db::remove_duplicate_urls(&conn).unwrap(), 1);
+    assert!(db::get_station_by_id(&conn, second).unwrap().is_none());
+}
+
+#[test]
+fn duplicate_names_are_grouped_but_not_removed() {
+    let conn = test_db();
+    let first = db::add_station(&conn, "Jazz FM", "http://jazz.example/128", None).unwrap();
+    let second = db::add_station(&conn, "jazz  fm ", "http://jazz.example/320", None).unwrap();
+    db::set_pinned(&conn, second, true).unwrap();
+    db::add_station(
+        &conn,
+        "Jazz FM Classics",
+        "http://jazz.example/classics",
+        None,
+    )
+    .unwrap();
+
+    let groups = db::find_duplicate_names(&conn).unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].keep.id, first);
+    let extras: Vec<i32> = groups[0].extras.iter().map(|s| s.id).collect();
+    assert_eq!(extras, vec![second]);
+    assert!(db::get_station_by_id(&conn, second).unwrap().is_some());
+
+    assert_eq!(db::DedupeBy::parse("Name"), Some(db::DedupeBy::Name));
+    assert_eq!(db::DedupeBy::parse("title"), None);
+    assert_eq!(db::DedupeBy::default(), db::DedupeBy::Url);
+}
+
+#[test]
+fn stats_export_as_csv() {
+    let conn = test_db();
+    let played = db::add_station(&conn, "Talk, Mostly", "http://talk.example", None).unwrap();
+    db::add_station(&conn, "Silent", "http://silent.example", None).unwrap();
+    db::update_station_stats(&conn, played, 90).unwrap();
+
+    let csv = db::stats_csv(&conn, false).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], db::STATS_CSV_HEADER);
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("\"Talk, Mostly\",http://talk.example,90,"));
+    assert!(lines[1].ends_with('Z'));
+
+    let csv = db::stats_csv(&conn, true).unwrap();
+    assert!(csv
+        .lines()
+        .any(|line| line == "Silent,http://silent.example,,"));
+    assert!(csv.lines().nth(1).unwrap().starts_with("\"Talk, Mostly\""));
+}
+
+#[test]
+fn pinned_stations_are_listed_first() {
+    let conn = test_db();
+    let first = db::load_stations(&conn).unwrap()[0].id;
+    let main = db::add_station(&conn, "My Main Station", "http://main.example", None).unwrap();
+    db::set_pinned(&conn, main, true).unwrap();
+
+    let stations = db::load_stations(&conn).unwrap();
+    assert_eq!(stations[0].id, main);
+    assert!(stations[0].pinned);
+    assert!(!stations[0].favorite);
+    assert_eq!(stations[1].id, first);
+
+    db::set_pinned(&conn, main, false).unwrap();
+    assert_eq!(db::load_stations(&conn).unwrap().last().unwrap().id, main);
+}
+
+#[test]
+fn lookup_by_id_and_url() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Lookup", "http://lookup.example", None).unwrap();
+    db::add_station(&conn, "Lookup copy", "http://lookup.example", None).unwrap();
+
+    assert_eq!(
+        db::get_station_by_id(&conn, id).unwrap().unwrap().name,
+        "Lookup"
+    );
+    assert!(db::get_station_by_id(&conn, -1).unwrap().is_none());
+
+    // With duplicate URLs the oldest station is returned
+    let by_url = db::get_station_by_url(&conn, "http://lookup.example")
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_url.id, id);
+    assert!(db::get_station_by_url(&conn, "http://missing.example")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn favorite_played_stations_uses_the_threshold() {
+    let conn = test_db();
+    let long = db::add_station(&conn, "Long", "http://long.example", None).unwrap();
+    let short = db::add_station(&conn, "Short", "http://short.example", None).unwrap();
+    db::update_station_stats(&conn, long, 600).unwrap();
+    db::update_station_stats(&conn, short, 30).unwrap();
+
+    assert_eq!(db::favorite_played_stations(&conn, 300).unwrap(), 1);
+    assert!(
+        db::get_station_by_id(&conn, long)
+            .unwrap()
+            .unwrap()
+            .favorite
+    );
+    assert!(
+        !db::get_station_by_id(&conn, short)
+            .unwrap()
+            .unwrap()
+            .favorite
+    );
+
+    // Already-favorite stations aren't counted again
+    assert_eq!(db::favorite_played_stations(&conn, 300).unwrap(), 0);
+}
+
+#[test]
+fn top_stations_since_only_counts_recent_plays() {
+    let conn = test_db();
+    let old = db::add_station(&conn, "Old Habit", "http://old.example", None).unwrap();
+    let new = db::add_station(&conn, "New Habit", "http://new.example", None).unwrap();
+
+    let old_entry =
+        db::add_history_entry(&conn, old, chrono::Utc::now().timestamp(), None).unwrap();
+    db::add_history_play_time(&conn, old_entry, 5000).unwrap();
+    conn.execute(
+        "UPDATE play_history SET started_at = started_at - 60 * 86400 WHERE id = ?1",
+        [old_entry],
+    )
+    .unwrap();
+    let new_entry =
+        db::add_history_entry(&conn, new, chrono::Utc::now().timestamp(), None).unwrap();
+    db::add_history_play_time(&conn, new_entry, 20).unwrap();
+    db::add_history_play_time(&conn, new_entry, 10).unwrap();
+
+    let week_ago = chrono::Utc::now().timestamp() - 7 * 86400;
+    let top = db::get_top_stations_since(&conn, week_ago, 5).unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].0.id, new);
+    assert_eq!(top[0].1, 30);
+}
+
+#[test]
+fn bool_settings_accept_common_spellings() {
+    let conn = test_db();
+    assert!(!db::get_bool_setting(&conn, "auto_advance").unwrap());
+    for value in ["on", "TRUE", "1", " yes "] {
+        db::set_setting(&conn, "auto_advance", value).unwrap();
+        assert!(
+            db::get_bool_setting(&conn, "auto_advance").unwrap(),
+            "{}",
+            value
+        );
+    }
+    db::set_setting(&conn, "auto_advance", "off").unwrap();
+    assert!(!db::get_bool_setting(&conn, "auto_advance").unwrap());
+}
+
+#[test]
+fn hidden_directory_stations_persist_until_unhidden() {
+    let conn = test_db();
+    assert!(db::load_hidden_urls(&conn).unwrap().is_empty());
+
+    db::hide_station(&conn, "http://junk.example/stream", "Junk").unwrap();
+    db::hide_station(&conn, "http://junk.example/stream", "Junk again").unwrap();
+    db::hide_station(&conn, "http://spam.example/stream", "Spam").unwrap();
+    let hidden = db::load_hidden_urls(&conn).unwrap();
+    assert_eq!(hidden.len(), 2);
+    assert!(hidden.contains("http://junk.example/stream"));
+
+    assert_eq!(db::unhide_all_stations(&conn).unwrap(), 2);
+    assert!(db::load_hidden_urls(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn read_only_writes_are_recognised() {
+    let path = std::env::temp_dir().join(format!("radio_cli_ro_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    db::init_db(&Connection::open(&path).unwrap()).unwrap();
+
+    let conn =
+        Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+    let err = db::add_station(&conn, "Test FM", "http://test.fm/live", None).unwrap_err();
+    assert!(db::write_failure_message(err.as_ref())
+        .unwrap()
+        .starts_with("Database is read-only"));
+
+    // Errors that aren't about writing don't trigger the banner
+    let other: Box<dyn std::error::Error> = "no such station".into();
+    assert_eq!(db::write_failure_message(other.as_ref()), None);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn import_conflict_policies() {
+    let doc = serde_json::json!({
+        "stations": [{
+            "name": "Renamed FM",
+            "url": "http://test.fm/live",
+            "description": "Imported",
+            "favorite": true,
+            "total_play_time": 60,
+        }]
+    });
+
+    for policy in [
+        db::ImportConflict::Skip,
+        db::ImportConflict::Overwrite,
+        db::ImportConflict::Keep,
+    ] {
+        let conn = test_db();
+        let id = db::add_station(&conn, "Test FM", "http://test.fm/live", Some("Local")).unwrap();
+        db::update_station_stats(&conn, id, 30).unwrap();
+        let before = db::load_stations(&conn).unwrap().len();
+        let preview = db::preview_json_import(&conn, &doc).unwrap();
+        assert_eq!((preview.new, preview.existing), (0, 1));
+
+        db::import_json(&conn, &doc, policy, |_, _| {}).unwrap();
+        let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+        let stations = db::load_stations(&conn).unwrap();
+
+        match policy {
+            db::ImportConflict::Skip => {
+                assert_eq!(station.name, "Test FM");
+                assert_eq!(stations.len(), before);
+            }
+            db::ImportConflict::Overwrite => {
+                assert_eq!(station.name, "Renamed FM");
+                assert_eq!(station.description.as_deref(), Some("Imported"));
+                assert_eq!(stations.len(), before);
+            }
+            db::ImportConflict::Keep => {
+                assert_eq!(station.name, "Test FM");
+                assert_eq!(stations.len(), before + 1);
+            }
+        }
+
+        // Stats survive on the saved row; only keep-both leaves them unmerged
+        let stats = db::get_station_stats(&conn, id).unwrap().unwrap();
+        let expected = if policy == db::ImportConflict::Keep {
+            30
+        } else {
+            60
+        };
+        assert_eq!(stats.total_play_time, expected);
+
+        // Importing the same file again doesn't count its play time twice
+        if policy != db::ImportConflict::Keep {
+            db::import_json(&conn, &doc, policy, |_, _| {}).unwrap();
+            let stats = db::get_station_stats(&conn, id).unwrap().unwrap();
+            assert_eq!(stats.total_play_time, 60);
+        }
+    }
+}
+
+#[test]
+fn stream_credentials_round_trip() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Private FM", "http://private.fm/live", None).unwrap();
+    assert!(db::get_station_credentials(&conn, id).unwrap().is_none());
+
+    let login = db::Credentials {
+        username: "listener".to_string(),
+        password: "s3cret".to_string(),
+    };
+    db::set_station_credentials(&conn, id, Some(&login)).unwrap();
+    assert!(db::get_station_credentials(&conn, id).unwrap() == Some(login));
+
+    db::set_station_credentials(&conn, id, None).unwrap();
+    assert!(db::get_station_credentials(&conn, id).unwrap().is_none());
+}
+
+#[test]
+fn bool_settings_fall_back_to_their_default() {
+    let conn = test_db();
+    assert!(db::get_bool_setting_or(&conn, "preflight_check", true).unwrap());
+    db::set_setting(&conn, "preflight_check", "off").unwrap();
+    assert!(!db::get_bool_setting_or(&conn, "preflight_check", true).unwrap());
+    db::set_setting(&conn, "preflight_check", "maybe").unwrap();
+    assert!(db::get_bool_setting_or(&conn, "preflight_check", true).unwrap());
+    assert!(!db::get_bool_setting(&conn, "preflight_check").unwrap());
+}
+
+#[test]
+fn mirrors_travel_through_json_export() {
+    let source = test_db();
+    let id = db::add_station(&source, "Jazz", "http://main.example/jazz", None).unwrap();
+    let mirrors = vec!["http://backup.example/jazz".to_string()];
+    db::set_station_mirrors(&source, id, &mirrors).unwrap();
+    let doc = db::export_json(&source).unwrap();
+
+    let conn = test_db();
+    db::import_json(&conn, &doc, db::ImportConflict::Skip, |_, _| {}).unwrap();
+    let imported = db::load_stations(&conn)
+        .unwrap()
+        .into_iter()
+        .find(|s| s.url == "http://main.example/jazz")
+        .unwrap()
+        .id;
+    assert_eq!(db::get_station_mirrors(&conn, imported).unwrap(), mirrors);
+}
+
+#[test]
+fn json_import_reports_progress() {
+    let conn = test_db();
+    let doc = serde_json::json!({
+        "stations": [
+            {"name": "One", "url": "http://one.example/live"},
+            {"name": "Broken"},
+            {"name": "Two", "url": "http://two.example/live"},
+        ]
+    });
+
+    let mut calls = Vec::new();
+    let summary = db::import_json(&conn, &doc, db::ImportConflict::Skip, |done, total| {
+        calls.push((done, total))
+    })
+    .unwrap();
+    assert_eq!(summary.added, 2);
+    assert_eq!(calls, vec![(0, 3), (1, 3), (2, 3), (3, 3)]);
+}
+
+#[test]
+fn import_preview_matches_normalized_urls() {
+    assert_eq!(
+        db::normalize_url(" HTTP://Radio.Example:80/live/#top "),
+        "http://radio.example/live"
+    );
+    assert_eq!(
+        db::normalize_url("https://radio.example/live?bitrate=128"),
+        "https://radio.example/live?bitrate=128"
+    );
+
+    let conn = test_db();
+    db::add_station(&conn, "Saved", "http://radio.example/live", None).unwrap();
+    let preview = db::preview_import(
+        &conn,
+        [
+            "HTTP://radio.example/live/",
+            "http://new.example/a",
+            "http://new.example/a",
+            "http://new.example/b",
+        ],
+    )
+    .unwrap();
+    assert_eq!((preview.new, preview.existing), (2, 2));
+    assert_eq!(
+        preview.summary(),
+        "Found 4 stations: 2 new, 2 already in library"
+    );
+}
+
+#[test]
+fn history_entries_can_start_in_the_past() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Late", "http://late.example", None).unwrap();
+    let started_at = chrono::Utc::now().timestamp() - 45;
+    db::add_history_entry(&conn, id, started_at, None).unwrap();
+
+    let history = db::get_recent_history(&conn, 1).unwrap();
+    assert_eq!(history[0].station_id, id);
+    assert_eq!(history[0].started_at, started_at);
+}
+
+#[test]
+fn station_history_only_covers_that_station() {
+    let conn = test_db();
+    let jazz = db::add_station(&conn, "Jazz", "http://jazz.example", None).unwrap();
+    let rock = db::add_station(&conn, "Rock", "http://rock.example", None).unwrap();
+    let now = chrono::Utc::now().timestamp();
+    db::add_history_entry(&conn, jazz, now - 60, Some("Old Tune")).unwrap();
+    db::add_history_entry(&conn, rock, now - 30, Some("Loud Song")).unwrap();
+    db::add_history_entry(&conn, jazz, now, Some("New Tune")).unwrap();
+
+    let history = db::get_station_history(&conn, jazz, 10).unwrap();
+    let songs: Vec<_> = history.iter().map(|e| e.song.as_deref()).collect();
+    assert_eq!(songs, vec![Some("New Tune"), Some("Old Tune")]);
+    assert_eq!(db::count_station_plays(&conn, jazz).unwrap(), 2);
+    assert_eq!(db::count_station_plays(&conn, rock).unwrap(), 1);
+}
+
+#[test]
+fn listening_is_totalled_per_day() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Daily", "http://daily.example", None).unwrap();
+    let day = 86400;
+    let now = 100 * day + 3600; // 01:00 UTC on day 100
+    for (started_at, play_time) in [
+        (now - 600, 300),       // Today
+        (now - 7200, 60),       // 23:00 yesterday
+        (now - 2 * day, 900),   // Two days ago
+        (now - 30 * day, 5000), // Outside the window
+    ] {
+        conn.execute(
+            "INSERT INTO play_history (station_id, started_at, play_time) VALUES (?1, ?2, ?3)",
+            rusqlite::params![id, started_at, play_time],
+        )
+        .unwrap();
+    }
+
+    let days = db::listening_by_day(&conn, now, 0, 7).unwrap();
+    assert_eq!(days, vec![0, 0, 0, 0, 900, 60, 300]);
+
+    // Two hours ahead of UTC, 23:00 yesterday was already today
+    let days = db::listening_by_day(&conn, now, 2 * 3600, 7).unwrap();
+    assert_eq!(days, vec![0, 0, 0, 0, 900, 0, 360]);
+}
+
+#[test]
+fn mirrors_are_tried_after_the_last_working_url() {
+    let mirrors = db::parse_mirror_urls("http://b.example, http://c.example\nhttp://b.example");
+    assert_eq!(mirrors, vec!["http://b.example", "http://c.example"]);
+
+    assert_eq!(
+        db::stream_url_order("http://a.example", &mirrors, None),
+        vec!["http://a.example", "http://b.example", "http://c.example"]
+    );
+    assert_eq!(
+        db::stream_url_order("http://a.example", &mirrors, Some("http://c.example")),
+        vec!["http://c.example", "http://a.example", "http://b.example"]
+    );
+    // A working URL that's no longer listed is ignored
+    assert_eq!(
+        db::stream_url_order("http://a.example", &[], Some("http://gone.example")),
+        vec!["http://a.example"]
+    );
+}
+
+#[test]
+fn changing_mirrors_forgets_the_working_url() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Flaky", "http://a.example", None).unwrap();
+    let mirrors = vec!["http://b.example".to_string()];
+    db::set_station_mirrors(&conn, id, &mirrors).unwrap();
+    db::set_working_url(&conn, id, Some("http://b.example")).unwrap();
+    assert_eq!(db::get_station_mirrors(&conn, id).unwrap(), mirrors);
+
+    // Saving the same list keeps it
+    db::set_station_mirrors(&conn, id, &mirrors).unwrap();
+    assert_eq!(
+        db::get_working_url(&conn, id).unwrap().as_deref(),
+        Some("http://b.example")
+    );
+
+    db::set_station_mirrors(&conn, id, &[]).unwrap();
+    assert!(db::get_station_mirrors(&conn, id).unwrap().is_empty());
+    assert_eq!(db::get_working_url(&conn, id).unwrap(), None);
+}
+
+#[test]
+fn failed_checks_are_remembered() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Dead", "http://dead.example", None).unwrap();
+    assert!(!db::get_station_by_id(&conn, id)
+        .unwrap()
+        .unwrap()
+        .check_failed());
+
+    db::record_check(&conn, id, Err("HTTP 404 Not Found"), 1_700_000_000).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert!(station.check_failed());
+    assert_eq!(
+        station.last_check_status.as_deref(),
+        Some("HTTP 404 Not Found")
+    );
+    assert_eq!(station.last_check_time, Some(1_700_000_000));
+
+    db::record_check(&conn, id, Ok(()), 1_700_000_100).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert!(!station.check_failed());
+    assert_eq!(station.last_check_status.as_deref(), Some(db::CHECK_OK));
+}
+
+#[test]
+fn urls_are_rewritten_by_substring() {
+    let conn = test_db();
+    let jazz = db::add_station(&conn, "Jazz", "http://old.example/jazz", None).unwrap();
+    let rock = db::add_station(&conn, "Rock", "http://old.example/rock", None).unwrap();
+    let other = db::add_station(&conn, "Other", "http://elsewhere.example", None).unwrap();
+
+    let preview = db::preview_url_rewrite(&conn, "old.example", "new.example").unwrap();
+    assert_eq!(preview.len(), 2);
+    assert_eq!(preview[0].new_url, "http://new.example/jazz");
+    // Previewing changes nothing
+    assert_eq!(
+        db::get_station_by_id(&conn, jazz).unwrap().unwrap().url,
+        "http://old.example/jazz"
+    );
+
+    assert_eq!(
+        db::rewrite_urls(&conn, "old.example", "new.example").unwrap(),
+        2
+    );
+    assert_eq!(
+        db::get_station_by_id(&conn, jazz).unwrap().unwrap().url,
+        "http://new.example/jazz"
+    );
+    assert_eq!(
+        db::get_station_by_id(&conn, rock).unwrap().unwrap().url,
+        "http://new.example/rock"
+    );
+    assert_eq!(
+        db::get_station_by_id(&conn, other).unwrap().unwrap().url,
+        "http://elsewhere.example"
+    );
+
+    assert!(db::rewrite_urls(&conn, "", "x").is_err());
+}
+
+#[test]
+fn url_rewrites_reach_mirrors_and_the_working_url() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Jazz", "http://main.example/jazz", None).unwrap();
+    let mirrors = vec![
+        "http://old.example/jazz".to_string(),
+        "http://backup.example/jazz".to_string(),
+    ];
+    db::set_station_mirrors(&conn, id, &mirrors).unwrap();
+    db::set_working_url(&conn, id, Some("http://old.example/jazz")).unwrap();
+
+    let preview = db::preview_url_rewrite(&conn, "old.example", "new.example").unwrap();
+    assert_eq!(preview.len(), 1);
+    assert_eq!(
+        preview[0].changes(),
+        vec![("http://old.example/jazz", "http://new.example/jazz")]
+    );
+
+    assert_eq!(
+        db::rewrite_urls(&conn, "old.example", "new.example").unwrap(),
+        1
+    );
+    assert_eq!(
+        db::get_station_by_id(&conn, id).unwrap().unwrap().url,
+        "http://main.example/jazz"
+    );
+    assert_eq!(
+        db::get_station_mirrors(&conn, id).unwrap(),
+        vec!["http://new.example/jazz", "http://backup.example/jazz"]
+    );
+    assert_eq!(
+        db::get_working_url(&conn, id).unwrap().as_deref(),
+        Some("http://new.example/jazz")
+    );
+}
+
+#[test]
+fn stations_are_indexed_by_their_first_letter() {
+    assert_eq!(db::name_initial("jazz24"), Some('J'));
+    assert_eq!(db::name_initial("  (Groove) Salad"), Some('G'));
+    assert_eq!(db::name_initial("1LIVE"), Some('1'));
+    assert_eq!(db::name_initial("Été FM"), Some('É'));
+    assert_eq!(db::name_initial("--"), None);
+}
+
+#[test]
+fn listed_stations_have_ids_and_favorites() {
+    let conn = test_db();
+    let id = db::add_station(&conn, "Jazz", "http://jazz.example/live", None).unwrap();
+    db::toggle_favorite(&conn, id, true).unwrap();
+
+    let listed = db::stations_json(&db::load_stations(&conn).unwrap());
+    let jazz = listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|station| station["id"] == id)
+        .unwrap();
+    assert_eq!(jazz["name"], "Jazz");
+    assert_eq!(jazz["url"], "http://jazz.example/live");
+    assert_eq!(jazz["favorite"], true);
+    assert!(jazz["description"].is_null());
+}
@@ -0,0 +1,41 @@
+use radio_cli::external::build_command;
+
+#[test]
+fn url_and_name_are_substituted() {
+    assert_eq!(
+        build_command(
+            "vlc --meta-title={name} {url}",
+            "http://ice.example/live",
+            "Jazz"
+        )
+        .unwrap(),
+        vec!["vlc", "--meta-title=Jazz", "http://ice.example/live"]
+    );
+}
+
+#[test]
+fn url_is_appended_without_a_placeholder() {
+    assert_eq!(
+        build_command("catt cast", "http://ice.example/live", "Jazz").unwrap(),
+        vec!["catt", "cast", "http://ice.example/live"]
+    );
+}
+
+#[test]
+fn substituted_values_stay_single_arguments() {
+    // A name with spaces or shell characters must not split or inject
+    assert_eq!(
+        build_command(
+            "player '{name}' {url}",
+            "http://a.example/?x=1&y=2",
+            "Rock; rm -rf ~"
+        )
+        .unwrap(),
+        vec!["player", "Rock; rm -rf ~", "http://a.example/?x=1&y=2"]
+    );
+}
+
+#[test]
+fn empty_template_is_an_error() {
+    assert!(build_command("   ", "http://ice.example/live", "Jazz").is_err());
+}
@@ -0,0 +1,44 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use radio_cli::app::{self, App};
+
+#[test]
+fn parses_names_modifiers_text_and_comments() {
+    let keys = app::parse_key_script("a Tab # add a station\nCtrl+R \"hi\" Space Enter").unwrap();
+    assert_eq!(
+        keys,
+        vec![
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        ]
+    );
+}
+
+#[test]
+fn rejects_unknown_keys_and_open_quotes() {
+    assert!(app::parse_key_script("Enter Bogus").is_err());
+    assert!(app::parse_key_script("\"unterminated").is_err());
+}
+
+// Only this test touches the environment, so it can't race the others
+#[test]
+fn scripted_keys_drive_the_ui() {
+    let root = std::env::temp_dir().join(format!("radio_cli_script_{}", std::process::id()));
+    std::env::set_var(radio_cli::paths::DATA_DIR_ENV, &root);
+
+    let mut app = App::new(false).unwrap();
+    let keys = app::parse_key_script(": \"volume\"").unwrap();
+    let screen = app.run_script(&keys, 100, 30).unwrap();
+    let text = app::buffer_to_text(&screen);
+
+    assert!(text.contains("Command Palette"));
+    assert!(text.contains("Volume Up"));
+    assert_eq!(text.lines().count(), 30);
+
+    std::env::remove_var(radio_cli::paths::DATA_DIR_ENV);
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,42 @@
+use radio_cli::ui::ListStyle;
+use ratatui::style::{Color, Modifier, Style};
+
+#[test]
+fn unset_settings_keep_the_classic_look() {
+    let style = ListStyle::from_settings(None, None);
+    assert_eq!(style, ListStyle::default());
+    assert_eq!(style.symbol, ">> ");
+    assert_eq!(
+        ListStyle::from_settings(None, Some("black on yellow bold")).style,
+        style.style
+    );
+}
+
+#[test]
+fn styles_are_read_from_words() {
+    let style = ListStyle::from_settings(Some("▶ "), Some("reversed"));
+    assert_eq!(style.symbol, "▶ ");
+    assert_eq!(
+        style.style,
+        Style::default().add_modifier(Modifier::REVERSED)
+    );
+
+    let style = ListStyle::from_settings(None, Some("Cyan underlined on #303030"));
+    assert_eq!(
+        style.style,
+        Style::default()
+            .fg(Color::Cyan)
+            .bg(Color::Rgb(0x30, 0x30, 0x30))
+            .add_modifier(Modifier::UNDERLINED)
+    );
+}
+
+#[test]
+fn unreadable_styles_fall_back_to_the_default() {
+    for spec in ["blinking neon", "on", "", "bold on nothing"] {
+        assert_eq!(
+            ListStyle::from_settings(None, Some(spec)).style,
+            ListStyle::default().style
+        );
+    }
+}
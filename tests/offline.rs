@@ -0,0 +1,20 @@
+use radio_cli::offline::{is_local_url, set_offline, OFFLINE_MESSAGE};
+use radio_cli::stream_info::check_stream;
+
+#[test]
+fn only_files_are_local() {
+    assert!(is_local_url("file:///home/me/music/mix.mp3"));
+    assert!(is_local_url("FILE:///tmp/loop.ogg"));
+    assert!(is_local_url("/srv/radio/archive.m3u"));
+    assert!(!is_local_url("http://ice1.somafm.com/groovesalad-128-mp3"));
+    assert!(!is_local_url("rtmp://live.example/stream"));
+    assert!(!is_local_url(""));
+}
+
+#[test]
+fn offline_mode_refuses_requests() {
+    set_offline(true);
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let checked = rt.block_on(check_stream("http://127.0.0.1:1/live", None, None));
+    assert_eq!(checked, Err(OFFLINE_MESSAGE.to_string()));
+}
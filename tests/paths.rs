@@ -0,0 +1,23 @@
+use radio_cli::paths;
+
+// One test so the environment variable isn't raced by other tests
+#[test]
+fn data_dir_override_holds_everything() {
+    let root = std::env::temp_dir().join(format!("radio_cli_paths_{}", std::process::id()));
+    std::env::set_var(paths::DATA_DIR_ENV, &root);
+
+    assert_eq!(paths::data_dir().unwrap(), root);
+    assert!(root.is_dir());
+    assert_eq!(paths::cache_dir(), Some(root.join("cache")));
+    assert_eq!(
+        radio_cli::logging::log_path(),
+        Some(root.join("cache").join("radio_cli.log"))
+    );
+    assert_eq!(
+        radio_cli::app::get_database_path().unwrap(),
+        root.join("stations.db")
+    );
+
+    std::env::remove_var(paths::DATA_DIR_ENV);
+    let _ = std::fs::remove_dir_all(&root);
+}
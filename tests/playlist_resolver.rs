@@ -0,0 +1,33 @@
+use radio_cli::stream_info::{is_playlist_url, parse_playlist};
+
+#[test]
+fn pls_uses_the_first_file_entry() {
+    let pls = "[playlist]\nNumberOfEntries=2\nFile2=http://backup.example/stream\nTitle2=Backup\nFile1=http://main.example:8000/live\nTitle1=Main\nVersion=2\n";
+    assert_eq!(
+        parse_playlist(pls).as_deref(),
+        Some("http://main.example:8000/live")
+    );
+}
+
+#[test]
+fn m3u_uses_the_first_stream_line() {
+    let m3u = "#EXTM3U\n#EXTINF:-1,Some Radio\r\nhttps://radio.example/aac\r\nhttps://radio.example/mp3\n";
+    assert_eq!(
+        parse_playlist(m3u).as_deref(),
+        Some("https://radio.example/aac")
+    );
+}
+
+#[test]
+fn playlists_without_streams_resolve_to_nothing() {
+    assert_eq!(parse_playlist("[playlist]\nNumberOfEntries=0\n"), None);
+    assert_eq!(parse_playlist("<html><body>Not found</body></html>"), None);
+}
+
+#[test]
+fn playlist_urls_are_recognised_by_extension() {
+    assert!(is_playlist_url("http://example.com/listen.pls"));
+    assert!(is_playlist_url("http://example.com/Listen.M3U?sid=1"));
+    assert!(!is_playlist_url("http://example.com/live.m3u8"));
+    assert!(!is_playlist_url("http://example.com:8000/stream"));
+}
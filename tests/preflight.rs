@@ -0,0 +1,44 @@
+use radio_cli::stream_info::{first_reachable, is_playable_content_type, is_valid_stream_url};
+
+#[test]
+fn audio_and_unlabelled_responses_pass() {
+    assert!(is_playable_content_type(Some("audio/mpeg")));
+    assert!(is_playable_content_type(Some("Audio/AAC; charset=binary")));
+    assert!(is_playable_content_type(Some("application/ogg")));
+    assert!(is_playable_content_type(Some(
+        "application/vnd.apple.mpegurl"
+    )));
+    assert!(is_playable_content_type(None));
+}
+
+#[test]
+fn web_pages_fail() {
+    assert!(!is_playable_content_type(Some("text/html; charset=utf-8")));
+    assert!(!is_playable_content_type(Some("application/json")));
+}
+
+#[test]
+fn only_http_streams_are_checked() {
+    // Anything else goes straight to mpv
+    assert!(is_valid_stream_url("https://stream.example/live.mp3"));
+    assert!(!is_valid_stream_url("rtsp://stream.example/live"));
+    assert!(!is_valid_stream_url("mms://stream.example/live"));
+    assert!(!is_valid_stream_url("/home/me/music/set.mp3"));
+}
+
+#[test]
+fn the_first_mirror_that_answers_plays() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    // Nothing listens on port 1; the rtsp:// mirror is left to mpv
+    let candidates = vec![
+        "http://127.0.0.1:1/live".to_string(),
+        "rtsp://stream.example/live".to_string(),
+    ];
+    assert_eq!(
+        rt.block_on(first_reachable(&candidates, None, None)),
+        Ok("rtsp://stream.example/live".to_string())
+    );
+    assert!(rt
+        .block_on(first_reachable(&candidates[..1], None, None))
+        .is_err());
+}
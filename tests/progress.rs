@@ -0,0 +1,9 @@
+use radio_cli::ui::format_clock;
+
+#[test]
+fn playback_clock() {
+    assert_eq!(format_clock(0.0), "0:00");
+    assert_eq!(format_clock(65.9), "1:05");
+    assert_eq!(format_clock(3600.0 + 62.0), "1:01:02");
+    assert_eq!(format_clock(-3.0), "0:00");
+}
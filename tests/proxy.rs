@@ -0,0 +1,35 @@
+use radio_cli::proxy::{mpv_proxy_arg, no_proxy_matches, resolve_proxy};
+
+#[test]
+fn mpv_proxy_flag_includes_the_proxy_url() {
+    assert_eq!(
+        mpv_proxy_arg("http://proxy.example.com:3128"),
+        "--http-proxy=http://proxy.example.com:3128"
+    );
+}
+
+#[test]
+fn setting_overrides_environment() {
+    assert_eq!(
+        resolve_proxy(Some(" http://corp:8080 ")).as_deref(),
+        Some("http://corp:8080")
+    );
+}
+
+#[test]
+fn setting_can_disable_proxy() {
+    assert_eq!(resolve_proxy(Some("none")), None);
+    assert_eq!(resolve_proxy(Some("off")), None);
+    assert_eq!(resolve_proxy(Some("")), None);
+}
+
+#[test]
+fn no_proxy_covers_hosts_and_their_subdomains() {
+    let list = "localhost, .lan.example,radio.example";
+    assert!(no_proxy_matches(list, "http://localhost:8000/live"));
+    assert!(no_proxy_matches(list, "http://nas.lan.example/stream"));
+    assert!(no_proxy_matches(list, "https://eu.radio.example/jazz"));
+    assert!(!no_proxy_matches(list, "https://otherradio.example/jazz"));
+    assert!(no_proxy_matches("*", "http://anything.example"));
+    assert!(!no_proxy_matches("", "http://anything.example"));
+}
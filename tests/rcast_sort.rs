@@ -0,0 +1,48 @@
+use radio_cli::rcast::{RcastSort, RcastStation};
+
+fn station(name: &str, listeners: Option<u32>, bitrate: Option<&str>) -> RcastStation {
+    RcastStation {
+        name: name.to_string(),
+        url: format!("http://{}.example/stream", name.to_lowercase()),
+        description: None,
+        bitrate: bitrate.map(str::to_string),
+        genre: None,
+        listeners,
+    }
+}
+
+fn names(stations: &[RcastStation]) -> Vec<&str> {
+    stations.iter().map(|s| s.name.as_str()).collect()
+}
+
+fn sample() -> Vec<RcastStation> {
+    vec![
+        station("bravo", Some(10), Some("64 kbps")),
+        station("Alpha", None, Some("320kbps")),
+        station("Charlie", Some(250), None),
+    ]
+}
+
+#[test]
+fn sorts_by_name_ignoring_case() {
+    let mut stations = sample();
+    RcastSort::Name.apply(&mut stations);
+    assert_eq!(names(&stations), ["Alpha", "bravo", "Charlie"]);
+}
+
+#[test]
+fn sorts_by_listeners_and_bitrate_highest_first() {
+    let mut stations = sample();
+    RcastSort::Listeners.apply(&mut stations);
+    assert_eq!(names(&stations), ["Charlie", "bravo", "Alpha"]);
+
+    RcastSort::Bitrate.apply(&mut stations);
+    assert_eq!(names(&stations), ["Alpha", "bravo", "Charlie"]);
+}
+
+#[test]
+fn cycles_through_every_sort() {
+    assert_eq!(RcastSort::Name.next(), RcastSort::Listeners);
+    assert_eq!(RcastSort::Listeners.next(), RcastSort::Bitrate);
+    assert_eq!(RcastSort::Bitrate.next(), RcastSort::Name);
+}
@@ -0,0 +1,28 @@
+use radio_cli::db::format_relative_time_at;
+
+const NOW: i64 = 1_700_000_000;
+const HOUR: i64 = 3600;
+const DAY: i64 = 86400;
+
+#[test]
+fn recent_times_are_relative() {
+    assert_eq!(format_relative_time_at(NOW - 5, NOW), "just now");
+    assert_eq!(format_relative_time_at(NOW - 60, NOW), "1 minute ago");
+    assert_eq!(
+        format_relative_time_at(NOW - 45 * 60, NOW),
+        "45 minutes ago"
+    );
+    assert_eq!(format_relative_time_at(NOW - 2 * HOUR, NOW), "2 hours ago");
+    assert_eq!(format_relative_time_at(NOW - 30 * HOUR, NOW), "yesterday");
+    assert_eq!(format_relative_time_at(NOW - 3 * DAY, NOW), "3 days ago");
+}
+
+#[test]
+fn future_timestamps_read_as_just_now() {
+    assert_eq!(format_relative_time_at(NOW + 100, NOW), "just now");
+}
+
+#[test]
+fn older_times_fall_back_to_the_date() {
+    assert_eq!(format_relative_time_at(NOW - 30 * DAY, NOW), "2023-10-15");
+}
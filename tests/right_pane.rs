@@ -0,0 +1,23 @@
+use radio_cli::ui::RightPane;
+
+#[test]
+fn right_pane_cycles_through_every_layout() {
+    let start = RightPane::default();
+    assert_eq!(start, RightPane::Stats);
+    assert_eq!(start.next(), RightPane::Split);
+    assert_eq!(start.next().next(), RightPane::Visualization);
+    assert_eq!(start.next().next().next(), start);
+}
+
+#[test]
+fn right_pane_setting_round_trips() {
+    for pane in [RightPane::Visualization, RightPane::Stats, RightPane::Split] {
+        assert_eq!(RightPane::from_setting(pane.label()), Some(pane));
+    }
+    assert_eq!(RightPane::from_setting(" Both "), Some(RightPane::Split));
+    assert_eq!(
+        RightPane::from_setting("vis"),
+        Some(RightPane::Visualization)
+    );
+    assert_eq!(RightPane::from_setting("sideways"), None);
+}
@@ -0,0 +1,44 @@
+use radio_cli::app::search_history::SEARCH_HISTORY_LIMIT;
+use radio_cli::app::SearchHistory;
+
+#[test]
+fn queries_are_recalled_newest_first() {
+    let mut history = SearchHistory::default();
+    for query in ["jazz", "soma", "bbc"] {
+        history.record(query);
+    }
+
+    assert_eq!(history.older(), Some("bbc"));
+    assert_eq!(history.older(), Some("soma"));
+    assert_eq!(history.older(), Some("jazz"));
+    // The oldest one stays put
+    assert_eq!(history.older(), Some("jazz"));
+    assert_eq!(history.newer(), Some("soma"));
+    assert_eq!(history.newer(), Some("bbc"));
+    // Past the newest, the query is empty again
+    assert_eq!(history.newer(), None);
+    assert!(!history.is_browsing());
+}
+
+#[test]
+fn repeats_move_to_the_front_and_the_list_is_capped() {
+    let mut history = SearchHistory::default();
+    history.record("jazz");
+    history.record("soma");
+    history.record(" jazz ");
+    history.record("   ");
+    assert_eq!(history.latest(), Some("jazz"));
+    assert_eq!(history.older(), Some("jazz"));
+    assert_eq!(history.older(), Some("soma"));
+    assert_eq!(history.older(), Some("soma"));
+
+    for i in 0..SEARCH_HISTORY_LIMIT + 5 {
+        history.record(&format!("query {}", i));
+    }
+    history.reset();
+    let oldest = (0..SEARCH_HISTORY_LIMIT)
+        .filter_map(|_| history.older().map(str::to_string))
+        .last();
+    assert_eq!(oldest.as_deref(), Some("query 5"));
+    assert_eq!(history.older(), Some("query 5"));
+}
@@ -0,0 +1,82 @@
+use radio_cli::db::{init_db_seeded, load_stations, SeedStation};
+use radio_cli::seed::{bundled_stations, parse_starter_pack, parse_station_spec};
+use rusqlite::Connection;
+
+#[test]
+fn starter_packs_accept_exports_and_bare_lists() {
+    let export = r#"{"version": 1, "stations": [
+        {"name": "Drone Zone", "url": "https://ice.example/drone", "description": "Ambient"}
+    ]}"#;
+    let stations = parse_starter_pack(export).unwrap();
+    assert_eq!(
+        stations,
+        vec![SeedStation {
+            name: "Drone Zone".to_string(),
+            url: "https://ice.example/drone".to_string(),
+            description: Some("Ambient".to_string()),
+        }]
+    );
+
+    let list = r#"[
+        {"name": "Jazz", "url": "http://jazz.example/live"},
+        {"name": "", "url": "http://nameless.example/live"},
+        {"name": "Broken", "url": "not a url"}
+    ]"#;
+    let stations = parse_starter_pack(list).unwrap();
+    assert_eq!(stations.len(), 1);
+    assert_eq!(stations[0].name, "Jazz");
+    assert_eq!(stations[0].description, None);
+}
+
+#[test]
+fn unusable_starter_packs_are_errors() {
+    assert!(parse_starter_pack("<html></html>").is_err());
+    assert!(parse_starter_pack(r#"{"stations": "none"}"#).is_err());
+    assert!(parse_starter_pack("[]").is_err());
+}
+
+#[test]
+fn a_starter_pack_replaces_the_built_in_stations() {
+    let conn = Connection::open_in_memory().unwrap();
+    let starter = vec![SeedStation {
+        name: "Jazz".to_string(),
+        url: "http://jazz.example/live".to_string(),
+        description: None,
+    }];
+    init_db_seeded(&conn, &starter).unwrap();
+    let stations = load_stations(&conn).unwrap();
+    assert_eq!(stations.len(), 1);
+    assert_eq!(stations[0].name, "Jazz");
+
+    // Without one, a new library gets the built-in list
+    let conn = Connection::open_in_memory().unwrap();
+    init_db_seeded(&conn, &[]).unwrap();
+    assert_eq!(load_stations(&conn).unwrap().len(), 4);
+}
+
+#[test]
+fn the_bundled_station_list_is_a_usable_starter_pack() {
+    let stations = parse_starter_pack(include_str!("../assets/bundled_stations.json")).unwrap();
+    assert_eq!(stations.len(), 15);
+
+    // Only builds with the feature seed from it
+    assert_eq!(
+        bundled_stations().is_empty(),
+        cfg!(not(feature = "bundled_stations"))
+    );
+}
+
+#[test]
+fn stations_are_added_from_pipe_separated_specs() {
+    let station = parse_station_spec("Jazz FM | http://jazz.example/live | Smooth | cool").unwrap();
+    assert_eq!(station.name, "Jazz FM");
+    assert_eq!(station.url, "http://jazz.example/live");
+    assert_eq!(station.description.as_deref(), Some("Smooth | cool"));
+
+    let station = parse_station_spec("Jazz FM|http://jazz.example/live|").unwrap();
+    assert_eq!(station.description, None);
+
+    assert!(parse_station_spec("Jazz FM").is_err());
+    assert!(parse_station_spec("|http://jazz.example/live").is_err());
+    assert!(parse_station_spec("Jazz FM|jazz.example").is_err());
+}
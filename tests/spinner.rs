@@ -0,0 +1,10 @@
+use radio_cli::ui::Spinner;
+
+#[test]
+fn spinner_turns_as_the_draw_loop_ticks() {
+    assert_eq!(Spinner::new(0).frame(), Spinner::new(5).frame());
+    assert_ne!(Spinner::new(0).frame(), Spinner::new(6).frame());
+    // And comes back around
+    assert_eq!(Spinner::new(0).frame(), Spinner::new(60).frame());
+    assert_eq!(Spinner::new(0).label("Loading…"), "⠋ Loading…");
+}
@@ -0,0 +1,103 @@
+use radio_cli::audio::parse_status_line;
+
+// Build a status line the way mpv prints our template
+fn line(song: &str, format: &str, bitrate: &str) -> String {
+    format!("STATUS:\x1e{}\x1e{}\x1e{}", song, format, bitrate)
+}
+
+fn parsed(song: Option<&str>, format: &str, bitrate: &str) -> (Option<String>, String, String) {
+    (
+        song.map(str::to_string),
+        format.to_string(),
+        bitrate.to_string(),
+    )
+}
+
+#[test]
+fn parses_a_normal_status_line() {
+    assert_eq!(
+        parse_status_line(&line("Boards of Canada - Roygbiv", "mp3", "128")),
+        parsed(Some("Boards of Canada - Roygbiv"), "mp3", "128 kbps")
+    );
+}
+
+#[test]
+fn empty_title_gives_no_song() {
+    assert_eq!(
+        parse_status_line(&line("", "aac", "64")),
+        parsed(None, "aac", "64 kbps")
+    );
+}
+
+#[test]
+fn title_containing_old_markers_is_kept_whole() {
+    assert_eq!(
+        parse_status_line(&line("FORMAT: BITRATE: The Remix", "mp3", "192")),
+        parsed(Some("FORMAT: BITRATE: The Remix"), "mp3", "192 kbps")
+    );
+    assert_eq!(
+        parse_status_line(&line("BITRATE: 9000 FORMAT: flac", "opus", "96")),
+        parsed(Some("BITRATE: 9000 FORMAT: flac"), "opus", "96 kbps")
+    );
+}
+
+#[test]
+fn title_containing_status_marker() {
+    assert_eq!(
+        parse_status_line(&line("STATUS: Quo - Live", "mp3", "128")),
+        parsed(Some("STATUS: Quo - Live"), "mp3", "128 kbps")
+    );
+}
+
+#[test]
+fn title_containing_separator_keeps_codec_and_bitrate() {
+    assert_eq!(
+        parse_status_line(&line("Odd\x1eTitle", "mp3", "128")),
+        parsed(Some("Odd Title"), "mp3", "128 kbps")
+    );
+}
+
+#[test]
+fn unavailable_properties_become_unknown() {
+    assert_eq!(
+        parse_status_line(&line("Song", "(unavailable)", "(unavailable)")),
+        parsed(Some("Song"), "Unknown", "Unknown")
+    );
+}
+
+#[test]
+fn bitrate_with_unit_is_not_doubled() {
+    assert_eq!(
+        parse_status_line(&line("Song", "mp3", "128 kbps")).2,
+        "128 kbps"
+    );
+}
+
+#[test]
+fn truncated_line_does_not_panic() {
+    assert_eq!(
+        parse_status_line("STATUS:\x1eHalf a title"),
+        parsed(Some("Half a title"), "Unknown", "Unknown")
+    );
+    assert_eq!(
+        parse_status_line("STATUS:"),
+        parsed(None, "Unknown", "Unknown")
+    );
+}
+
+#[test]
+fn control_characters_and_terminal_noise_are_stripped() {
+    let noisy = format!("\r\x1b[K{}\r", line("Tab\there\r", "mp3", "128"));
+    assert_eq!(
+        parse_status_line(&noisy),
+        parsed(Some("Tab here"), "mp3", "128 kbps")
+    );
+}
+
+#[test]
+fn multibyte_titles_are_handled() {
+    assert_eq!(
+        parse_status_line(&line("Sigur Rós – Hoppípolla 🎵", "vorbis", "160")),
+        parsed(Some("Sigur Rós – Hoppípolla 🎵"), "vorbis", "160 kbps")
+    );
+}
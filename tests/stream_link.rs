@@ -0,0 +1,29 @@
+use radio_cli::stream_info::find_stream_link;
+
+#[test]
+fn audio_tags_and_stream_links_are_found() {
+    let page = r#"<html><head><script src="/app.js"></script></head>
+        <body><img src='logo.png'><audio SRC="/live/radio.mp3"></audio></body></html>"#;
+    assert_eq!(
+        find_stream_link(page, "https://radio.example/listen/").as_deref(),
+        Some("https://radio.example/live/radio.mp3")
+    );
+
+    let page = r#"<a href="/about">About</a> <a href="http://ice.example:8000/;">Listen</a>"#;
+    assert_eq!(
+        find_stream_link(page, "https://radio.example/").as_deref(),
+        Some("http://ice.example:8000/;")
+    );
+
+    let page = r#"<a href="listen.pls">Tune in</a>"#;
+    assert_eq!(
+        find_stream_link(page, "https://radio.example/player/index.html").as_deref(),
+        Some("https://radio.example/player/listen.pls")
+    );
+}
+
+#[test]
+fn pages_without_streams_give_nothing() {
+    let page = r#"<a href="/schedule">Schedule</a><img src="cover.jpg"><a href=mailto:x@y.z>"#;
+    assert_eq!(find_stream_link(page, "https://radio.example/"), None);
+}
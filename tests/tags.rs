@@ -0,0 +1,81 @@
+use radio_cli::app::{complete_tag, is_existing_tag, suggest_tags};
+use radio_cli::db;
+use rusqlite::Connection;
+
+fn tags(list: &[&str]) -> Vec<String> {
+    list.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn tag_lists_are_trimmed_and_deduplicated() {
+    assert_eq!(
+        db::parse_tags(" jazz, Ambient ,,JAZZ, lofi "),
+        tags(&["jazz", "Ambient", "lofi"])
+    );
+    assert!(db::parse_tags(" , ").is_empty());
+}
+
+#[test]
+fn suggestions_complete_the_last_tag() {
+    let all = tags(&["ambient", "electronic", "jazz", "Jazz Fusion"]);
+    assert_eq!(suggest_tags(&all, "ja"), tags(&["jazz", "Jazz Fusion"]));
+    assert_eq!(suggest_tags(&all, "jazz, J"), tags(&["Jazz Fusion"]));
+    assert_eq!(suggest_tags(&all, "Jazz"), tags(&["jazz", "Jazz Fusion"]));
+    assert!(suggest_tags(&all, "jazz").iter().all(|tag| tag != "jazz"));
+    assert!(suggest_tags(&all, "jazz, ").is_empty());
+
+    assert_eq!(complete_tag("ja", "jazz"), "jazz, ");
+    assert_eq!(
+        complete_tag("ambient,  el", "electronic"),
+        "ambient, electronic, "
+    );
+}
+
+#[test]
+fn typed_tags_matching_an_existing_one_are_finished() {
+    let all = tags(&["ambient", "jazz", "Jazz Fusion"]);
+    assert!(is_existing_tag(&all, "JAZZ"));
+    assert!(is_existing_tag(&all, "ambient, jazz "));
+    assert!(!is_existing_tag(&all, "ja"));
+    assert!(!is_existing_tag(&all, "jazz, "));
+}
+
+#[test]
+fn station_tags_are_stored_per_station() {
+    let conn = Connection::open_in_memory().unwrap();
+    db::init_db(&conn).unwrap();
+    let a = db::add_station(&conn, "A", "http://a.example/live", None).unwrap();
+    let b = db::add_station(&conn, "B", "http://b.example/live", None).unwrap();
+
+    db::set_station_tags(&conn, a, &tags(&["jazz", "Ambient"])).unwrap();
+    db::set_station_tags(&conn, b, &tags(&["jazz"])).unwrap();
+    assert_eq!(
+        db::get_station_tags(&conn, a).unwrap(),
+        tags(&["Ambient", "jazz"])
+    );
+    assert_eq!(db::get_all_tags(&conn).unwrap(), tags(&["Ambient", "jazz"]));
+
+    db::delete_station(&conn, a).unwrap();
+    assert_eq!(db::get_all_tags(&conn).unwrap(), tags(&["jazz"]));
+}
+
+#[test]
+fn tags_are_counted_and_browsable() {
+    let conn = Connection::open_in_memory().unwrap();
+    db::init_db(&conn).unwrap();
+    let a = db::add_station(&conn, "Zed FM", "http://a.example/live", None).unwrap();
+    let b = db::add_station(&conn, "Alpha FM", "http://b.example/live", None).unwrap();
+    db::set_station_tags(&conn, a, &tags(&["jazz", "talk"])).unwrap();
+    db::set_station_tags(&conn, b, &tags(&["jazz"])).unwrap();
+
+    assert_eq!(
+        db::get_tag_counts(&conn).unwrap(),
+        vec![("jazz".to_string(), 2), ("talk".to_string(), 1)]
+    );
+    let names: Vec<String> = db::get_stations_with_tag(&conn, "jazz")
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["Alpha FM", "Zed FM"]);
+}
@@ -0,0 +1,17 @@
+use radio_cli::app::{insert_char, remove_char_before};
+
+#[test]
+fn cursors_count_characters_not_bytes() {
+    let mut path = String::from("~/Musik/é.m3u");
+    // Before the "." that follows "é"
+    insert_char(&mut path, 9, 'x');
+    assert_eq!(path, "~/Musik/éx.m3u");
+
+    remove_char_before(&mut path, 9);
+    assert_eq!(path, "~/Musik/x.m3u");
+
+    insert_char(&mut path, 13, 'u');
+    assert_eq!(path, "~/Musik/x.m3uu");
+    remove_char_before(&mut path, 0);
+    assert_eq!(path, "~/Musik/x.m3uu");
+}
@@ -0,0 +1,61 @@
+use radio_cli::ui::Theme;
+use radio_cli::visualizations::ColorMode;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+#[test]
+fn themes_are_chosen_by_name_or_base16_shell() {
+    assert_eq!(Theme::from_name("Terminal"), Some(Theme::Terminal));
+    assert_eq!(Theme::from_name("base16"), Some(Theme::Base16));
+    assert_eq!(Theme::from_name("solarized"), None);
+
+    assert_eq!(
+        Theme::from_base16_env(Some("base16-eighties")),
+        Theme::Base16
+    );
+    assert_eq!(Theme::from_base16_env(Some("")), Theme::Default);
+    assert_eq!(Theme::from_base16_env(None), Theme::Default);
+}
+
+#[test]
+fn terminal_theme_uses_the_terminals_own_background_and_text() {
+    let theme = Theme::Terminal;
+    assert_eq!(
+        theme.cell_colors(Color::White, Color::Black),
+        (Color::Reset, Color::Reset)
+    );
+    // Colored highlights keep their contrast
+    assert_eq!(
+        theme.cell_colors(Color::Black, Color::Yellow),
+        (Color::Black, Color::Yellow)
+    );
+    assert_eq!(
+        Theme::Base16.cell_colors(Color::Yellow, Color::Black),
+        (Color::Yellow, Color::Indexed(18))
+    );
+}
+
+#[test]
+fn only_themed_modes_recolor() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 1));
+    buffer
+        .get_mut(0, 0)
+        .set_fg(Color::White)
+        .set_bg(Color::DarkGray);
+    let original = buffer.clone();
+
+    Theme::Default.apply(&mut buffer);
+    assert_eq!(buffer, original);
+
+    Theme::Terminal.apply(&mut buffer);
+    assert_eq!(buffer.get(0, 0).fg, Color::Reset);
+    assert_eq!(buffer.get(0, 0).bg, Color::Reset);
+
+    assert_eq!(
+        Theme::Terminal.color_mode(ColorMode::TrueColor),
+        ColorMode::Ansi16
+    );
+    assert_eq!(
+        Theme::Default.color_mode(ColorMode::TrueColor),
+        ColorMode::TrueColor
+    );
+}
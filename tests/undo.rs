@@ -0,0 +1,178 @@
+mod common;
+
+use common::test_db;
+use radio_cli::db;
+use radio_cli::undo::{StationCommand, StationSnapshot, UndoStack, UNDO_CAPACITY};
+use rusqlite::Connection;
+
+fn snapshot(conn: &Connection, id: i32) -> StationSnapshot {
+    StationSnapshot::take(conn, db::get_station_by_id(conn, id).unwrap().unwrap()).unwrap()
+}
+
+#[test]
+fn undo_add_removes_the_station_and_redo_restores_it() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    let id = db::add_station(&conn, "New", "http://new.example/stream", None).unwrap();
+    stack.record(StationCommand::Add(Box::new(snapshot(&conn, id))));
+
+    assert!(stack.undo(&conn).unwrap().is_some());
+    assert!(db::get_station_by_id(&conn, id).unwrap().is_none());
+
+    assert!(stack.redo(&conn).unwrap().is_some());
+    assert_eq!(
+        db::get_station_by_id(&conn, id).unwrap().unwrap().name,
+        "New"
+    );
+}
+
+#[test]
+fn undo_edit_restores_previous_fields() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    let id = db::add_station(&conn, "Old", "http://old.example", Some("desc")).unwrap();
+    let before = snapshot(&conn, id);
+    db::update_station(
+        &conn,
+        id,
+        "Renamed",
+        "http://new.example",
+        None,
+        Some("--no-video"),
+    )
+    .unwrap();
+    let after = snapshot(&conn, id);
+    stack.record(StationCommand::Edit {
+        before: Box::new(before),
+        after: Box::new(after),
+    });
+
+    stack.undo(&conn).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert_eq!(station.name, "Old");
+    assert_eq!(station.url, "http://old.example");
+    assert_eq!(station.description.as_deref(), Some("desc"));
+    assert_eq!(station.mpv_args, None);
+
+    stack.redo(&conn).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert_eq!(station.name, "Renamed");
+    assert_eq!(station.mpv_args.as_deref(), Some("--no-video"));
+}
+
+#[test]
+fn undo_add_and_redo_keep_the_tags() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    let id = db::add_station(&conn, "Tagged", "http://tagged.example", None).unwrap();
+    db::set_station_tags(&conn, id, &["jazz".to_string()]).unwrap();
+    stack.record(StationCommand::Add(Box::new(snapshot(&conn, id))));
+
+    stack.undo(&conn).unwrap();
+    assert!(db::get_station_tags(&conn, id).unwrap().is_empty());
+
+    stack.redo(&conn).unwrap();
+    assert_eq!(db::get_station_tags(&conn, id).unwrap(), vec!["jazz"]);
+}
+
+#[test]
+fn undo_edit_restores_tags_mirrors_and_login() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    let id = db::add_station(&conn, "Radio", "http://radio.example", None).unwrap();
+    let old_mirrors = vec!["http://old-mirror.example".to_string()];
+    db::set_station_tags(&conn, id, &["jazz".to_string()]).unwrap();
+    db::set_station_mirrors(&conn, id, &old_mirrors).unwrap();
+    let before = snapshot(&conn, id);
+
+    let new_mirrors = vec!["http://new-mirror.example".to_string()];
+    let login = db::Credentials {
+        username: "listener".to_string(),
+        password: "s3cret".to_string(),
+    };
+    db::set_station_tags(&conn, id, &["rock".to_string(), "live".to_string()]).unwrap();
+    db::set_station_mirrors(&conn, id, &new_mirrors).unwrap();
+    db::set_station_credentials(&conn, id, Some(&login)).unwrap();
+    let after = snapshot(&conn, id);
+    stack.record(StationCommand::Edit {
+        before: Box::new(before),
+        after: Box::new(after),
+    });
+
+    stack.undo(&conn).unwrap();
+    assert_eq!(db::get_station_tags(&conn, id).unwrap(), vec!["jazz"]);
+    assert_eq!(db::get_station_mirrors(&conn, id).unwrap(), old_mirrors);
+    assert!(db::get_station_credentials(&conn, id).unwrap().is_none());
+
+    stack.redo(&conn).unwrap();
+    let mut tags = db::get_station_tags(&conn, id).unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["live", "rock"]);
+    assert_eq!(db::get_station_mirrors(&conn, id).unwrap(), new_mirrors);
+    assert!(db::get_station_credentials(&conn, id).unwrap() == Some(login));
+}
+
+#[test]
+fn undo_delete_restores_station_id_favorite_stats_tags_and_mirrors() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    let id = db::add_station(&conn, "Gone", "http://gone.example", None).unwrap();
+    db::toggle_favorite(&conn, id, true).unwrap();
+    db::update_station_stats(&conn, id, 120).unwrap();
+    db::set_station_tags(&conn, id, &["jazz".to_string()]).unwrap();
+    let mirrors = vec!["http://backup.example".to_string()];
+    db::set_station_mirrors(&conn, id, &mirrors).unwrap();
+    let login = db::Credentials {
+        username: "listener".to_string(),
+        password: "s3cret".to_string(),
+    };
+    db::set_station_credentials(&conn, id, Some(&login)).unwrap();
+
+    let command = StationCommand::delete(
+        &conn,
+        vec![db::get_station_by_id(&conn, id).unwrap().unwrap()],
+    )
+    .unwrap();
+    db::delete_station(&conn, id).unwrap();
+    assert!(db::get_station_stats(&conn, id).unwrap().is_none());
+    stack.record(command);
+
+    stack.undo(&conn).unwrap();
+    let station = db::get_station_by_id(&conn, id).unwrap().unwrap();
+    assert!(station.favorite);
+    let stats = db::get_station_stats(&conn, id).unwrap().unwrap();
+    assert_eq!(stats.total_play_time, 120);
+    assert_eq!(db::get_station_tags(&conn, id).unwrap(), vec!["jazz"]);
+    assert_eq!(db::get_station_mirrors(&conn, id).unwrap(), mirrors);
+    assert!(db::get_station_credentials(&conn, id).unwrap() == Some(login));
+
+    stack.redo(&conn).unwrap();
+    assert!(db::get_station_by_id(&conn, id).unwrap().is_none());
+}
+
+#[test]
+fn history_is_capped_and_new_changes_clear_redo() {
+    let conn = test_db();
+    let mut stack = UndoStack::new();
+
+    for i in 0..UNDO_CAPACITY + 5 {
+        let url = format!("http://station{}.example", i);
+        let id = db::add_station(&conn, "Station", &url, None).unwrap();
+        stack.record(StationCommand::Add(Box::new(snapshot(&conn, id))));
+    }
+
+    let mut undone = 0;
+    while stack.undo(&conn).unwrap().is_some() {
+        undone += 1;
+    }
+    assert_eq!(undone, UNDO_CAPACITY);
+
+    let id = db::add_station(&conn, "Fresh", "http://fresh.example", None).unwrap();
+    stack.record(StationCommand::Add(Box::new(snapshot(&conn, id))));
+    assert!(stack.redo(&conn).unwrap().is_none());
+}
@@ -0,0 +1,18 @@
+use radio_cli::stream_info::{is_valid_stream_url, split_url_list};
+
+#[test]
+fn only_http_urls_with_a_host_are_streams() {
+    assert!(is_valid_stream_url("http://example.com/stream"));
+    assert!(is_valid_stream_url(" https://radio.example:8000/live.mp3 "));
+    assert!(!is_valid_stream_url("ftp://example.com/stream"));
+    assert!(!is_valid_stream_url("example.com/stream"));
+    assert!(!is_valid_stream_url("Groove Salad"));
+}
+
+#[test]
+fn pasted_text_is_split_per_line() {
+    let text = "https://a.example/one\n\n  http://b.example/two  \nnot a url\r\n";
+    let (urls, invalid) = split_url_list(text);
+    assert_eq!(urls, vec!["https://a.example/one", "http://b.example/two"]);
+    assert_eq!(invalid, vec!["not a url"]);
+}
@@ -0,0 +1,150 @@
+use radio_cli::audio::AudioState;
+use radio_cli::visualizations::export;
+use radio_cli::visualizations::{ColorMode, VisualizationType};
+
+#[test]
+fn same_seed_renders_the_same_frames() {
+    let a = export::render_frames(
+        VisualizationType::Starfield,
+        5,
+        42,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+    let b = export::render_frames(
+        VisualizationType::Starfield,
+        5,
+        42,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+    let c = export::render_frames(
+        VisualizationType::Starfield,
+        5,
+        7,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+
+    assert_eq!(a.len(), 5);
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn asciicast_has_a_header_and_one_event_per_frame() {
+    let frames = export::render_frames(
+        VisualizationType::BarSpectrum,
+        3,
+        1,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+    let cast = export::asciicast(&frames);
+    let lines: Vec<&str> = cast.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    let header: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["width"], 40);
+    assert_eq!(header["height"], 12);
+
+    let event: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(event[1], "o");
+    assert!(event[2].as_str().unwrap().contains("Bar Spectrum"));
+}
+
+#[test]
+fn visualizations_are_found_by_loose_name() {
+    assert_eq!(
+        VisualizationType::from_name("bar-spectrum"),
+        Some(VisualizationType::BarSpectrum)
+    );
+    assert_eq!(
+        VisualizationType::from_name("WaveForms"),
+        Some(VisualizationType::WaveForms)
+    );
+    assert_eq!(
+        VisualizationType::from_name("radial spectrum"),
+        Some(VisualizationType::RadialSpectrum)
+    );
+    assert_eq!(
+        VisualizationType::from_name("PLASMA"),
+        Some(VisualizationType::Plasma)
+    );
+    assert_eq!(
+        VisualizationType::from_name("text-scroller"),
+        Some(VisualizationType::TextScroller)
+    );
+    assert_eq!(VisualizationType::from_name("lava lamp"), None);
+}
+
+#[test]
+fn radial_spectrum_animates() {
+    let frames = export::render_frames(
+        VisualizationType::RadialSpectrum,
+        10,
+        3,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+
+    assert_ne!(frames[0], frames[9]);
+}
+
+#[test]
+fn text_scroller_moves_across_the_canvas() {
+    let frames = export::render_frames(
+        VisualizationType::TextScroller,
+        60,
+        3,
+        40,
+        12,
+        ColorMode::TrueColor,
+    )
+    .unwrap();
+
+    assert_ne!(frames[20], frames[59]);
+}
+
+#[test]
+fn frozen_visualization_holds_its_frame() {
+    let mut state = AudioState::new();
+    state.is_playing = true;
+    state.update_visualization();
+    let stars: Vec<f64> = state.stars.iter().map(|star| star.z).collect();
+
+    state.vis_frozen = true;
+    state.update_visualization();
+    assert_eq!(state.frame_count, 1);
+    assert_eq!(
+        state.stars.iter().map(|star| star.z).collect::<Vec<_>>(),
+        stars
+    );
+
+    state.vis_frozen = false;
+    state.update_visualization();
+    assert_eq!(state.frame_count, 2);
+}
+
+#[test]
+fn star_count_is_kept_in_range() {
+    let mut state = AudioState::new();
+    assert_eq!(state.stars.len(), 200);
+
+    assert_eq!(state.set_star_count(300), 300);
+    assert_eq!(state.stars.len(), 300);
+    assert_eq!(state.set_star_count(5), 50);
+    assert_eq!(state.set_star_count(10_000), 500);
+    assert_eq!(state.stars.len(), 500);
+}